@@ -0,0 +1,52 @@
+#!/usr/bin/env bash
+# Replays the migrations on a scratch database and checks that scripts indexed by shell_script_fts
+# before 20251104091236_add_script_description recreated the index are still found afterwards.
+# Run it after touching the FTS table or its triggers: ./prisma/check_fts_migrations.sh
+set -euo pipefail
+
+cd "$(dirname "$0")/migrations"
+db="$(mktemp)"
+trap 'rm -f "$db"' EXIT
+
+rebuilding_migration="20251104091236_add_script_description"
+
+apply() {
+    sqlite3 -bail "$db" < "$1/migration.sql"
+}
+
+search() {
+    sqlite3 "$db" "SELECT rowid FROM shell_script_fts WHERE shell_script_fts MATCH '$1' ORDER BY rowid;" | tr '\n' ' '
+}
+
+for migration in */; do
+    migration="${migration%/}"
+    [[ "$migration" < "$rebuilding_migration" ]] || break
+    apply "$migration"
+done
+
+sqlite3 -bail "$db" <<'SQL'
+INSERT INTO "shell_script" ("name", "command", "ordering") VALUES
+    ('Prune images', 'docker image prune -f', 0),
+    ('Checkout', 'git checkout main', 1),
+    ('Compose logs', 'docker compose logs -f', 2);
+SQL
+before="$(search docker) | $(search checkout)"
+
+for migration in */; do
+    migration="${migration%/}"
+    [[ "$migration" < "$rebuilding_migration" ]] || apply "$migration"
+done
+
+after="$(search docker) | $(search checkout)"
+if [[ "$before" != "$after" ]]; then
+    echo "Search results changed across $rebuilding_migration: '$before' became '$after'" >&2
+    exit 1
+fi
+
+sqlite3 -bail "$db" "UPDATE \"shell_script\" SET \"description\" = 'Frees disk space' WHERE \"id\" = 1;"
+if [[ "$(search disk)" != "1 " ]]; then
+    echo "Descriptions are not indexed after $rebuilding_migration" >&2
+    exit 1
+fi
+
+echo "shell_script_fts keeps its search results across the migrations ($after)"
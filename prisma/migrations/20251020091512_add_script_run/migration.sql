@@ -0,0 +1,16 @@
+-- CreateTable
+CREATE TABLE "script_run" (
+    "id" INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    "shell_script_id" INTEGER NOT NULL,
+    "shell" TEXT NOT NULL,
+    "command" TEXT NOT NULL,
+    "output" TEXT NOT NULL DEFAULT '',
+    "exit_code" INTEGER,
+    "started_at" REAL NOT NULL DEFAULT (CAST((julianday('now') - 2440587.5) * 86400000.0 AS REAL)),
+    "started_at_hk" TEXT NOT NULL DEFAULT (strftime('%Y-%m-%d %H:%M:%S', datetime('now', '+8 hours'))),
+    "finished_at" REAL,
+    CONSTRAINT "script_run_shell_script_id_fkey" FOREIGN KEY ("shell_script_id") REFERENCES "shell_script" ("id") ON DELETE RESTRICT ON UPDATE CASCADE
+);
+
+-- CreateIndex
+CREATE INDEX "script_run_shell_script_id_idx" ON "script_run"("shell_script_id");
@@ -0,0 +1,16 @@
+-- CreateTable
+CREATE TABLE "script_placeholder_value" (
+    "id" INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    "shell_script_id" INTEGER NOT NULL,
+    "name" TEXT NOT NULL,
+    "value" TEXT NOT NULL,
+    "created_at" REAL NOT NULL DEFAULT (CAST((julianday('now') - 2440587.5) * 86400000.0 AS REAL)),
+    "created_at_hk" TEXT NOT NULL DEFAULT (strftime('%Y-%m-%d %H:%M:%S', datetime('now', '+8 hours'))),
+    CONSTRAINT "script_placeholder_value_shell_script_id_fkey" FOREIGN KEY ("shell_script_id") REFERENCES "shell_script" ("id") ON DELETE RESTRICT ON UPDATE CASCADE
+);
+
+-- CreateIndex
+CREATE INDEX "script_placeholder_value_shell_script_id_idx" ON "script_placeholder_value"("shell_script_id");
+
+-- CreateIndex
+CREATE UNIQUE INDEX "script_placeholder_value_shell_script_id_name_key" ON "script_placeholder_value"("shell_script_id", "name");
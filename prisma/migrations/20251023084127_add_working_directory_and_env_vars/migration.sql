@@ -0,0 +1,17 @@
+-- AlterTable
+ALTER TABLE "shell_script" ADD COLUMN "working_directory" TEXT;
+
+-- CreateTable
+CREATE TABLE "shell_script_env_var" (
+    "id" INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    "shell_script_id" INTEGER NOT NULL,
+    "key" TEXT NOT NULL,
+    "value" TEXT NOT NULL,
+    "ordering" INTEGER NOT NULL,
+    "created_at" REAL NOT NULL DEFAULT (CAST((julianday('now') - 2440587.5) * 86400000.0 AS REAL)),
+    "created_at_hk" TEXT NOT NULL DEFAULT (strftime('%Y-%m-%d %H:%M:%S', datetime('now', '+8 hours'))),
+    CONSTRAINT "shell_script_env_var_shell_script_id_fkey" FOREIGN KEY ("shell_script_id") REFERENCES "shell_script" ("id") ON DELETE RESTRICT ON UPDATE CASCADE
+);
+
+-- CreateIndex
+CREATE INDEX "shell_script_env_var_shell_script_id_idx" ON "shell_script_env_var"("shell_script_id");
@@ -0,0 +1,4 @@
+-- AlterTable
+ALTER TABLE "shell_script" ADD COLUMN "interpreter" TEXT NOT NULL DEFAULT 'login_shell';
+ALTER TABLE "shell_script" ADD COLUMN "shebang" TEXT;
+ALTER TABLE "shell_script" ADD COLUMN "source_rc" BOOLEAN NOT NULL DEFAULT true;
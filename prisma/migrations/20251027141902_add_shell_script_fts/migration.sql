@@ -0,0 +1,28 @@
+-- CreateVirtualTable
+-- Full-text index over shell_script, not representable in schema.prisma.
+-- It is an external content table: only the index lives here, the triggers below keep it in sync.
+CREATE VIRTUAL TABLE "shell_script_fts" USING fts5(
+    "name",
+    "command",
+    content='shell_script',
+    content_rowid='id'
+);
+
+-- CreateTrigger
+CREATE TRIGGER "shell_script_fts_after_insert" AFTER INSERT ON "shell_script" BEGIN
+    INSERT INTO "shell_script_fts"("rowid", "name", "command") VALUES (new."id", new."name", new."command");
+END;
+
+-- CreateTrigger
+CREATE TRIGGER "shell_script_fts_after_delete" AFTER DELETE ON "shell_script" BEGIN
+    INSERT INTO "shell_script_fts"("shell_script_fts", "rowid", "name", "command") VALUES ('delete', old."id", old."name", old."command");
+END;
+
+-- CreateTrigger
+CREATE TRIGGER "shell_script_fts_after_update" AFTER UPDATE OF "name", "command" ON "shell_script" BEGIN
+    INSERT INTO "shell_script_fts"("shell_script_fts", "rowid", "name", "command") VALUES ('delete', old."id", old."name", old."command");
+    INSERT INTO "shell_script_fts"("rowid", "name", "command") VALUES (new."id", new."name", new."command");
+END;
+
+-- Index the scripts that already exist
+INSERT INTO "shell_script_fts"("shell_script_fts") VALUES ('rebuild');
@@ -0,0 +1,5 @@
+-- AlterTable
+ALTER TABLE "scripts_folder" ADD COLUMN "parent_id" INTEGER;
+
+-- CreateIndex
+CREATE INDEX "scripts_folder_parent_id_idx" ON "scripts_folder"("parent_id");
@@ -0,0 +1,3 @@
+-- AlterTable
+ALTER TABLE "shell_script" ADD COLUMN "pinned" BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE "shell_script" ADD COLUMN "pinned_ordering" INTEGER NOT NULL DEFAULT 0;
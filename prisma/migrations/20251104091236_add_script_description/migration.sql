@@ -0,0 +1,39 @@
+-- AlterTable
+ALTER TABLE "shell_script" ADD COLUMN "description" TEXT NOT NULL DEFAULT '';
+
+-- DropTrigger
+DROP TRIGGER "shell_script_fts_after_insert";
+DROP TRIGGER "shell_script_fts_after_delete";
+DROP TRIGGER "shell_script_fts_after_update";
+
+-- DropVirtualTable
+-- FTS5 tables cannot gain columns, the index is recreated with the description
+DROP TABLE "shell_script_fts";
+
+-- CreateVirtualTable
+CREATE VIRTUAL TABLE "shell_script_fts" USING fts5(
+    "name",
+    "command",
+    "description",
+    content='shell_script',
+    content_rowid='id'
+);
+
+-- CreateTrigger
+CREATE TRIGGER "shell_script_fts_after_insert" AFTER INSERT ON "shell_script" BEGIN
+    INSERT INTO "shell_script_fts"("rowid", "name", "command", "description") VALUES (new."id", new."name", new."command", new."description");
+END;
+
+-- CreateTrigger
+CREATE TRIGGER "shell_script_fts_after_delete" AFTER DELETE ON "shell_script" BEGIN
+    INSERT INTO "shell_script_fts"("shell_script_fts", "rowid", "name", "command", "description") VALUES ('delete', old."id", old."name", old."command", old."description");
+END;
+
+-- CreateTrigger
+CREATE TRIGGER "shell_script_fts_after_update" AFTER UPDATE OF "name", "command", "description" ON "shell_script" BEGIN
+    INSERT INTO "shell_script_fts"("shell_script_fts", "rowid", "name", "command", "description") VALUES ('delete', old."id", old."name", old."command", old."description");
+    INSERT INTO "shell_script_fts"("rowid", "name", "command", "description") VALUES (new."id", new."name", new."command", new."description");
+END;
+
+-- Index the scripts that already exist
+INSERT INTO "shell_script_fts"("shell_script_fts") VALUES ('rebuild');
@@ -0,0 +1,8 @@
+-- AlterTable
+ALTER TABLE "application_state" ADD COLUMN "trash_retention_days" INTEGER NOT NULL DEFAULT 30;
+
+-- AlterTable
+ALTER TABLE "scripts_folder" ADD COLUMN "deleted_at" REAL;
+
+-- AlterTable
+ALTER TABLE "shell_script" ADD COLUMN "deleted_at" REAL;
@@ -0,0 +1,8 @@
+-- AlterTable
+ALTER TABLE "rel_scriptsfolder_shellscript" ADD COLUMN "ordering" INTEGER NOT NULL DEFAULT 0;
+
+-- Backfill
+-- Every folder keeps the order it had, which so far came from the script itself.
+UPDATE "rel_scriptsfolder_shellscript" SET "ordering" = (
+    SELECT "ordering" FROM "shell_script" WHERE "shell_script"."id" = "rel_scriptsfolder_shellscript"."shell_script_id"
+);
@@ -1,27 +1,52 @@
 use std::sync::Arc;
 
+use crate::bus::Bus;
+use crate::component::command_palette::CommandPalette;
+use crate::component::history_import_window::HistoryImportWindow;
 use crate::component::left_folders_col::folder_col::FolderColumn;
+use crate::component::library_import_window::LibraryImportWindow;
+use crate::component::output_console::output_console::OutputConsole;
+use crate::component::placeholder_form_window::placeholder_form_window;
 use crate::component::right_scripts_col::scripts_col::ScriptsColumn;
+use crate::component::toast_overlay::toast_overlay;
 use crate::component::top_menu::top_menu;
 use crate::db::get_db::get_db;
+use crate::db::repository::folder_repository::FolderRepository;
 use crate::dispatch_folder_command;
-use crate::domain::folder::folder_command_handler::{FolderCommand, FolderCommandHandler};
-use crate::domain::folder::folder_event_handler::FolderEventHandler;
+use crate::domain::execution::execution_module::ExecutionModule;
+use crate::domain::folder::folder_command_handler::FolderCommand;
+use crate::domain::folder::folder_module::FolderModule;
+use crate::domain::library::library_module::LibraryModule;
+use crate::domain::notification::notification_module::NotificationModule;
+use crate::domain::palette::palette_module::PaletteModule;
 
 pub struct App {
     folder_col: FolderColumn,
     scripts_col: ScriptsColumn,
-    folder_command_handler: FolderCommandHandler,
-    folder_event_handler: FolderEventHandler,
+    output_console: OutputConsole,
+    command_palette: CommandPalette,
+    library_import_window: LibraryImportWindow,
+    history_import_window: HistoryImportWindow,
+    bus: Bus,
 }
 
 impl Default for App {
     fn default() -> Self {
+        let mut bus = Bus::new();
+        bus.register(FolderModule)
+            .register(ExecutionModule)
+            .register(PaletteModule)
+            .register(LibraryModule)
+            .register(NotificationModule);
+
         Self {
             folder_col: FolderColumn::new(),
             scripts_col: ScriptsColumn::new(),
-            folder_command_handler: FolderCommandHandler::new(),
-            folder_event_handler: FolderEventHandler::new(),
+            output_console: OutputConsole::new(),
+            command_palette: CommandPalette::new(),
+            library_import_window: LibraryImportWindow::new(),
+            history_import_window: HistoryImportWindow::new(),
+            bus,
         }
     }
 }
@@ -47,16 +72,20 @@ impl App {
                         };
                     }
                 }
-                Err(e) => eprintln!("Failed to load application state: {:?}", e),
+                Err(e) => crate::report_error("Failed to load application state", &e),
             }
 
-            let folders = db.scripts_folder().find_many(vec![]).exec().await;
+            let folders = FolderRepository::new().get_all_folders().await;
             match folders {
                 Ok(folders) => {
                     crate::with_folder_state_reducer(|reducer| reducer.set_folder_list(folders));
                 }
-                Err(e) => eprintln!("Failed to load folders: {:?}", e),
+                Err(e) => crate::report_error("Failed to load folders", &e),
             }
+            dispatch_folder_command(FolderCommand::LoadTags);
+            dispatch_folder_command(FolderCommand::LoadPinnedScripts);
+            // The trash is loaded once what expired is gone
+            dispatch_folder_command(FolderCommand::PurgeExpiredTrash);
         });
 
         Default::default()
@@ -73,25 +102,18 @@ impl eframe::App for App {
         );
         ctx.set_style(style);
         while let Ok(message) = crate::EVENT_RECEIVER.get().unwrap().try_recv() {
-            match message {
-                crate::AppMessage::Command(cmd) => match cmd {
-                    crate::AppCommand::Folder(wrapped_cmd) => {
-                        self.folder_command_handler.handle(wrapped_cmd);
-                    }
-                },
-                crate::AppMessage::Event(evt) => match evt {
-                    crate::AppEvent::Folder(event) => {
-                        self.folder_event_handler.handle(event);
-                    }
-                },
-                crate::AppMessage::Callback(cb) => {
-                    cb();
-                }
-            }
+            self.bus.deliver(message);
         }
 
         top_menu(ctx);
         self.folder_col.view(ctx);
+        // Bottom panel has to be laid out before the central panel takes the remaining space
+        self.output_console.view(ctx);
         self.scripts_col.view(ctx);
+        placeholder_form_window(ctx);
+        self.library_import_window.view(ctx);
+        self.history_import_window.view(ctx);
+        self.command_palette.view(ctx);
+        toast_overlay(ctx);
     }
 }
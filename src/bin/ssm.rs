@@ -0,0 +1,692 @@
+#![warn(clippy::all, rust_2018_idioms)]
+
+// Command line companion of the app: lists, edits and runs the scripts stored in the same database.
+//
+//   ssm list                         folders
+//   ssm list <folder>                scripts of a folder
+//   ssm show <folder> <script>
+//   ssm add <folder> <script> [-c <command>]      the command is read from stdin without -c
+//   ssm update <folder> <script> [-c <command> | --stdin] [--rename <name>]
+//   ssm run <folder> <script> [-p name=value]...
+//
+// <folder> is a folder name, or the `parent/child` path of a nested folder whose name is not unique
+
+use clap::{Args, Parser, Subcommand};
+use shell_script_manager::db::repository::folder_repository::FolderRepository;
+use shell_script_manager::db::repository::script_repository::ScriptRepository;
+use shell_script_manager::db::repository::script_run_repository::ScriptRunRepository;
+use shell_script_manager::domain::execution::interpreter::{Interpreter, InterpreterSettings};
+use shell_script_manager::domain::execution::launcher::ScriptLauncher;
+use shell_script_manager::domain::execution::placeholder::{
+    parse_placeholders, prefill_values, render_command,
+};
+use shell_script_manager::domain::execution::process_registry;
+use shell_script_manager::domain::execution::script_execution::ScriptExecution;
+use shell_script_manager::prisma::{scripts_folder, shell_script};
+use std::collections::{HashMap, VecDeque};
+use std::io::Read;
+use std::process::ExitCode;
+use std::sync::Mutex;
+
+#[derive(Parser)]
+#[command(
+    name = "ssm",
+    version,
+    about = "Command line companion of Shell Script Manager"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: CliCommand,
+}
+
+#[derive(Subcommand)]
+enum CliCommand {
+    #[command(about = "List all folders, or the scripts of one folder")]
+    List { folder: Option<String> },
+    #[command(about = "Print a script together with its settings")]
+    Show { folder: String, script: String },
+    #[command(
+        about = "Add a script to a folder, the command is read from stdin unless -c is given"
+    )]
+    Add {
+        folder: String,
+        script: String,
+        #[arg(short, long)]
+        command: Option<String>,
+        #[command(flatten)]
+        options: ScriptOptions,
+    },
+    #[command(about = "Update a script, only the given fields are changed")]
+    Update {
+        folder: String,
+        script: String,
+        #[arg(short, long, conflicts_with = "stdin")]
+        command: Option<String>,
+        #[arg(long, help = "Read the new command from stdin")]
+        stdin: bool,
+        #[arg(long)]
+        rename: Option<String>,
+        #[command(flatten)]
+        options: ScriptOptions,
+    },
+    #[command(about = "Run a script, streaming its output and exiting with its exit code")]
+    Run {
+        folder: String,
+        script: String,
+        #[arg(
+            short = 'p',
+            long = "param",
+            value_parser = parse_key_value,
+            help = "Placeholder value as name=value, the last used value or the default is taken otherwise"
+        )]
+        params: Vec<(String, String)>,
+    },
+}
+
+#[derive(Args)]
+struct ScriptOptions {
+    #[arg(long, value_parser = parse_interpreter)]
+    interpreter: Option<Interpreter>,
+    #[arg(
+        long,
+        help = "Shebang of the custom interpreter, e.g. \"#!/usr/bin/env ruby\""
+    )]
+    shebang: Option<String>,
+    #[arg(long, help = "Source the shell rc files before running (true/false)")]
+    source_rc: Option<bool>,
+    #[arg(long, help = "Working directory, an empty string clears it")]
+    cwd: Option<String>,
+    #[arg(
+        short = 'e',
+        long = "env",
+        value_parser = parse_key_value,
+        help = "Environment variable as KEY=VALUE, replaces a variable with the same key"
+    )]
+    env_vars: Vec<(String, String)>,
+    #[arg(long, help = "Remove all environment variables before applying --env")]
+    clear_env: bool,
+}
+
+impl ScriptOptions {
+    fn changes_interpreter(&self) -> bool {
+        self.interpreter.is_some() || self.shebang.is_some() || self.source_rc.is_some()
+    }
+
+    fn changes_environment(&self) -> bool {
+        self.cwd.is_some() || !self.env_vars.is_empty() || self.clear_env
+    }
+
+    fn apply_to_interpreter(&self, settings: &mut InterpreterSettings) {
+        if let Some(interpreter) = self.interpreter {
+            settings.interpreter = interpreter;
+        }
+        if let Some(shebang) = &self.shebang {
+            settings.shebang = (!shebang.trim().is_empty()).then(|| shebang.trim().to_string());
+        }
+        if let Some(source_rc) = self.source_rc {
+            settings.source_rc = source_rc;
+        }
+    }
+
+    fn apply_to_environment(
+        &self,
+        working_directory: &mut Option<String>,
+        env_vars: &mut Vec<(String, String)>,
+    ) {
+        if let Some(cwd) = &self.cwd {
+            *working_directory = (!cwd.trim().is_empty()).then(|| cwd.trim().to_string());
+        }
+        if self.clear_env {
+            env_vars.clear();
+        }
+        for (key, value) in &self.env_vars {
+            match env_vars.iter_mut().find(|(existing, _)| existing == key) {
+                Some(existing) => existing.1 = value.clone(),
+                None => env_vars.push((key.clone(), value.clone())),
+            }
+        }
+    }
+}
+
+fn parse_key_value(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected name=value, got '{}'", value)),
+    }
+}
+
+fn parse_interpreter(value: &str) -> Result<Interpreter, String> {
+    Interpreter::ALL
+        .into_iter()
+        .find(|interpreter| interpreter.as_str() == value)
+        .ok_or_else(|| {
+            let names: Vec<&str> = Interpreter::ALL.iter().map(|i| i.as_str()).collect();
+            format!("expected one of {}", names.join(", "))
+        })
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    if let Err(e) = connect_database().await {
+        eprintln!("ssm: {}", e);
+        return ExitCode::FAILURE;
+    }
+
+    let result = match cli.command {
+        CliCommand::List { folder } => list(folder).await,
+        CliCommand::Show { folder, script } => show(&folder, &script).await,
+        CliCommand::Add {
+            folder,
+            script,
+            command,
+            options,
+        } => add(&folder, script, command, &options).await,
+        CliCommand::Update {
+            folder,
+            script,
+            command,
+            stdin,
+            rename,
+            options,
+        } => update(&folder, &script, command, stdin, rename, &options).await,
+        CliCommand::Run {
+            folder,
+            script,
+            params,
+        } => return run(&folder, &script, params).await,
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("ssm: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn connect_database() -> Result<(), String> {
+    let db_path = shell_script_manager::db::get_db::database_path();
+    let db_url = format!("file:{}", db_path.display());
+    let client = shell_script_manager::prisma::new_client_with_url(&db_url)
+        .await
+        .map_err(|e| format!("failed to open {}: {}", db_path.display(), e))?;
+
+    // Same as `initialize_database`, without printing to stdout which is ours here
+    if let Err(e) = client._migrate_deploy().await {
+        client
+            .scripts_folder()
+            .count(vec![])
+            .exec()
+            .await
+            .map_err(|_| format!("failed to initialize the database: {}", e))?;
+    }
+
+    shell_script_manager::PRISMA_CLIENT
+        .set(client)
+        .map_err(|_| "database client already set".to_string())
+}
+
+// `name` is either a folder name or the path of a nested folder as `parent/child`, a name
+// shared by several folders has to be given as a path
+async fn find_folder(name: &str) -> Result<scripts_folder::Data, String> {
+    let folders = FolderRepository::new()
+        .get_all_folders()
+        .await
+        .map_err(|e| format!("failed to load folders: {:?}", e))?;
+    let paths: Vec<String> = folders.iter().map(|f| folder_path(&folders, f)).collect();
+    let lowercase_name = name.to_lowercase();
+    let matching = |matches: &dyn Fn(&scripts_folder::Data, &str) -> bool| -> Vec<usize> {
+        (0..folders.len())
+            .filter(|&i| matches(&folders[i], &paths[i]))
+            .collect()
+    };
+
+    let mut found = matching(&|_, path| path == name);
+    if found.is_empty() {
+        found = matching(&|f, _| f.name == name);
+    }
+    if found.is_empty() {
+        found = matching(&|_, path| path.to_lowercase() == lowercase_name);
+    }
+    if found.is_empty() {
+        found = matching(&|f, _| f.name.to_lowercase() == lowercase_name);
+    }
+    match found.as_slice() {
+        [index] => Ok(folders[*index].clone()),
+        [] => Err(format!(
+            "no folder named '{}' (available: {})",
+            name,
+            paths.join(", ")
+        )),
+        _ => {
+            let candidates: Vec<&str> = found.iter().map(|&i| paths[i].as_str()).collect();
+            Err(format!(
+                "{} folders are named '{}', give its path instead: {}",
+                found.len(),
+                name,
+                candidates.join(", ")
+            ))
+        }
+    }
+}
+
+// Names of the folder and its parents from the top, joined by `/`
+fn folder_path(folders: &[scripts_folder::Data], folder: &scripts_folder::Data) -> String {
+    let mut names = vec![folder.name.as_str()];
+    let mut parent_id = folder.parent_id;
+    // Bounded by the folder count in case the parents ever form a cycle
+    while let Some(id) = parent_id
+        && names.len() <= folders.len()
+    {
+        let Some(parent) = folders.iter().find(|f| f.id == id) else {
+            break;
+        };
+        names.push(parent.name.as_str());
+        parent_id = parent.parent_id;
+    }
+    names.reverse();
+    names.join("/")
+}
+
+async fn find_script(
+    folder: &scripts_folder::Data,
+    name: &str,
+) -> Result<shell_script::Data, String> {
+    let scripts = scripts_of_folder(folder.id).await?;
+    let matches: Vec<&shell_script::Data> = {
+        let exact: Vec<_> = scripts.iter().filter(|s| s.name == name).collect();
+        if exact.is_empty() {
+            scripts
+                .iter()
+                .filter(|s| s.name.to_lowercase() == name.to_lowercase())
+                .collect()
+        } else {
+            exact
+        }
+    };
+    match matches.as_slice() {
+        [script] => Ok((*script).clone()),
+        [] => Err(format!(
+            "no script named '{}' in folder '{}'",
+            name, folder.name
+        )),
+        _ => Err(format!(
+            "{} scripts are named '{}' in folder '{}', rename one of them first",
+            matches.len(),
+            name,
+            folder.name
+        )),
+    }
+}
+
+async fn scripts_of_folder(folder_id: i32) -> Result<Vec<shell_script::Data>, String> {
+    ScriptRepository::new()
+        .get_scripts_by_folder(folder_id)
+        .await
+        .map_err(|e| format!("failed to load scripts: {:?}", e))
+}
+
+async fn list(folder: Option<String>) -> Result<(), String> {
+    match folder {
+        None => {
+            let folders = FolderRepository::new()
+                .get_all_folders()
+                .await
+                .map_err(|e| format!("failed to load folders: {:?}", e))?;
+            for folder in folders.iter() {
+                let count = scripts_of_folder(folder.id).await?.len();
+                println!("{}  ({} scripts)", folder_path(&folders, folder), count);
+            }
+        }
+        Some(folder) => {
+            let folder = find_folder(&folder).await?;
+            for script in scripts_of_folder(folder.id).await? {
+                let first_line = script.command.lines().next().unwrap_or_default();
+                println!("{}\t{}", script.name, first_line);
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn show(folder: &str, script: &str) -> Result<(), String> {
+    let folder = find_folder(folder).await?;
+    let script = find_script(&folder, script).await?;
+    let execution = ScriptExecution::from(&script);
+    let settings = &execution.interpreter_settings;
+
+    println!("Name:        {}", script.name);
+    println!("Folder:      {}", folder.name);
+    println!("Created:     {}", script.created_at_hk);
+    match (settings.interpreter, &settings.shebang) {
+        (Interpreter::Custom, Some(shebang)) => println!("Interpreter: {}", shebang),
+        (interpreter, _) => println!("Interpreter: {}", interpreter.label()),
+    }
+    println!("Source rc:   {}", settings.source_rc);
+    if let Some(working_directory) = &execution.working_directory {
+        println!("Directory:   {}", working_directory);
+    }
+    for (key, value) in &execution.env_vars {
+        println!("Env:         {}={}", key, value);
+    }
+    let placeholders = parse_placeholders(&script.command);
+    if !placeholders.is_empty() {
+        let names: Vec<&str> = placeholders.iter().map(|p| p.name.as_str()).collect();
+        println!("Parameters:  {}", names.join(", "));
+    }
+    println!();
+    println!("{}", script.command);
+    Ok(())
+}
+
+fn read_stdin() -> Result<String, String> {
+    let mut command = String::new();
+    std::io::stdin()
+        .read_to_string(&mut command)
+        .map_err(|e| format!("failed to read the command from stdin: {}", e))?;
+    Ok(command.trim_end_matches('\n').to_string())
+}
+
+async fn add(
+    folder: &str,
+    name: String,
+    command: Option<String>,
+    options: &ScriptOptions,
+) -> Result<(), String> {
+    let folder = find_folder(folder).await?;
+    let command = match command {
+        Some(command) => command,
+        None => read_stdin()?,
+    };
+    let mut interpreter_settings = InterpreterSettings::default();
+    options.apply_to_interpreter(&mut interpreter_settings);
+
+    let script_repository = ScriptRepository::new();
+    let script = script_repository
+        .create_script(name.clone(), command, interpreter_settings)
+        .await
+        .map_err(|e| format!("failed to add script: {:?}", e))?;
+    script_repository
+        .create_script_relationship(folder.id, script.id)
+        .await
+        .map_err(|e| format!("failed to add script to folder: {:?}", e))?;
+
+    if options.changes_environment() {
+        let mut working_directory = None;
+        let mut env_vars = vec![];
+        options.apply_to_environment(&mut working_directory, &mut env_vars);
+        script_repository
+            .update_script_environment(script.id, working_directory, env_vars)
+            .await
+            .map_err(|e| format!("failed to save the environment: {:?}", e))?;
+    }
+
+    println!("Added '{}' to '{}'", name, folder.name);
+    Ok(())
+}
+
+async fn update(
+    folder: &str,
+    name: &str,
+    command: Option<String>,
+    stdin: bool,
+    rename: Option<String>,
+    options: &ScriptOptions,
+) -> Result<(), String> {
+    let folder = find_folder(folder).await?;
+    let script = find_script(&folder, name).await?;
+    let script_repository = ScriptRepository::new();
+
+    let command = match (command, stdin) {
+        (Some(command), _) => Some(command),
+        (None, true) => Some(read_stdin()?),
+        (None, false) => None,
+    };
+    if let Some(command) = command {
+        script_repository
+            .update_script_command(script.id, command)
+            .await
+            .map_err(|e| format!("failed to update the command: {:?}", e))?;
+    }
+    if let Some(new_name) = &rename {
+        script_repository
+            .update_script_name(script.id, new_name.clone())
+            .await
+            .map_err(|e| format!("failed to rename the script: {:?}", e))?;
+    }
+
+    let execution = ScriptExecution::from(&script);
+    if options.changes_interpreter() {
+        let mut interpreter_settings = execution.interpreter_settings.clone();
+        options.apply_to_interpreter(&mut interpreter_settings);
+        script_repository
+            .update_script_interpreter(script.id, interpreter_settings)
+            .await
+            .map_err(|e| format!("failed to update the interpreter: {:?}", e))?;
+    }
+    if options.changes_environment() {
+        let mut working_directory = execution.working_directory.clone();
+        let mut env_vars = execution.env_vars.clone();
+        options.apply_to_environment(&mut working_directory, &mut env_vars);
+        script_repository
+            .update_script_environment(script.id, working_directory, env_vars)
+            .await
+            .map_err(|e| format!("failed to update the environment: {:?}", e))?;
+    }
+
+    println!(
+        "Updated '{}' in '{}'",
+        rename.as_deref().unwrap_or(name),
+        folder.name
+    );
+    Ok(())
+}
+
+// Fills in the placeholders the same way the app's prompt prefills them
+async fn render_placeholders(
+    script: &shell_script::Data,
+    params: Vec<(String, String)>,
+) -> Result<String, String> {
+    let placeholders = parse_placeholders(&script.command);
+    for (name, _) in &params {
+        if !placeholders.iter().any(|p| &p.name == name) {
+            eprintln!("ssm: ignoring unknown parameter '{}'", name);
+        }
+    }
+    if placeholders.is_empty() {
+        return Ok(script.command.clone());
+    }
+
+    let script_repository = ScriptRepository::new();
+    let last_values = script_repository
+        .get_placeholder_values(script.id)
+        .await
+        .unwrap_or_default();
+    let prefilled = prefill_values(&placeholders, &last_values);
+    let params: HashMap<String, String> = params.into_iter().collect();
+
+    let mut values = vec![];
+    for (placeholder, prefilled) in placeholders.iter().zip(prefilled) {
+        let value = params.get(&placeholder.name).cloned().unwrap_or(prefilled);
+        if value.is_empty() {
+            return Err(format!(
+                "missing value for {{{{{}}}}}, pass -p {}=<value>",
+                placeholder.name, placeholder.name
+            ));
+        }
+        if !placeholder.choices.is_empty() && !placeholder.choices.contains(&value) {
+            return Err(format!(
+                "'{}' is not a valid value for {{{{{}}}}} (choices: {})",
+                value,
+                placeholder.name,
+                placeholder.choices.join(", ")
+            ));
+        }
+        values.push((placeholder.name.clone(), value));
+    }
+
+    if let Err(e) = script_repository
+        .save_placeholder_values(script.id, values.clone())
+        .await
+    {
+        eprintln!("ssm: failed to remember placeholder values: {:?}", e);
+    }
+    Ok(render_command(
+        &script.command,
+        &values.into_iter().collect(),
+    ))
+}
+
+async fn run(folder: &str, name: &str, params: Vec<(String, String)>) -> ExitCode {
+    let prepared = async {
+        let folder = find_folder(folder).await?;
+        let script = find_script(&folder, name).await?;
+        let command = render_placeholders(&script, params).await?;
+        Ok::<_, String>(ScriptExecution {
+            command,
+            ..ScriptExecution::from(&script)
+        })
+    };
+    let execution = match prepared.await {
+        Ok(execution) => execution,
+        Err(e) => {
+            eprintln!("ssm: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let launcher = ScriptLauncher::prepare(&execution, 0);
+    let script_run_repository = ScriptRunRepository::new();
+    let persisted_run_id = match script_run_repository
+        .create_run(
+            execution.script_id,
+            launcher.interpreter_name(),
+            execution.command.clone(),
+        )
+        .await
+    {
+        Ok(script_run) => Some(script_run.id),
+        Err(e) => {
+            eprintln!("ssm: failed to record the run: {:?}", e);
+            None
+        }
+    };
+
+    // The script shares our process group, so Ctrl+C reaches it directly. We stay alive to
+    // record how it ended.
+    tokio::spawn(async { while tokio::signal::ctrl_c().await.is_ok() {} });
+
+    let mut child = match launcher.spawn(false, std::process::Stdio::inherit()) {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("ssm: failed to start '{}': {}", execution.script_name, e);
+            if let Some(persisted_run_id) = persisted_run_id
+                && let Err(e) = script_run_repository
+                    .finish_run(persisted_run_id, None, e.to_string())
+                    .await
+            {
+                eprintln!("ssm: failed to record the run result: {:?}", e);
+            }
+            launcher.cleanup();
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let captured_output = Mutex::new(VecDeque::new());
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let output = async {
+        tokio::join!(
+            forward_output(stdout, tokio::io::stdout(), &captured_output),
+            forward_output(stderr, tokio::io::stderr(), &captured_output),
+        );
+    };
+    let wait = child.wait();
+    tokio::pin!(output, wait);
+    // Same as the app: a background process of the script may keep the pipes open forever
+    let status = tokio::select! {
+        status = &mut wait => {
+            let _ = tokio::time::timeout(process_registry::OUTPUT_DRAIN_TIMEOUT, &mut output)
+                .await;
+            status
+        }
+        () = &mut output => wait.await,
+    };
+    launcher.cleanup();
+
+    let status = match status {
+        Ok(status) => Some(status),
+        Err(e) => {
+            eprintln!("ssm: failed to wait for '{}': {}", execution.script_name, e);
+            None
+        }
+    };
+    // Stored like the app does, without an exit code when a signal ended the script
+    let exit_code = status.and_then(|status| status.code());
+    if let Some(persisted_run_id) = persisted_run_id {
+        let output = Vec::from(captured_output.into_inner().unwrap()).join("\n");
+        if let Err(e) = script_run_repository
+            .finish_run(persisted_run_id, exit_code, output)
+            .await
+        {
+            eprintln!("ssm: failed to record the run result: {:?}", e);
+        }
+    }
+
+    match status.as_ref().and_then(shell_exit_code) {
+        Some(code) => ExitCode::from(u8::try_from(code & 0xff).unwrap_or(1)),
+        None => ExitCode::FAILURE,
+    }
+}
+
+// Our own exit status, killed by a signal reports 128 + signal like shells do
+fn shell_exit_code(status: &std::process::ExitStatus) -> Option<i32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        status.code().or_else(|| status.signal().map(|s| 128 + s))
+    }
+    #[cfg(not(unix))]
+    {
+        status.code()
+    }
+}
+
+// Copies a pipe of the script to our terminal while keeping the tail for the run history
+async fn forward_output<R, W>(
+    reader: Option<R>,
+    mut writer: W,
+    captured_output: &Mutex<VecDeque<String>>,
+) where
+    R: tokio::io::AsyncRead + Unpin,
+    W: tokio::io::AsyncWrite + Unpin,
+{
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+
+    let Some(reader) = reader else {
+        return;
+    };
+    let mut segments = tokio::io::BufReader::new(reader).split(b'\n');
+    while let Ok(Some(mut segment)) = segments.next_segment().await {
+        let line = String::from_utf8_lossy(&segment)
+            .trim_end_matches('\r')
+            .to_string();
+        {
+            let mut captured = captured_output.lock().unwrap();
+            captured.push_back(line);
+            if captured.len() > shell_script_manager::MAX_PERSISTED_OUTPUT_LINES {
+                captured.pop_front();
+            }
+        }
+        segment.push(b'\n');
+        if writer.write_all(&segment).await.is_err() {
+            break;
+        }
+        let _ = writer.flush().await;
+    }
+}
@@ -0,0 +1,182 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+// Everything goes through the channel to the UI thread, where `Bus::deliver` hands it to the
+// handlers the domain modules registered. Commands have exactly one handler, events go to every
+// subscriber.
+
+pub trait Command: Debug + Send + 'static {
+    // What the handler replies to the one who dispatched the command
+    type Output: Send + 'static;
+}
+
+// Each subscriber gets its own clone
+pub trait Event: Debug + Clone + Send + 'static {}
+
+// A domain plugs its command handlers and event subscribers into the bus
+pub trait DomainModule {
+    fn register(self, bus: &mut Bus);
+}
+
+// A command that failed, as its reply: what went wrong and the underlying error
+#[derive(Debug, Clone)]
+pub struct CommandError {
+    pub message: String,
+    pub detail: String,
+}
+
+impl CommandError {
+    // Also logs the failure and shows it as a toast, see `crate::report_error`
+    pub fn report<E: Debug>(message: &str, error: &E) -> Self {
+        crate::report_error(message, error);
+        Self {
+            message: message.to_string(),
+            detail: format!("{:?}", error),
+        }
+    }
+}
+
+// Hands the output of a command back to the one who dispatched it. The callback runs on the UI
+// thread, nothing happens when nobody asked for the output.
+pub struct Reply<T> {
+    callback: Option<Box<dyn FnOnce(T) + Send + 'static>>,
+}
+
+impl<T: Send + 'static> Reply<T> {
+    pub fn none() -> Self {
+        Self { callback: None }
+    }
+
+    pub fn to<F>(callback: F) -> Self
+    where
+        F: FnOnce(T) + Send + 'static,
+    {
+        Self {
+            callback: Some(Box::new(callback)),
+        }
+    }
+
+    pub fn send(self, output: T) {
+        if let Some(callback) = self.callback {
+            crate::send_event(AppMessage::Callback(Box::new(move || callback(output))));
+        }
+    }
+}
+
+struct CommandEnvelope<C: Command> {
+    command: C,
+    reply: Reply<C::Output>,
+}
+
+pub enum AppMessage {
+    // A `CommandEnvelope`
+    Command(Box<dyn Any + Send>),
+    Event(Box<dyn Any + Send>),
+    Callback(Box<dyn FnOnce() + Send + 'static>),
+}
+
+impl std::fmt::Debug for AppMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppMessage::Command(_) => f.debug_tuple("Command").field(&"<command>").finish(),
+            AppMessage::Event(_) => f.debug_tuple("Event").field(&"<event>").finish(),
+            AppMessage::Callback(_) => f.debug_tuple("Callback").field(&"<callback>").finish(),
+        }
+    }
+}
+
+pub fn dispatch<C: Command>(command: C) {
+    dispatch_with_reply(command, Reply::none());
+}
+
+pub fn dispatch_with_reply<C: Command>(command: C, reply: Reply<C::Output>) {
+    crate::send_event(AppMessage::Command(Box::new(CommandEnvelope {
+        command,
+        reply,
+    })));
+}
+
+pub fn publish<E: Event>(event: E) {
+    crate::send_event(AppMessage::Event(Box::new(event)));
+}
+
+type CommandHandler = Box<dyn FnMut(Box<dyn Any + Send>)>;
+type EventSubscriber = Box<dyn FnMut(&(dyn Any + Send))>;
+
+// Keyed by the type of the boxed envelope or event
+#[derive(Default)]
+pub struct Bus {
+    command_handlers: HashMap<TypeId, CommandHandler>,
+    event_subscribers: HashMap<TypeId, Vec<EventSubscriber>>,
+}
+
+impl Bus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register<M: DomainModule>(&mut self, module: M) -> &mut Self {
+        module.register(self);
+        self
+    }
+
+    // A later handler for the same command replaces the earlier one
+    pub fn handle_commands<C, F>(&mut self, mut handler: F)
+    where
+        C: Command,
+        F: FnMut(C, Reply<C::Output>) + 'static,
+    {
+        self.command_handlers.insert(
+            TypeId::of::<CommandEnvelope<C>>(),
+            Box::new(move |envelope| {
+                if let Ok(envelope) = envelope.downcast::<CommandEnvelope<C>>() {
+                    handler(envelope.command, envelope.reply);
+                }
+            }),
+        );
+    }
+
+    // Subscribers are called in the order they subscribed in
+    pub fn subscribe<E, F>(&mut self, mut subscriber: F)
+    where
+        E: Event,
+        F: FnMut(E) + 'static,
+    {
+        self.event_subscribers
+            .entry(TypeId::of::<E>())
+            .or_default()
+            .push(Box::new(move |event| {
+                if let Some(event) = event.downcast_ref::<E>() {
+                    subscriber(event.clone());
+                }
+            }));
+    }
+
+    pub fn deliver(&mut self, message: AppMessage) {
+        match message {
+            AppMessage::Command(envelope) => {
+                // The type of the boxed value, not of the box
+                let type_id = (*envelope).type_id();
+                match self.command_handlers.get_mut(&type_id) {
+                    Some(handler) => handler(envelope),
+                    // Every domain module registers its handlers before the first dispatch, so
+                    // this is a missing `register` call. The reply of the command never comes.
+                    None => {
+                        debug_assert!(false, "No handler registered for a dispatched command");
+                        eprintln!("No handler registered for a dispatched command");
+                    }
+                }
+            }
+            AppMessage::Event(event) => {
+                let type_id = (*event).type_id();
+                if let Some(subscribers) = self.event_subscribers.get_mut(&type_id) {
+                    for subscriber in subscribers.iter_mut() {
+                        subscriber(event.as_ref());
+                    }
+                }
+            }
+            AppMessage::Callback(callback) => callback(),
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use crate::db::repository::script_run_repository::now_millis;
+use crate::domain::execution::script_execution::ScriptExecution;
+use crate::domain::folder::folder_command_handler::FolderCommand;
+use crate::domain::palette::palette_command_handler::PaletteCommand;
+use crate::domain::palette::palette_search::rank_entries;
+use crate::state::palette_state::PaletteEntry;
+use crate::{dispatch_folder_command, dispatch_palette_command};
+use crate::{with_folder_state, with_palette_state, with_palette_state_reducer};
+use egui::{Key, KeyboardShortcut, Modifiers};
+use std::sync::Arc;
+
+pub const PALETTE_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::K);
+const MAX_VISIBLE_RESULTS: usize = 50;
+
+enum PaletteAction {
+    Execute(PaletteEntry),
+    Edit(PaletteEntry),
+    Close,
+}
+
+// Ranked indices into the entries, kept until the query or the entries change
+struct Ranking {
+    query: String,
+    entries: Arc<Vec<PaletteEntry>>,
+    indices: Vec<usize>,
+}
+
+pub struct CommandPalette {
+    query: String,
+    selected_index: usize,
+    // Scroll the selection into view only when it was moved with the keyboard
+    scroll_to_selected: bool,
+    ranking: Option<Ranking>,
+}
+
+impl CommandPalette {
+    pub fn new() -> Self {
+        Self {
+            query: String::new(),
+            selected_index: 0,
+            scroll_to_selected: false,
+            ranking: None,
+        }
+    }
+
+    pub fn view(&mut self, ctx: &egui::Context) {
+        let open = with_palette_state(|state| *state.open.read().unwrap());
+        if ctx.input_mut(|i| i.consume_shortcut(&PALETTE_SHORTCUT)) {
+            if open {
+                self.close();
+            } else {
+                self.query.clear();
+                self.selected_index = 0;
+                dispatch_palette_command(PaletteCommand::Open);
+            }
+            return;
+        }
+        if !open {
+            return;
+        }
+
+        let entries = with_palette_state(|state| state.entries.read().unwrap().clone());
+        let ranked = self.ranked_indices(&entries);
+
+        // Consumed before the text field sees them, Shift+Enter has to be checked before Enter
+        let (up, down, shift_enter, enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(Modifiers::NONE, Key::ArrowUp),
+                i.consume_key(Modifiers::NONE, Key::ArrowDown),
+                i.consume_key(Modifiers::SHIFT, Key::Enter),
+                i.consume_key(Modifiers::NONE, Key::Enter),
+                i.consume_key(Modifiers::NONE, Key::Escape),
+            )
+        });
+        if up {
+            self.selected_index = self.selected_index.saturating_sub(1);
+            self.scroll_to_selected = true;
+        }
+        if down {
+            self.selected_index += 1;
+            self.scroll_to_selected = true;
+        }
+        self.selected_index = self.selected_index.min(ranked.len().saturating_sub(1));
+
+        let selected_entry = ranked
+            .get(self.selected_index)
+            .and_then(|index| entries.get(*index))
+            .cloned();
+        let mut action = match (shift_enter, enter, escape, selected_entry) {
+            (_, _, true, _) => Some(PaletteAction::Close),
+            (true, _, _, Some(entry)) => Some(PaletteAction::Edit(entry)),
+            (_, true, _, Some(entry)) => Some(PaletteAction::Execute(entry)),
+            _ => None,
+        };
+
+        let window = egui::Window::new("Command Palette")
+            .id(egui::Id::new("command_palette"))
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .fixed_size([560.0, 0.0])
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 80.0))
+            .show(ctx, |ui| {
+                let query_response = ui.add(
+                    egui::TextEdit::singleline(&mut self.query)
+                        .hint_text("Search scripts, commands and folders, #tag to filter...")
+                        .desired_width(f32::INFINITY)
+                        .font(egui::TextStyle::Heading),
+                );
+                // Keeps typing going to the query while the palette is open
+                query_response.request_focus();
+                if query_response.changed() {
+                    self.selected_index = 0;
+                }
+                ui.separator();
+
+                if ranked.is_empty() {
+                    ui.weak("No matching scripts");
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(360.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        for (row, index) in ranked.iter().enumerate() {
+                            let Some(entry) = entries.get(*index) else {
+                                continue;
+                            };
+                            let response = self.result_row(ui, entry, row == self.selected_index);
+                            if response.hovered()
+                                && ui.input(|i| i.pointer.delta() != egui::Vec2::ZERO)
+                            {
+                                self.selected_index = row;
+                            }
+                            if response.clicked() {
+                                action = Some(PaletteAction::Execute(entry.clone()));
+                            }
+                        }
+                    });
+                self.scroll_to_selected = false;
+
+                ui.separator();
+                ui.weak(format!(
+                    "↑↓ navigate   Enter run   Shift+Enter edit   Esc close   {} toggle",
+                    ctx.format_shortcut(&PALETTE_SHORTCUT)
+                ));
+            });
+
+        // Clicking anywhere outside of the palette dismisses it
+        if let Some(window) = window {
+            let clicked_outside = ctx.input(|i| {
+                i.pointer.any_pressed()
+                    && i.pointer
+                        .interact_pos()
+                        .is_some_and(|pos| !window.response.rect.contains(pos))
+            });
+            if clicked_outside && action.is_none() {
+                action = Some(PaletteAction::Close);
+            }
+        }
+
+        match action {
+            Some(PaletteAction::Execute(entry)) => {
+                crate::execute_script(ScriptExecution::from(&entry.script));
+                self.close();
+            }
+            Some(PaletteAction::Edit(entry)) => {
+                Self::open_in_editor(entry);
+                self.close();
+            }
+            Some(PaletteAction::Close) => self.close(),
+            None => {}
+        }
+    }
+
+    fn ranked_indices(&mut self, entries: &Arc<Vec<PaletteEntry>>) -> Vec<usize> {
+        let up_to_date = self.ranking.as_ref().is_some_and(|ranking| {
+            ranking.query == self.query && Arc::ptr_eq(&ranking.entries, entries)
+        });
+        if !up_to_date {
+            let indices = rank_entries(entries, &self.query, now_millis())
+                .into_iter()
+                .take(MAX_VISIBLE_RESULTS)
+                .collect();
+            self.ranking = Some(Ranking {
+                query: self.query.clone(),
+                entries: entries.clone(),
+                indices,
+            });
+        }
+        self.ranking
+            .as_ref()
+            .map(|ranking| ranking.indices.clone())
+            .unwrap_or_default()
+    }
+
+    fn result_row(
+        &self,
+        ui: &mut egui::Ui,
+        entry: &PaletteEntry,
+        selected: bool,
+    ) -> egui::Response {
+        let fill = if selected {
+            ui.visuals().selection.bg_fill
+        } else {
+            egui::Color32::TRANSPARENT
+        };
+        let frame_response = egui::Frame::NONE
+            .fill(fill)
+            .corner_radius(4.0)
+            .inner_margin(egui::Margin::symmetric(6, 4))
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(&entry.script.name).strong());
+                    let folder_names: Vec<&str> = entry
+                        .folders
+                        .iter()
+                        .map(|(_, name)| name.as_str())
+                        .collect();
+                    ui.weak(folder_names.join(", "));
+                    for tag in entry.tags.iter() {
+                        ui.label(egui::RichText::new(format!("#{}", tag)).small().weak());
+                    }
+                    if entry.run_count > 0 {
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.weak(format!("{} runs", entry.run_count));
+                        });
+                    }
+                });
+                let first_line = entry.script.command.lines().next().unwrap_or_default();
+                ui.label(egui::RichText::new(first_line).monospace().weak().small());
+            });
+        let response = ui.interact(
+            frame_response.response.rect,
+            ui.id().with(("palette_row", entry.script.id)),
+            egui::Sense::click(),
+        );
+        if selected && self.scroll_to_selected {
+            response.scroll_to_me(None);
+        }
+        response
+    }
+
+    // Shows the script's folder and opens the regular edit window of the scripts column
+    fn open_in_editor(entry: PaletteEntry) {
+        let selected_folder_id =
+            with_folder_state(|state| *state.selected_folder_id.read().unwrap());
+        let folder_ids: Vec<i32> = entry.folders.iter().map(|(id, _)| *id).collect();
+        let already_visible = selected_folder_id.is_some_and(|id| folder_ids.contains(&id));
+        if let (Some(folder_id), false) = (folder_ids.first(), already_visible) {
+            dispatch_folder_command(FolderCommand::SelectFolder {
+                folder_id: *folder_id,
+            });
+        }
+        crate::with_folder_state_reducer(|r| r.request_script_edit(entry.script));
+    }
+
+    fn close(&mut self) {
+        self.query.clear();
+        self.selected_index = 0;
+        self.ranking = None;
+        with_palette_state_reducer(|r| r.close_palette());
+    }
+}
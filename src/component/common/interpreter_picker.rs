@@ -0,0 +1,37 @@
+use crate::domain::execution::interpreter::{Interpreter, InterpreterSettings};
+use egui::Ui;
+
+// Interpreter combo box, the shebang field for custom interpreters and the rc-sourcing toggle.
+// Returns true when anything changed so that callers can refresh their syntax highlighting.
+pub fn interpreter_picker(ui: &mut Ui, id_salt: &str, settings: &mut InterpreterSettings) -> bool {
+    let before = settings.clone();
+    ui.horizontal(|ui| {
+        ui.label("Interpreter:");
+        egui::ComboBox::from_id_salt((id_salt, "interpreter"))
+            .selected_text(settings.interpreter.label())
+            .show_ui(ui, |ui| {
+                for interpreter in Interpreter::ALL {
+                    ui.selectable_value(
+                        &mut settings.interpreter,
+                        interpreter,
+                        interpreter.label(),
+                    );
+                }
+            });
+        if settings.interpreter == Interpreter::Custom {
+            let mut shebang = settings.shebang.clone().unwrap_or_default();
+            ui.add(
+                egui::TextEdit::singleline(&mut shebang)
+                    .font(egui::TextStyle::Monospace)
+                    .hint_text("#!/usr/bin/env ruby")
+                    .desired_width(220.0),
+            );
+            settings.shebang = (!shebang.is_empty()).then_some(shebang);
+        }
+        ui.checkbox(&mut settings.source_rc, "Source rc files")
+            .on_hover_text(
+                "Load ~/.zshrc, ~/.bashrc, ... first so that PATH and aliases are available",
+            );
+    });
+    *settings != before
+}
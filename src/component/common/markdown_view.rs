@@ -0,0 +1,193 @@
+use egui::{RichText, Ui};
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+
+const LIST_INDENT: f32 = 14.0;
+
+#[derive(Clone, Copy, Default)]
+struct InlineStyle {
+    strong: bool,
+    emphasis: bool,
+    strikethrough: bool,
+    code: bool,
+}
+
+struct Span {
+    text: String,
+    style: InlineStyle,
+    link: Option<String>,
+}
+
+// Renders the Markdown subset script descriptions need: headings, paragraphs, (nested) lists,
+// links, emphasis, inline code and code blocks. Anything else is shown as plain text.
+pub fn markdown_view(ui: &mut Ui, markdown: &str) {
+    let mut renderer = MarkdownRenderer::default();
+    for event in Parser::new_ext(markdown, Options::ENABLE_STRIKETHROUGH) {
+        renderer.event(ui, event);
+    }
+    renderer.flush(ui);
+}
+
+#[derive(Default)]
+struct MarkdownRenderer {
+    spans: Vec<Span>,
+    style: InlineStyle,
+    link: Option<String>,
+    heading: Option<HeadingLevel>,
+    // Next number of each open list, `None` for bullet lists
+    lists: Vec<Option<u64>>,
+    item_marker: Option<String>,
+    code_block: Option<String>,
+}
+
+impl MarkdownRenderer {
+    fn event(&mut self, ui: &mut Ui, event: Event<'_>) {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                self.flush(ui);
+                self.heading = Some(level);
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                self.flush(ui);
+                self.heading = None;
+                ui.add_space(2.0);
+            }
+            Event::End(TagEnd::Paragraph) => {
+                self.flush(ui);
+                if self.lists.is_empty() {
+                    ui.add_space(4.0);
+                }
+            }
+            Event::Start(Tag::List(first_number)) => {
+                // The text of the parent item comes before its nested list
+                self.flush(ui);
+                self.lists.push(first_number);
+            }
+            Event::End(TagEnd::List(_)) => {
+                self.flush(ui);
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    ui.add_space(4.0);
+                }
+            }
+            Event::Start(Tag::Item) => {
+                self.flush(ui);
+                self.item_marker = Some(match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}.", *number - 1)
+                    }
+                    _ => "•".to_string(),
+                });
+            }
+            Event::End(TagEnd::Item) => self.flush(ui),
+            Event::Start(Tag::CodeBlock(_)) => {
+                self.flush(ui);
+                self.code_block = Some(String::new());
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some(code) = self.code_block.take() {
+                    egui::Frame::NONE
+                        .fill(ui.visuals().code_bg_color)
+                        .corner_radius(4.0)
+                        .inner_margin(6.0)
+                        .show(ui, |ui| {
+                            ui.set_width(ui.available_width());
+                            ui.label(RichText::new(code.trim_end()).monospace());
+                        });
+                    ui.add_space(4.0);
+                }
+            }
+            Event::Start(Tag::Strong) => self.style.strong = true,
+            Event::End(TagEnd::Strong) => self.style.strong = false,
+            Event::Start(Tag::Emphasis) => self.style.emphasis = true,
+            Event::End(TagEnd::Emphasis) => self.style.emphasis = false,
+            Event::Start(Tag::Strikethrough) => self.style.strikethrough = true,
+            Event::End(TagEnd::Strikethrough) => self.style.strikethrough = false,
+            Event::Start(Tag::Link { dest_url, .. }) => self.link = Some(dest_url.to_string()),
+            Event::End(TagEnd::Link) => self.link = None,
+            Event::Text(text) => match self.code_block.as_mut() {
+                Some(code) => code.push_str(&text),
+                None => self.push_span(&text, self.style),
+            },
+            Event::Code(code) => {
+                let style = InlineStyle {
+                    code: true,
+                    ..self.style
+                };
+                self.push_span(&code, style);
+            }
+            Event::Html(html) | Event::InlineHtml(html) => self.push_span(&html, self.style),
+            Event::SoftBreak => self.push_span(" ", self.style),
+            Event::HardBreak => self.push_span("\n", self.style),
+            Event::Rule => {
+                self.flush(ui);
+                ui.separator();
+            }
+            _ => {}
+        }
+    }
+
+    fn push_span(&mut self, text: &str, style: InlineStyle) {
+        self.spans.push(Span {
+            text: text.to_string(),
+            style,
+            link: self.link.clone(),
+        });
+    }
+
+    // Lays out the collected text of the current block as one wrapped line run
+    fn flush(&mut self, ui: &mut Ui) {
+        let item_marker = self.item_marker.take();
+        if self.spans.is_empty() && item_marker.is_none() {
+            return;
+        }
+        let spans = std::mem::take(&mut self.spans);
+        let heading = self.heading;
+        let depth = self.lists.len();
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing.x = 0.0;
+            if depth > 0 {
+                ui.add_space(depth as f32 * LIST_INDENT);
+            }
+            if let Some(marker) = item_marker {
+                ui.label(format!("{} ", marker));
+            }
+            for span in spans {
+                let text = rich_text(span.text, span.style, heading);
+                match span.link {
+                    Some(url) => {
+                        ui.hyperlink_to(text, url);
+                    }
+                    None => {
+                        ui.label(text);
+                    }
+                }
+            }
+        });
+    }
+}
+
+fn rich_text(text: String, style: InlineStyle, heading: Option<HeadingLevel>) -> RichText {
+    let mut rich_text = RichText::new(text);
+    if let Some(level) = heading {
+        let size = match level {
+            HeadingLevel::H1 => 20.0,
+            HeadingLevel::H2 => 17.0,
+            _ => 15.0,
+        };
+        rich_text = rich_text.size(size).strong();
+    }
+    if style.strong {
+        rich_text = rich_text.strong();
+    }
+    if style.emphasis {
+        rich_text = rich_text.italics();
+    }
+    if style.strikethrough {
+        rich_text = rich_text.strikethrough();
+    }
+    if style.code {
+        rich_text = rich_text.code();
+    }
+    rich_text
+}
@@ -1,2 +1,5 @@
 pub mod div_with_padding;
 pub mod horizontal_filled_button;
+pub mod interpreter_picker;
+pub mod markdown_view;
+pub mod tag_chips;
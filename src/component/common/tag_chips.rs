@@ -0,0 +1,19 @@
+// Small rounded `#tag` labels, returns the tag that was clicked
+pub fn tag_chips(ui: &mut egui::Ui, tags: &[&str]) -> Option<String> {
+    let mut clicked = None;
+    for tag in tags {
+        let chip = egui::Button::new(egui::RichText::new(format!("#{}", tag)).small())
+            .fill(ui.visuals().selection.bg_fill.gamma_multiply(0.35))
+            .stroke(egui::Stroke::NONE)
+            .corner_radius(8.0)
+            .small();
+        if ui
+            .add(chip)
+            .on_hover_text(format!("Show all scripts tagged #{}", tag))
+            .clicked()
+        {
+            clicked = Some(tag.to_string());
+        }
+    }
+    clicked
+}
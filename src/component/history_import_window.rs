@@ -0,0 +1,173 @@
+use crate::db::repository::script_repository::NewScript;
+use crate::dispatch_folder_command;
+use crate::domain::execution::interpreter::InterpreterSettings;
+use crate::domain::folder::folder_command_handler::FolderCommand;
+use crate::domain::library::shell_history::{HistoryCommand, script_name_from_command};
+use crate::{with_folder_state, with_library_state, with_library_state_reducer};
+use std::collections::HashSet;
+
+const ROW_HEIGHT: f32 = 20.0;
+
+pub struct HistoryImportWindow {
+    filter: String,
+    checked_commands: HashSet<String>,
+    folder_id: Option<i32>,
+}
+
+impl HistoryImportWindow {
+    pub fn new() -> Self {
+        Self {
+            filter: String::new(),
+            checked_commands: HashSet::new(),
+            folder_id: None,
+        }
+    }
+
+    pub fn view(&mut self, ctx: &egui::Context) {
+        let Some(commands) =
+            with_library_state(|state| state.history_import.read().unwrap().clone())
+        else {
+            return;
+        };
+        let folders = with_folder_state(|state| state.folder_list.read().unwrap().clone());
+        // Defaults to the folder that is open in the scripts column
+        if self
+            .folder_id
+            .is_none_or(|id| !folders.iter().any(|f| f.id == id))
+        {
+            self.folder_id = with_folder_state(|state| *state.selected_folder_id.read().unwrap())
+                .or_else(|| folders.first().map(|f| f.id));
+        }
+
+        let filter = self.filter.to_lowercase();
+        let visible: Vec<&HistoryCommand> = commands
+            .iter()
+            .filter(|c| filter.is_empty() || c.command.to_lowercase().contains(&filter))
+            .collect();
+
+        let mut open = true;
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new("Import from Shell History")
+            .id(egui::Id::new("history_import_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(640.0)
+            .default_height(480.0)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.filter)
+                        .hint_text("Filter commands...")
+                        .desired_width(f32::INFINITY),
+                );
+                ui.horizontal(|ui| {
+                    if ui.button("Select shown").clicked() {
+                        for command in visible.iter() {
+                            self.checked_commands.insert(command.command.clone());
+                        }
+                    }
+                    if ui.button("Clear selection").clicked() {
+                        self.checked_commands.clear();
+                    }
+                    ui.weak(format!(
+                        "{} of {} commands shown",
+                        visible.len(),
+                        commands.len()
+                    ));
+                });
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .max_height(360.0)
+                    .auto_shrink([false, true])
+                    .show_rows(ui, ROW_HEIGHT, visible.len(), |ui, row_range| {
+                        for command in visible.iter().skip(row_range.start).take(row_range.len()) {
+                            self.command_row(ui, command);
+                        }
+                    });
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.label("Add to folder:");
+                    let selected_name = folders
+                        .iter()
+                        .find(|f| Some(f.id) == self.folder_id)
+                        .map(|f| f.name.clone())
+                        .unwrap_or_else(|| "No folder".to_string());
+                    egui::ComboBox::from_id_salt("history_import_folder")
+                        .selected_text(selected_name)
+                        .show_ui(ui, |ui| {
+                            for folder in folders.iter() {
+                                ui.selectable_value(
+                                    &mut self.folder_id,
+                                    Some(folder.id),
+                                    &folder.name,
+                                );
+                            }
+                        });
+                });
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        cancelled = true;
+                    }
+                    let can_import = self.folder_id.is_some() && !self.checked_commands.is_empty();
+                    let add_label = format!("Add {} scripts", self.checked_commands.len());
+                    if ui
+                        .add_enabled(can_import, egui::Button::new(add_label))
+                        .clicked()
+                    {
+                        confirmed = true;
+                    }
+                });
+            });
+
+        if confirmed {
+            if let Some(folder_id) = self.folder_id {
+                // Keeps the order of the list, most recently used first
+                let scripts = commands
+                    .iter()
+                    .filter(|c| self.checked_commands.contains(&c.command))
+                    .map(|command| NewScript {
+                        name: script_name_from_command(&command.command),
+                        command: command.command.clone(),
+                        interpreter_settings: InterpreterSettings::default(),
+                    })
+                    .collect();
+                dispatch_folder_command(FolderCommand::AddScriptsToFolder { folder_id, scripts });
+            }
+        }
+        if confirmed || cancelled || !open {
+            self.filter.clear();
+            self.checked_commands.clear();
+            with_library_state_reducer(|r| r.close_history_import());
+        }
+    }
+
+    fn command_row(&mut self, ui: &mut egui::Ui, command: &HistoryCommand) {
+        ui.horizontal(|ui| {
+            let mut checked = self.checked_commands.contains(&command.command);
+            let first_line = command.command.lines().next().unwrap_or_default();
+            if ui
+                .checkbox(&mut checked, egui::RichText::new(first_line).monospace())
+                .on_hover_text(&command.command)
+                .changed()
+            {
+                if checked {
+                    self.checked_commands.insert(command.command.clone());
+                } else {
+                    self.checked_commands.remove(&command.command);
+                }
+            }
+            let line_count = command.command.lines().count();
+            if line_count > 1 {
+                ui.weak(format!("+{} lines", line_count - 1));
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.weak(format!("{} · {}×", command.shell.label(), command.use_count));
+            });
+        });
+    }
+}
@@ -1,9 +1,13 @@
 use crate::dispatch_folder_command;
 use crate::domain::folder::folder_command_handler::FolderCommand;
+use crate::domain::folder::folder_tree::subtree_ids;
 use crate::prisma::scripts_folder::Data;
 use egui::Ui;
 
 pub fn confirm_delete_folder_window(ui: &mut Ui, folder: &Data) {
+    let subfolder_count = crate::with_folder_state(|state| {
+        subtree_ids(&state.folder_list.read().unwrap(), folder.id).len() - 1
+    });
     egui::Window::new("Confirm Delete")
         .collapsible(false)
         .resizable(false)
@@ -13,6 +17,13 @@ pub fn confirm_delete_folder_window(ui: &mut Ui, folder: &Data) {
                 "Are you sure you want to delete this folder: {}?",
                 folder.name
             ));
+            if subfolder_count > 0 {
+                ui.label(format!(
+                    "Its {} subfolder(s) will be deleted as well.",
+                    subfolder_count
+                ));
+            }
+            ui.weak("It can be restored from the Trash.");
             ui.add_space(20.0);
             ui.horizontal(|ui| {
                 if ui.button("Cancel").clicked() {
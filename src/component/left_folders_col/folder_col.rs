@@ -1,18 +1,33 @@
+use crate::component::common::div_with_padding::div_with_padding;
 use crate::component::common::horizontal_filled_button::horizontal_filled_button;
 use crate::component::left_folders_col::folder_item::FolderItem;
-use crate::dispatch_folder_command;
+use crate::component::right_scripts_col::scripts_of_selected_folder::{
+    ScriptDragPayload, folders_of_script,
+};
 use crate::domain::folder::folder_command_handler::FolderCommand;
+use crate::domain::folder::folder_tree::{FolderRow, child_folders, subtree_ids, visible_rows};
 use crate::prisma::scripts_folder::Data;
+use crate::state::folder_state::VirtualFolder;
+use crate::{dispatch_folder_command, with_folder_state_reducer};
 use eframe::emath::Pos2;
 use egui::{Color32, Id, Response, Stroke, Ui};
-use prisma_client_rust::bigdecimal::ToPrimitive;
 use std::sync::Arc;
 
 pub struct FolderColumn;
 
+const INDENT_PER_LEVEL: f32 = 16.0;
+const TOGGLE_WIDTH: f32 = 14.0;
+
 #[derive(Clone, PartialEq, Eq, Copy, Debug)]
-struct Location {
-    row_index: i32,
+struct FolderDragPayload {
+    folder_id: i32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DropPosition {
+    Before,
+    Inside,
+    After,
 }
 
 impl FolderColumn {
@@ -56,39 +71,52 @@ impl FolderColumn {
             .show(ui, |ui| {
                 egui::ScrollArea::vertical().show(ui, |ui| {
                     // Get direct access to state - we handle locking ourselves!
+                    Self::virtual_folder_entries(ui);
+                    ui.add_space(6.0);
+
                     crate::with_folder_state(|state| {
                         let folders_vec = (*state.folder_list.read().unwrap()).clone();
-                        let selected_id = *state.selected_folder_id.read().unwrap();
+                        let collapsed = state.collapsed_folder_ids.read().unwrap().clone();
+                        // Script cards are only dragged out of the selected folder
+                        let dragged_scripts =
+                            state.scripts_of_selected_folder.read().unwrap().clone();
+                        // No folder is highlighted while "Pinned" or "Trash" is open
+                        let selected_id = match *state.virtual_folder.read().unwrap() {
+                            Some(_) => None,
+                            None => *state.selected_folder_id.read().unwrap(),
+                        };
                         let rename_folder =
                             state.folder_to_rename.read().unwrap().as_ref().cloned();
                         let rename_text = state.rename_text.read().unwrap().as_ref().cloned();
 
-                        let mut from: Option<Location> = None;
-                        let mut to = None;
-
                         if folders_vec.is_empty() {
                             ui.label("No folders yet...");
                         } else {
-                            for (row_idx, folder) in (&*folders_vec).into_iter().enumerate() {
-                                let (item_location, response) = Self::render_dnd_folder_item(
+                            for row in visible_rows(&folders_vec, &collapsed) {
+                                let response = Self::render_dnd_folder_item(
                                     ui,
                                     selected_id,
                                     &rename_folder,
                                     &rename_text,
-                                    row_idx,
-                                    &folder,
+                                    &row,
+                                    !collapsed.contains(&row.folder.id),
+                                );
+
+                                Self::handle_script_drop_event(
+                                    ui,
+                                    &dragged_scripts,
+                                    row.folder,
+                                    &response,
                                 );
 
                                 if let (Some(pointer), Some(hovered_payload)) = (
                                     ui.input(|i| i.pointer.interact_pos()),
-                                    response.dnd_hover_payload::<Location>(),
+                                    response.dnd_hover_payload::<FolderDragPayload>(),
                                 ) {
                                     Self::handle_drop_event(
                                         ui,
-                                        from,
-                                        to,
-                                        row_idx,
-                                        item_location,
+                                        &folders_vec,
+                                        row.folder,
                                         response,
                                         pointer,
                                         hovered_payload,
@@ -101,57 +129,218 @@ impl FolderColumn {
             });
     }
 
+    // Virtual entries above the tree, listing scripts of every folder. Script cards can be dropped
+    // onto them, to pin them or to move them to the trash.
+    fn virtual_folder_entries(ui: &mut Ui) {
+        let (virtual_folder, pinned_count, trash_count) = crate::with_folder_state(|state| {
+            (
+                *state.virtual_folder.read().unwrap(),
+                state.pinned_scripts.read().unwrap().len(),
+                state.trash.read().unwrap().len(),
+            )
+        });
+
+        let response = Self::virtual_folder_entry(
+            ui,
+            "pinned_entry",
+            &format!("📌 Pinned ({})", pinned_count),
+            virtual_folder == Some(VirtualFolder::Pinned),
+        );
+        if response.clicked() {
+            with_folder_state_reducer(|r| r.select_virtual_folder(VirtualFolder::Pinned));
+            dispatch_folder_command(FolderCommand::LoadPinnedScripts);
+        }
+        if let Some(dragged_payload) = Self::script_drop_target(ui, &response, "Pin") {
+            dispatch_folder_command(FolderCommand::SetScriptPinned {
+                script_id: dragged_payload.script_id,
+                pinned: true,
+            });
+        }
+
+        ui.add_space(2.0);
+        let response = Self::virtual_folder_entry(
+            ui,
+            "trash_entry",
+            &format!("🗑 Trash ({})", trash_count),
+            virtual_folder == Some(VirtualFolder::Trash),
+        );
+        if response.clicked() {
+            with_folder_state_reducer(|r| r.select_virtual_folder(VirtualFolder::Trash));
+            dispatch_folder_command(FolderCommand::LoadTrash);
+        }
+        if let Some(dragged_payload) = Self::script_drop_target(ui, &response, "Move to trash") {
+            dispatch_folder_command(FolderCommand::DeleteScript {
+                script_id: dragged_payload.script_id,
+            });
+        }
+    }
+
+    fn virtual_folder_entry(ui: &mut Ui, id_salt: &str, label: &str, selected: bool) -> Response {
+        let inner_response = ui.horizontal(|ui| {
+            ui.add_space(TOGGLE_WIDTH + ui.spacing().item_spacing.x);
+            div_with_padding(ui, 4.0, selected, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add_space(4.0);
+                    ui.label(label);
+                    ui.allocate_space(ui.available_size());
+                })
+                .response
+            })
+        });
+        let response = ui.interact(
+            inner_response.inner.rect,
+            Id::new(id_salt),
+            egui::Sense::click(),
+        );
+        if response.hovered() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+            ui.painter().rect_filled(
+                response.rect,
+                4.0,
+                Color32::from_rgba_premultiplied(0, 0, 0, 30),
+            );
+        }
+        response
+    }
+
+    // Highlights the entry while a script card hovers it, `hint` says what a drop does
+    fn script_drop_target(
+        ui: &mut Ui,
+        response: &Response,
+        hint: &str,
+    ) -> Option<Arc<ScriptDragPayload>> {
+        if response.dnd_hover_payload::<ScriptDragPayload>().is_some() {
+            ui.painter().rect_filled(
+                response.rect,
+                4.0,
+                ui.visuals().selection.bg_fill.gamma_multiply(0.4),
+            );
+            ui.painter().text(
+                response.rect.right_center() - egui::vec2(8.0, 0.0),
+                egui::Align2::RIGHT_CENTER,
+                hint,
+                egui::FontId::proportional(12.0),
+                ui.visuals().strong_text_color(),
+            );
+        }
+        response.dnd_release_payload::<ScriptDragPayload>()
+    }
+
     fn handle_drop_event(
         ui: &mut Ui,
-        mut from: Option<Location>,
-        mut to: Option<Location>,
-        row_idx: usize,
-        item_location: Location,
+        folders: &[Data],
+        target: &Data,
         response: Response,
         pointer: Pos2,
-        hovered_payload: Arc<Location>,
+        hovered_payload: Arc<FolderDragPayload>,
     ) {
         let rect = response.rect;
+        let dragged_id = hovered_payload.folder_id;
+        // Neither onto itself nor into one of its own subfolders
+        if subtree_ids(folders, dragged_id).contains(&target.id) {
+            return;
+        }
 
-        // Preview insertion:
+        // The upper and lower quarter insert next to the folder, the middle moves into it
         let stroke = egui::Stroke::new(2.0, Color32::from_black_alpha(60));
-        let insert_row_idx = if *hovered_payload == item_location {
-            // We are dragged onto ourselves
-            ui.painter().hline(rect.x_range(), rect.center().y, stroke);
-            row_idx
-        } else if pointer.y < rect.center().y {
-            // Above us
+        let position = if pointer.y < rect.top() + rect.height() / 4.0 {
             ui.painter().hline(rect.x_range(), rect.top() - 2.0, stroke);
-            row_idx
-        } else {
-            // Below us
+            DropPosition::Before
+        } else if pointer.y > rect.bottom() - rect.height() / 4.0 {
             ui.painter()
                 .hline(rect.x_range(), rect.bottom() + 2.0, stroke);
-            row_idx + 1
+            DropPosition::After
+        } else {
+            ui.painter()
+                .rect_stroke(rect, 4.0, stroke, egui::StrokeKind::Inside);
+            DropPosition::Inside
         };
 
-        let dragged_payload_opt = response.dnd_release_payload::<Location>();
-        if let Some(dragged_payload) = dragged_payload_opt {
-            // The user dropped onto this item.
-            from = Some(*dragged_payload);
-            to = Some(Location {
-                row_index: insert_row_idx.to_i32().unwrap(),
+        if response
+            .dnd_release_payload::<FolderDragPayload>()
+            .is_some()
+        {
+            // Indices are among the new siblings without the dragged folder
+            let (parent_id, index) = match position {
+                DropPosition::Inside => {
+                    let children = child_folders(folders, Some(target.id));
+                    let index = children.iter().filter(|f| f.id != dragged_id).count();
+                    (Some(target.id), index)
+                }
+                DropPosition::Before | DropPosition::After => {
+                    let target_index = child_folders(folders, target.parent_id)
+                        .iter()
+                        .filter(|f| f.id != dragged_id)
+                        .position(|f| f.id == target.id)
+                        .unwrap_or(0);
+                    let index = match position {
+                        DropPosition::After => target_index + 1,
+                        _ => target_index,
+                    };
+                    (target.parent_id, index)
+                }
+            };
+
+            dispatch_folder_command(FolderCommand::MoveFolder {
+                folder_id: dragged_id,
+                parent_id,
+                index,
             });
+        }
+    }
 
-            let from_row_index = dragged_payload.row_index;
+    // A script card dropped onto the folder is moved into it, or also linked into it while
+    // Ctrl / Cmd is held. A folder that already holds the script is no drop zone.
+    fn handle_script_drop_event(
+        ui: &mut Ui,
+        dragged_scripts: &[crate::prisma::shell_script::Data],
+        target: &Data,
+        response: &Response,
+    ) {
+        let Some(hovered_payload) = response.dnd_hover_payload::<ScriptDragPayload>() else {
+            return;
+        };
+        let already_linked = dragged_scripts
+            .iter()
+            .find(|script| script.id == hovered_payload.script_id)
+            .is_some_and(|script| {
+                folders_of_script(script)
+                    .iter()
+                    .any(|folder| folder.id == target.id)
+            });
+        if hovered_payload.folder_id == target.id || already_linked {
+            return;
+        }
+        ui.painter().rect_filled(
+            response.rect,
+            4.0,
+            ui.visuals().selection.bg_fill.gamma_multiply(0.4),
+        );
+        let link = ui.input(|i| i.modifiers.command);
+        // Tooltips are hidden while dragging, the hint is painted into the drop zone instead
+        ui.painter().text(
+            response.rect.right_center() - egui::vec2(8.0, 0.0),
+            egui::Align2::RIGHT_CENTER,
+            if link { "Add here" } else { "Move here" },
+            egui::FontId::proportional(12.0),
+            ui.visuals().strong_text_color(),
+        );
 
-            let moving_downwards = insert_row_idx as i32 >= from_row_index;
-            // If moving downwards, we need to adjust the target index since the item will be removed first
-            let to_row_index = if moving_downwards {
-                (insert_row_idx as i32 - 1).max(0)
+        if let Some(dragged_payload) = response.dnd_release_payload::<ScriptDragPayload>() {
+            let command = if link {
+                FolderCommand::LinkScriptToFolder {
+                    script_id: dragged_payload.script_id,
+                    folder_id: target.id,
+                }
             } else {
-                insert_row_idx as i32
+                FolderCommand::MoveScriptToFolder {
+                    script_id: dragged_payload.script_id,
+                    from_folder_id: dragged_payload.folder_id,
+                    to_folder_id: target.id,
+                    index: None,
+                }
             };
-
-            dispatch_folder_command(FolderCommand::ReorderFolders {
-                from_index: from_row_index,
-                to_index: to_row_index,
-            });
+            dispatch_folder_command(command);
         }
     }
 
@@ -160,31 +349,37 @@ impl FolderColumn {
         selected_id: Option<i32>,
         rename_folder: &Option<Arc<Data>>,
         rename_text: &Option<String>,
-        row_idx: usize,
-        folder: &&Data,
-    ) -> (Location, Response) {
-        let item_id = Id::new(("my_drag_and_drop_demo", row_idx));
-        let item_location = Location {
-            row_index: row_idx.to_i32().unwrap(),
+        row: &FolderRow,
+        expanded: bool,
+    ) -> Response {
+        let folder = row.folder;
+        let item_id = Id::new(("folder_drag_source", folder.id));
+        let payload = FolderDragPayload {
+            folder_id: folder.id,
         };
         let inner_response = ui.horizontal(|ui| {
-            let dnd_res = ui.dnd_drag_source(item_id, item_location, |ui| {
+            ui.add_space(row.depth as f32 * INDENT_PER_LEVEL);
+            if row.has_children {
+                let toggle = if expanded { "▼" } else { "▶" };
+                if ui
+                    .add_sized([TOGGLE_WIDTH, 20.0], egui::Button::new(toggle).frame(false))
+                    .clicked()
+                {
+                    with_folder_state_reducer(|r| r.toggle_folder_collapsed(folder.id));
+                }
+            } else {
+                ui.add_space(TOGGLE_WIDTH + ui.spacing().item_spacing.x);
+            }
+            let dnd_res = ui.dnd_drag_source(item_id, payload, |ui| {
                 ui.label(egui::RichText::new(" :: "));
             });
-            Self::render_folder(
-                ui,
-                selected_id,
-                rename_folder.clone(),
-                &rename_text,
-                &folder,
-            );
+            Self::render_folder(ui, selected_id, rename_folder.clone(), &rename_text, folder);
             (dnd_res, ())
         });
 
         ui.add_space(2.0);
 
-        let response = inner_response.response;
-        (item_location, response)
+        inner_response.response
     }
 
     fn render_folder(
@@ -192,7 +387,7 @@ impl FolderColumn {
         selected_id: Option<i32>,
         rename_folder: Option<Arc<Data>>,
         rename_text: &Option<String>,
-        folder: &&Data,
+        folder: &Data,
     ) {
         let is_renaming = rename_folder
             .as_ref()
@@ -220,7 +415,7 @@ impl FolderColumn {
         // Set cursor to pointer and background color when hovering
 
         if response.clicked() {
-            dispatch_folder_command(FolderCommand::CreateFolder {});
+            dispatch_folder_command(FolderCommand::CreateFolder { parent_id: None });
         }
     }
 }
@@ -95,6 +95,26 @@ impl<'a> FolderItem<'a> {
                     *state.rename_text.write().unwrap() = Some(folder_.name.clone());
                 });
             }
+            if ui
+                .add_sized([120.0, 20.0], |ui: &mut egui::Ui| {
+                    ui.button("New Subfolder")
+                })
+                .clicked()
+            {
+                dispatch_folder_command(FolderCommand::CreateFolder {
+                    parent_id: Some(folder.id),
+                });
+            }
+            if ui
+                .add_sized([120.0, 20.0], |ui: &mut egui::Ui| {
+                    ui.button("Export as Shell Scripts…")
+                })
+                .clicked()
+            {
+                crate::with_library_state_reducer(|r| {
+                    r.request_shell_export(folder.id, folder.name.clone())
+                });
+            }
             if ui
                 .add_sized([120.0, 20.0], |ui: &mut egui::Ui| {
                     ui.button("Delete Folder")
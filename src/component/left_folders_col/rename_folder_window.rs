@@ -1,6 +1,6 @@
 use crate::domain::folder::folder_command_handler::FolderCommand;
 use crate::prisma::scripts_folder::Data;
-use crate::{dispatch_folder_command, dispatch_folder_command_with_callback};
+use crate::dispatch_folder_command_with_reply;
 use egui::Ui;
 use std::sync::Arc;
 
@@ -35,17 +35,20 @@ pub fn rename_folder_window(ui: &mut Ui, folder_: Arc<Data>) {
                     });
                 }
                 if ui.button("Rename").clicked() || enter_pressed {
-                    dispatch_folder_command_with_callback(
+                    dispatch_folder_command_with_reply(
                         FolderCommand::RenameFolder {
                             folder_id: folder_.id,
                             new_name: text,
                         },
-                        Some(|| {
-                            crate::with_folder_state(|state| {
-                                *state.folder_to_rename.write().unwrap() = None;
-                                *state.rename_text.write().unwrap() = None;
-                            });
-                        }),
+                        |result| {
+                            // Stays open on failure, so the name can be fixed and retried
+                            if result.is_ok() {
+                                crate::with_folder_state(|state| {
+                                    *state.folder_to_rename.write().unwrap() = None;
+                                    *state.rename_text.write().unwrap() = None;
+                                });
+                            }
+                        },
                     );
                 }
             });
@@ -0,0 +1,213 @@
+use crate::domain::library::library_command_handler::LibraryCommand;
+use crate::domain::library::library_document::{ConflictPolicy, ImportMode};
+use crate::domain::library::shell_files::MENU_FILE_NAME;
+use crate::{dispatch_library_command, with_library_state, with_library_state_reducer};
+
+const DEFAULT_EXPORT_FILE_NAME: &str = "shell-scripts.yaml";
+
+// File → Export Library…
+pub fn export_library_dialog() {
+    let path = rfd::FileDialog::new()
+        .set_title("Export Library")
+        .add_filter("YAML", &["yaml", "yml"])
+        .add_filter("JSON", &["json"])
+        .set_file_name(DEFAULT_EXPORT_FILE_NAME)
+        .save_file();
+    if let Some(path) = path {
+        dispatch_library_command(LibraryCommand::Export { path });
+    }
+}
+
+// File → Import Library…, the import itself starts from the options window
+pub fn import_library_dialog() {
+    let path = rfd::FileDialog::new()
+        .set_title("Import Library")
+        .add_filter("Library", &["yaml", "yml", "json"])
+        .pick_file();
+    if let Some(path) = path {
+        with_library_state_reducer(|r| r.request_import(path));
+    }
+}
+
+// File → Import from Project…, a directory with a package.json, Makefile or justfile
+pub fn import_project_dialog() {
+    let directory = rfd::FileDialog::new()
+        .set_title("Import from Project")
+        .pick_folder();
+    if let Some(directory) = directory {
+        dispatch_library_command(LibraryCommand::ImportProject { directory });
+    }
+}
+
+// File → Import Shell Scripts…, e.g. a directory written by "Export as Shell Scripts…"
+pub fn import_shell_scripts_dialog() {
+    let directory = rfd::FileDialog::new()
+        .set_title("Import Shell Scripts")
+        .pick_folder();
+    if let Some(directory) = directory {
+        dispatch_library_command(LibraryCommand::ImportShellScripts { directory });
+    }
+}
+
+pub struct LibraryImportWindow {
+    replace: bool,
+    conflict_policy: ConflictPolicy,
+    with_menu: bool,
+}
+
+impl LibraryImportWindow {
+    pub fn new() -> Self {
+        Self {
+            replace: false,
+            conflict_policy: ConflictPolicy::Skip,
+            with_menu: true,
+        }
+    }
+
+    pub fn view(&mut self, ctx: &egui::Context) {
+        self.import_options_window(ctx);
+        self.shell_export_window(ctx);
+        Self::status_window(ctx);
+    }
+
+    fn import_options_window(&mut self, ctx: &egui::Context) {
+        let Some(path) = with_library_state(|state| state.import_path.read().unwrap().clone())
+        else {
+            return;
+        };
+        let mut confirmed = false;
+        let mut cancelled = false;
+
+        egui::Window::new("Import Library")
+            .id(egui::Id::new("library_import_window"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(path.display().to_string()).monospace());
+                ui.add_space(10.0);
+
+                ui.radio_value(&mut self.replace, false, "Merge into the current library");
+                ui.add_enabled_ui(!self.replace, |ui| {
+                    ui.indent("conflict_policy", |ui| {
+                        ui.label("When a folder already has a script of the same name:");
+                        ui.radio_value(
+                            &mut self.conflict_policy,
+                            ConflictPolicy::Skip,
+                            "Keep the existing script",
+                        );
+                        ui.radio_value(
+                            &mut self.conflict_policy,
+                            ConflictPolicy::Overwrite,
+                            "Overwrite it with the imported one",
+                        );
+                        ui.radio_value(
+                            &mut self.conflict_policy,
+                            ConflictPolicy::KeepBoth,
+                            "Keep both, the imported one is renamed",
+                        );
+                    });
+                });
+                ui.radio_value(&mut self.replace, true, "Replace the current library");
+                if self.replace {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        "All folders, scripts and run history will be deleted first.",
+                    );
+                }
+
+                ui.add_space(20.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        cancelled = true;
+                    }
+                    if ui.button("Import").clicked() {
+                        confirmed = true;
+                    }
+                });
+            });
+
+        if confirmed {
+            let mode = if self.replace {
+                ImportMode::Replace
+            } else {
+                ImportMode::Merge(self.conflict_policy)
+            };
+            if let Some(path) = with_library_state_reducer(|r| r.take_import_path()) {
+                dispatch_library_command(LibraryCommand::Import { path, mode });
+            }
+        } else if cancelled {
+            with_library_state_reducer(|r| r.take_import_path());
+        }
+    }
+
+    fn shell_export_window(&mut self, ctx: &egui::Context) {
+        let Some((folder_id, folder_name)) =
+            with_library_state(|state| state.shell_export_folder.read().unwrap().clone())
+        else {
+            return;
+        };
+        let mut choose_directory = false;
+        let mut cancelled = false;
+
+        egui::Window::new(format!("Export \"{}\" as Shell Scripts", folder_name))
+            .id(egui::Id::new("shell_export_window"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label("Every script is written to its own executable file.");
+                ui.checkbox(
+                    &mut self.with_menu,
+                    format!("Also write {} to pick and run them", MENU_FILE_NAME),
+                );
+                ui.add_space(20.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        cancelled = true;
+                    }
+                    if ui.button("Choose Directory…").clicked() {
+                        choose_directory = true;
+                    }
+                });
+            });
+
+        if choose_directory {
+            let directory = rfd::FileDialog::new()
+                .set_title("Export as Shell Scripts")
+                .set_file_name(&folder_name)
+                .pick_folder();
+            // Keeps the window open when the dialog was dismissed
+            if let Some(directory) = directory {
+                with_library_state_reducer(|r| r.take_shell_export());
+                dispatch_library_command(LibraryCommand::ExportFolderAsShellScripts {
+                    folder_id,
+                    directory,
+                    with_menu: self.with_menu,
+                });
+            }
+        } else if cancelled {
+            with_library_state_reducer(|r| r.take_shell_export());
+        }
+    }
+
+    fn status_window(ctx: &egui::Context) {
+        let Some(message) =
+            with_library_state(|state| state.status_message.read().unwrap().clone())
+        else {
+            return;
+        };
+        egui::Window::new("Library")
+            .id(egui::Id::new("library_status_window"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(message);
+                ui.add_space(10.0);
+                if ui.button("OK").clicked() {
+                    with_library_state_reducer(|r| r.dismiss_status_message());
+                }
+            });
+    }
+}
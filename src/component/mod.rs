@@ -1,4 +1,10 @@
+pub mod command_palette;
 mod common;
+pub mod history_import_window;
 pub mod left_folders_col;
+pub mod library_import_window;
+pub mod output_console;
+pub mod placeholder_form_window;
 pub mod right_scripts_col;
+pub mod toast_overlay;
 pub mod top_menu;
@@ -0,0 +1 @@
+pub mod output_console;
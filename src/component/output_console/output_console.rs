@@ -0,0 +1,181 @@
+use crate::domain::execution::execution_command_handler::ExecutionCommand;
+use crate::state::execution_state::{OutputStream, RunStatus, ScriptRunOutput};
+use crate::{dispatch_execution_command, with_execution_state, with_execution_state_reducer};
+use egui::{Color32, Ui};
+use std::time::Duration;
+
+pub struct OutputConsole;
+
+impl OutputConsole {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn view(&self, ctx: &egui::Context) {
+        let (console_open, runs, selected_run_id) = with_execution_state(|state| {
+            (
+                *state.console_open.read().unwrap(),
+                state.runs.read().unwrap().clone(),
+                *state.selected_run_id.read().unwrap(),
+            )
+        });
+
+        // Output arrives through the event channel, keep frames coming while anything runs
+        if runs.iter().any(|r| r.is_running()) {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+
+        if !console_open {
+            return;
+        }
+
+        egui::TopBottomPanel::bottom("Output Console")
+            .resizable(true)
+            .default_height(220.0)
+            .height_range(80.0..=800.0)
+            .show(ctx, |ui| {
+                ui.add_space(4.0);
+                Self::header(ui, &runs, selected_run_id);
+                ui.separator();
+
+                let selected_run = runs
+                    .iter()
+                    .find(|r| Some(r.run_id) == selected_run_id)
+                    .or(runs.last());
+                match selected_run {
+                    Some(run) => Self::run_output(ui, run),
+                    None => {
+                        ui.label("Nothing has been executed yet...");
+                    }
+                }
+            });
+    }
+
+    fn header(ui: &mut Ui, runs: &[ScriptRunOutput], selected_run_id: Option<u64>) {
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new("Output").strong());
+            ui.separator();
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button("✖").on_hover_text("Hide console").clicked() {
+                    with_execution_state_reducer(|r| r.set_console_open(false));
+                }
+                if ui.button("Clear finished").clicked() {
+                    with_execution_state_reducer(|r| r.clear_finished_runs());
+                }
+                ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
+                    egui::ScrollArea::horizontal()
+                        .id_salt("output_console_run_tabs")
+                        .show(ui, |ui| {
+                            for run in runs.iter().rev() {
+                                let is_selected = Some(run.run_id) == selected_run_id;
+                                let tab_text = format!(
+                                    "{} {}",
+                                    Self::status_icon(&run.status),
+                                    run.script_name
+                                );
+                                if ui.selectable_label(is_selected, tab_text).clicked() {
+                                    with_execution_state_reducer(|r| r.select_run(run.run_id));
+                                }
+                            }
+                        });
+                });
+            });
+        });
+    }
+
+    fn run_output(ui: &mut Ui, run: &ScriptRunOutput) {
+        ui.horizontal(|ui| {
+            ui.label(Self::status_text(&run.status));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if run.is_running() {
+                    let stop_button = ui.add_enabled(
+                        !run.stop_requested,
+                        egui::Button::new(if run.stop_requested {
+                            "Stopping..."
+                        } else {
+                            "Stop"
+                        }),
+                    );
+                    if stop_button.clicked() {
+                        dispatch_execution_command(ExecutionCommand::StopRun {
+                            run_id: run.run_id,
+                        });
+                    }
+                }
+                if ui.button("Copy output").clicked() {
+                    let text = run
+                        .lines
+                        .iter()
+                        .map(|l| l.text.as_str())
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    ui.ctx().copy_text(text);
+                }
+            });
+        });
+        ui.add_space(4.0);
+
+        let stderr_color = ui.visuals().error_fg_color;
+        // Every row is a single unwrapped monospace line, so only the visible ones get laid out
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        let notice_rows = usize::from(run.truncated_lines > 0);
+        egui::Frame::NONE
+            .fill(ui.visuals().code_bg_color)
+            .inner_margin(6.0)
+            .show(ui, |ui| {
+                egui::ScrollArea::vertical()
+                    .id_salt(("output_console_lines", run.run_id))
+                    .stick_to_bottom(true)
+                    .auto_shrink([false, false])
+                    .show_rows(
+                        ui,
+                        row_height,
+                        notice_rows + run.lines.len(),
+                        |ui, row_range| {
+                            for row in row_range {
+                                if row < notice_rows {
+                                    ui.label(
+                                        egui::RichText::new(format!(
+                                            "... {} earlier lines truncated",
+                                            run.truncated_lines
+                                        ))
+                                        .monospace()
+                                        .italics()
+                                        .color(Color32::GRAY),
+                                    );
+                                    continue;
+                                }
+                                let line = &run.lines[row - notice_rows];
+                                let text = egui::RichText::new(&line.text).monospace();
+                                let text = match line.stream {
+                                    OutputStream::Stdout => text,
+                                    OutputStream::Stderr => text.color(stderr_color),
+                                };
+                                ui.add(
+                                    egui::Label::new(text).wrap_mode(egui::TextWrapMode::Extend),
+                                );
+                            }
+                        },
+                    );
+            });
+    }
+
+    fn status_icon(status: &RunStatus) -> &'static str {
+        match status {
+            RunStatus::Running => "⏳",
+            RunStatus::Finished { exit_code: Some(0) } => "✅",
+            RunStatus::Finished { .. } | RunStatus::FailedToStart { .. } => "❌",
+        }
+    }
+
+    fn status_text(status: &RunStatus) -> String {
+        match status {
+            RunStatus::Running => "Running...".to_string(),
+            RunStatus::Finished {
+                exit_code: Some(code),
+            } => format!("Exited with code {}", code),
+            RunStatus::Finished { exit_code: None } => "Terminated by signal".to_string(),
+            RunStatus::FailedToStart { error } => format!("Failed to start: {}", error),
+        }
+    }
+}
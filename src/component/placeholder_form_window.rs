@@ -0,0 +1,94 @@
+use crate::domain::execution::execution_command_handler::ExecutionCommand;
+use crate::domain::execution::placeholder::render_command;
+use crate::{dispatch_execution_command, with_execution_state, with_execution_state_reducer};
+use std::collections::HashMap;
+
+pub fn placeholder_form_window(ctx: &egui::Context) {
+    let Some(form) = with_execution_state(|state| state.placeholder_form.read().unwrap().clone())
+    else {
+        return;
+    };
+    let mut values = form.values.clone();
+    let mut submitted = false;
+    let mut cancelled = false;
+
+    egui::Window::new(format!("Run: {}", form.execution.script_name))
+        .id(egui::Id::new("placeholder_form_window"))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            egui::Grid::new("placeholder_form_grid")
+                .num_columns(2)
+                .spacing([12.0, 8.0])
+                .show(ui, |ui| {
+                    for (index, placeholder) in form.placeholders.iter().enumerate() {
+                        let Some(value) = values.get_mut(index) else {
+                            continue;
+                        };
+                        ui.label(&placeholder.name);
+                        if placeholder.choices.is_empty() {
+                            ui.text_edit_singleline(value);
+                        } else {
+                            egui::ComboBox::from_id_salt(("placeholder_choice", index))
+                                .selected_text(value.as_str())
+                                .show_ui(ui, |ui| {
+                                    for choice in placeholder.choices.iter() {
+                                        ui.selectable_value(value, choice.clone(), choice);
+                                    }
+                                });
+                        }
+                        ui.end_row();
+                    }
+                });
+
+            ui.add_space(10.0);
+            ui.label("Command:");
+            let preview = render_command(&form.execution.command, &named_values(&form, &values));
+            egui::Frame::NONE
+                .fill(ui.visuals().code_bg_color)
+                .show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut preview.as_str())
+                            .font(egui::TextStyle::Monospace)
+                            .desired_rows(3)
+                            .desired_width(480.0),
+                    );
+                });
+
+            let enter_pressed = ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let escape_pressed = ui.input(|i| i.key_pressed(egui::Key::Escape));
+            ui.add_space(20.0);
+            ui.horizontal(|ui| {
+                if ui.button("Cancel").clicked() || escape_pressed {
+                    cancelled = true;
+                }
+                if ui.button("Run").clicked() || enter_pressed {
+                    submitted = true;
+                }
+            });
+        });
+
+    if submitted {
+        dispatch_execution_command(ExecutionCommand::RunWithPlaceholderValues {
+            values: named_values(&form, &values).into_iter().collect(),
+            execution: form.execution,
+        });
+        with_execution_state_reducer(|r| r.close_placeholder_form());
+    } else if cancelled {
+        with_execution_state_reducer(|r| r.close_placeholder_form());
+    } else if values != form.values {
+        with_execution_state_reducer(|r| r.set_placeholder_values(values));
+    }
+}
+
+fn named_values(
+    form: &crate::state::execution_state::PlaceholderForm,
+    values: &[String],
+) -> HashMap<String, String> {
+    form.placeholders
+        .iter()
+        .zip(values.iter())
+        .map(|(placeholder, value)| (placeholder.name.clone(), value.clone()))
+        .collect()
+}
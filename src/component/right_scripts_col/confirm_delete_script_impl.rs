@@ -1,4 +1,5 @@
 use crate::component::right_scripts_col::scripts_col::ScriptsColumn;
+use crate::component::right_scripts_col::scripts_of_selected_folder::folders_of_script;
 use crate::dispatch_folder_command;
 use crate::domain::folder::folder_command_handler::FolderCommand;
 use egui::Ui;
@@ -18,6 +19,15 @@ impl ScriptsColumn {
                                 "Are you sure you want to delete this script: \"{}\"?",
                                 script.name
                             ));
+                            let folder_count = folders_of_script(script).len();
+                            if folder_count > 1 {
+                                ui.label(format!(
+                                    "It is shared by {} folders and will be removed from all of them. \
+                                     Use \"Remove from this folder\" to keep it in the others.",
+                                    folder_count
+                                ));
+                            }
+                            ui.weak("It can be restored from the Trash.");
                             ui.add_space(20.0);
                             ui.horizontal(|ui| {
                                 if ui.button("Cancel").clicked() {
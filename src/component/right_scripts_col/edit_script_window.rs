@@ -1,9 +1,26 @@
+use crate::component::common::interpreter_picker::interpreter_picker;
+use crate::component::common::markdown_view::markdown_view;
+use crate::component::common::tag_chips::tag_chips;
 use crate::component::right_scripts_col::scripts_col::ScriptsColumn;
+use crate::db::repository::script_repository::ScriptDetails;
 use crate::dispatch_folder_command;
+use crate::domain::execution::script_execution::ScriptExecution;
 use crate::domain::folder::folder_command_handler::FolderCommand;
+use crate::domain::folder::script_tags::{parse_tag_list, tags_of_script};
 use egui::Ui;
 
 impl ScriptsColumn {
+    pub fn begin_edit(&mut self, script: &crate::prisma::shell_script::Data) {
+        self.editing_script_id = Some(script.id);
+        self.editing_command = script.command.clone();
+        self.editing_description = script.description.clone();
+        let execution = ScriptExecution::from(script);
+        self.editing_working_directory = execution.working_directory.unwrap_or_default();
+        self.editing_env_vars = execution.env_vars;
+        self.editing_interpreter_settings = execution.interpreter_settings;
+        self.editing_tags = tags_of_script(script).join(", ");
+    }
+
     pub fn edit_script_window(&mut self, ui: &mut Ui, script_id: i32) {
         egui::Window::new("Edit Script")
             .collapsible(false)
@@ -12,6 +29,8 @@ impl ScriptsColumn {
             .default_width(600.0)
             .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
             .show(ui.ctx(), |ui| {
+                interpreter_picker(ui, "edit_script", &mut self.editing_interpreter_settings);
+                ui.add_space(10.0);
                 ui.add(
                     egui::TextEdit::multiline(&mut self.editing_command)
                         .font(egui::TextStyle::Monospace)
@@ -19,19 +38,127 @@ impl ScriptsColumn {
                         .desired_rows(20)
                         .desired_width(580.0),
                 );
+                ui.add_space(10.0);
+                self.description_editor(ui);
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    ui.label("Working directory:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.editing_working_directory)
+                            .hint_text("~/projects/my-app")
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+                ui.add_space(10.0);
+                self.tags_editor(ui);
+                ui.add_space(10.0);
+                self.env_vars_editor(ui);
                 ui.add_space(20.0);
                 ui.horizontal(|ui| {
                     if ui.button("Cancel").clicked() {
                         self.editing_script_id = None;
                     }
                     if ui.button("Save").clicked() {
-                        dispatch_folder_command(FolderCommand::UpdateScript {
+                        let working_directory = self.editing_working_directory.trim();
+                        dispatch_folder_command(FolderCommand::UpdateScriptDetails {
                             script_id,
-                            new_command: self.editing_command.clone(),
+                            details: ScriptDetails {
+                                command: self.editing_command.clone(),
+                                description: self.editing_description.trim().to_string(),
+                                interpreter_settings: self.editing_interpreter_settings.clone(),
+                                working_directory: (!working_directory.is_empty())
+                                    .then(|| working_directory.to_string()),
+                                env_vars: self
+                                    .editing_env_vars
+                                    .iter()
+                                    .map(|(key, value)| (key.trim().to_string(), value.clone()))
+                                    .filter(|(key, _)| !key.is_empty())
+                                    .collect(),
+                                tags: parse_tag_list(&self.editing_tags),
+                            },
                         });
                         self.editing_script_id = None;
                     }
                 });
             });
     }
+
+    fn description_editor(&mut self, ui: &mut Ui) {
+        ui.label("Description (Markdown):");
+        ui.add(
+            egui::TextEdit::multiline(&mut self.editing_description)
+                .hint_text("What it does and when to use it")
+                .desired_rows(4)
+                .desired_width(580.0),
+        );
+        if !self.editing_description.trim().is_empty() {
+            egui::CollapsingHeader::new("Preview")
+                .id_salt("edit_script_description_preview")
+                .show(ui, |ui| markdown_view(ui, &self.editing_description));
+        }
+    }
+
+    fn tags_editor(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Tags:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.editing_tags)
+                    .hint_text("prod, deploy")
+                    .desired_width(f32::INFINITY),
+            );
+        });
+        // Tags of other scripts, one click away
+        let current_tags = parse_tag_list(&self.editing_tags);
+        let known_tags = crate::with_folder_state(|state| state.tag_list.read().unwrap().clone());
+        let suggestions: Vec<&str> = known_tags
+            .iter()
+            .filter(|tag| !current_tags.contains(tag))
+            .map(|tag| tag.as_str())
+            .collect();
+        if suggestions.is_empty() {
+            return;
+        }
+        ui.horizontal_wrapped(|ui| {
+            ui.weak("Add:");
+            if let Some(tag) = tag_chips(ui, &suggestions) {
+                let mut tags = current_tags;
+                tags.push(tag);
+                self.editing_tags = tags.join(", ");
+            }
+        });
+    }
+
+    fn env_vars_editor(&mut self, ui: &mut Ui) {
+        ui.label("Environment variables:");
+        let mut row_to_remove = None;
+        egui::Grid::new("edit_script_env_vars_grid")
+            .num_columns(3)
+            .spacing([8.0, 4.0])
+            .show(ui, |ui| {
+                for (index, (key, value)) in self.editing_env_vars.iter_mut().enumerate() {
+                    ui.add(
+                        egui::TextEdit::singleline(key)
+                            .font(egui::TextStyle::Monospace)
+                            .hint_text("KEY")
+                            .desired_width(180.0),
+                    );
+                    ui.add(
+                        egui::TextEdit::singleline(value)
+                            .font(egui::TextStyle::Monospace)
+                            .hint_text("value")
+                            .desired_width(320.0),
+                    );
+                    if ui.button("🗑").on_hover_text("Remove variable").clicked() {
+                        row_to_remove = Some(index);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(index) = row_to_remove {
+            self.editing_env_vars.remove(index);
+        }
+        if ui.button("+ Add variable").clicked() {
+            self.editing_env_vars.push((String::new(), String::new()));
+        }
+    }
 }
@@ -1,6 +1,13 @@
 mod confirm_delete_script_impl;
 mod edit_script_window;
 mod new_script_window;
+mod pinned_scripts;
 mod rename_script_window;
+mod revision_history_window;
+mod run_history_window;
+mod script_folders_window;
+mod script_search;
+mod script_tag_filter;
 pub mod scripts_col;
-mod scripts_of_selected_folder;
+pub mod scripts_of_selected_folder;
+mod trash_list;
@@ -1,3 +1,4 @@
+use crate::component::common::interpreter_picker::interpreter_picker;
 use crate::component::right_scripts_col::scripts_col::ScriptsColumn;
 use crate::dispatch_folder_command;
 use crate::domain::folder::folder_command_handler::FolderCommand;
@@ -47,6 +48,11 @@ impl ScriptsColumn {
             .default_width(600.0)
             .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
             .show(ui.ctx(), |ui| {
+                if interpreter_picker(ui, "add_script", &mut self.adding_interpreter_settings) {
+                    // Syntax highlighting follows the interpreter
+                    self.code_lang = self.adding_interpreter_settings.code_lang();
+                }
+                ui.add_space(10.0);
                 ui.add(
                     egui::TextEdit::multiline(&mut self.adding_code)
                         .font(egui::TextStyle::Monospace) // for cursor height
@@ -65,6 +71,7 @@ impl ScriptsColumn {
                             folder_id: selected_folder.unwrap().id,
                             name: "New Script".into(),
                             command: self.adding_code.clone(),
+                            interpreter_settings: self.adding_interpreter_settings.clone(),
                         });
                         self.adding_new_script = false;
                     }
@@ -0,0 +1,129 @@
+use crate::component::common::tag_chips::tag_chips;
+use crate::component::right_scripts_col::script_tag_filter::apply_tag_filter;
+use crate::component::right_scripts_col::scripts_col::ScriptsColumn;
+use crate::component::right_scripts_col::scripts_of_selected_folder::folders_of_script;
+use crate::dispatch_folder_command;
+use crate::domain::execution::script_execution::ScriptExecution;
+use crate::domain::folder::folder_command_handler::FolderCommand;
+use crate::domain::folder::script_tags::tags_of_script;
+use crate::prisma::shell_script::Data;
+use eframe::epaint::Color32;
+use egui::{Id, Response, Ui};
+
+// Dragged by the " :: " handle of a pinned card, only reorders the "Pinned" list
+#[derive(Clone, PartialEq, Eq, Copy, Debug)]
+struct PinnedDragPayload {
+    row_index: usize,
+}
+
+impl ScriptsColumn {
+    pub fn pinned_scripts(&mut self, ui: &mut Ui) {
+        let (scripts, tag_filter) = crate::with_folder_state(|state| {
+            (
+                state.pinned_scripts.read().unwrap().clone(),
+                state.tag_filter.read().unwrap().clone(),
+            )
+        });
+        if scripts.is_empty() {
+            ui.weak("Nothing pinned yet, use 📌 on a script or drop it onto \"Pinned\"");
+            return;
+        }
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (row_idx, script) in scripts.iter().enumerate() {
+                if let Some(tag) = tag_filter.as_deref()
+                    && !tags_of_script(script).contains(&tag)
+                {
+                    continue;
+                }
+                let payload = PinnedDragPayload { row_index: row_idx };
+                let response = self.pinned_script_item(ui, script, payload);
+                if let Some(pointer) = ui.input(|i| i.pointer.interact_pos())
+                    && let Some(hovered_payload) = response.dnd_hover_payload::<PinnedDragPayload>()
+                    && hovered_payload.row_index != row_idx
+                {
+                    let rect = response.rect;
+                    let stroke = egui::Stroke::new(2.0, Color32::from_black_alpha(60));
+                    let insert_row_idx = if pointer.y < rect.center().y {
+                        ui.painter().hline(rect.x_range(), rect.top() - 3.0, stroke);
+                        row_idx
+                    } else {
+                        ui.painter()
+                            .hline(rect.x_range(), rect.bottom() + 3.0, stroke);
+                        row_idx + 1
+                    };
+                    if let Some(dragged_payload) =
+                        response.dnd_release_payload::<PinnedDragPayload>()
+                    {
+                        let from_index = dragged_payload.row_index;
+                        // The dragged script is taken out first, shifting the rows below it
+                        let to_index = if insert_row_idx > from_index {
+                            insert_row_idx - 1
+                        } else {
+                            insert_row_idx
+                        };
+                        if from_index != to_index {
+                            dispatch_folder_command(FolderCommand::ReorderPinnedScripts {
+                                from_index,
+                                to_index,
+                            });
+                        }
+                    }
+                }
+                ui.add_space(6.0);
+            }
+        });
+    }
+
+    fn pinned_script_item(
+        &mut self,
+        ui: &mut Ui,
+        script: &Data,
+        payload: PinnedDragPayload,
+    ) -> Response {
+        let frame = egui::Frame::group(ui.style()).fill(ui.visuals().faint_bg_color);
+        let frame_response = frame.show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.horizontal(|ui| {
+                ui.dnd_drag_source(Id::new(("pinned_drag_source", script.id)), payload, |ui| {
+                    ui.label(egui::RichText::new(" :: "));
+                });
+                ui.label(egui::RichText::new(&script.name).strong().size(16.0));
+                let folder_names: Vec<&str> = folders_of_script(script)
+                    .into_iter()
+                    .map(|folder| folder.name.as_str())
+                    .collect();
+                ui.weak(folder_names.join(", "));
+                if let Some(tag) = tag_chips(ui, &tags_of_script(script)) {
+                    apply_tag_filter(Some(tag), true);
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Execute").clicked() {
+                        crate::execute_script(ScriptExecution::from(script));
+                    }
+                    if ui.button("Edit").clicked() {
+                        self.begin_edit(script);
+                    }
+                    pin_button(ui, script);
+                });
+            });
+            let first_line = script.command.lines().next().unwrap_or_default();
+            ui.label(egui::RichText::new(first_line).monospace().weak());
+        });
+        frame_response.response
+    }
+}
+
+// Toggles whether the script is listed under "Pinned"
+pub fn pin_button(ui: &mut Ui, script: &Data) {
+    let hover_text = if script.pinned { "Unpin" } else { "Pin" };
+    if ui
+        .selectable_label(script.pinned, "📌")
+        .on_hover_text(hover_text)
+        .clicked()
+    {
+        dispatch_folder_command(FolderCommand::SetScriptPinned {
+            script_id: script.id,
+            pinned: !script.pinned,
+        });
+    }
+}
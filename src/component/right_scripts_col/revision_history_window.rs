@@ -0,0 +1,145 @@
+use crate::component::right_scripts_col::scripts_col::ScriptsColumn;
+use crate::dispatch_folder_command;
+use crate::domain::folder::folder_command_handler::FolderCommand;
+use crate::domain::folder::script_diff::{DiffLine, DiffLineKind, unified_diff};
+use crate::prisma::shell_script;
+use crate::prisma::shell_script_revision::Data;
+use egui::{Color32, RichText, Ui};
+
+impl ScriptsColumn {
+    pub fn revision_history_window(&mut self, ui: &mut Ui, script_id: i32) {
+        let (script, revisions) = crate::with_folder_state(|state| {
+            (
+                state.revision_history_script.read().unwrap().clone(),
+                state.script_revisions.read().unwrap().clone(),
+            )
+        });
+        let title = script
+            .as_ref()
+            .map(|script| format!("Versions: {}", script.name))
+            .unwrap_or_else(|| "Versions".to_string());
+
+        let mut open = true;
+        egui::Window::new(title)
+            .id(egui::Id::new("revision_history_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_height(450.0)
+            .default_width(820.0)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ui.ctx(), |ui| {
+                let Some(script) = script else {
+                    ui.spinner();
+                    return;
+                };
+                if revisions.is_empty() {
+                    ui.label("The name and command of this script have never been changed...");
+                    return;
+                }
+                let selected_revision = revisions
+                    .iter()
+                    .find(|r| Some(r.id) == self.selected_revision_id)
+                    .or(revisions.first());
+                ui.columns(2, |columns| {
+                    if let [revisions_col, diff_col] = columns {
+                        self.revision_list(revisions_col, &revisions);
+                        if let Some(revision) = selected_revision {
+                            Self::revision_diff(diff_col, revision, &script);
+                        }
+                    }
+                });
+                ui.separator();
+                if let Some(revision) = selected_revision
+                    && ui.button("Restore this version").clicked()
+                {
+                    dispatch_folder_command(FolderCommand::RestoreScriptRevision {
+                        script_id,
+                        revision_id: revision.id,
+                    });
+                    // The replaced version is listed on top once the restore went through
+                    self.selected_revision_id = None;
+                }
+            });
+        if !open {
+            crate::with_folder_state_reducer(|r| r.close_revision_history());
+            self.selected_revision_id = None;
+        }
+    }
+
+    fn revision_list(&mut self, ui: &mut Ui, revisions: &[Data]) {
+        egui::ScrollArea::vertical()
+            .id_salt("revision_history_list")
+            .max_height(360.0)
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                let selected_id = self
+                    .selected_revision_id
+                    .or(revisions.first().map(|r| r.id));
+                for revision in revisions.iter() {
+                    let text = format!("{}  ·  {}", revision.created_at_hk, revision.name);
+                    if ui
+                        .selectable_label(selected_id == Some(revision.id), text)
+                        .clicked()
+                    {
+                        self.selected_revision_id = Some(revision.id);
+                    }
+                }
+            });
+    }
+
+    // What changed from the revision to the current version
+    fn revision_diff(ui: &mut Ui, revision: &Data, script: &shell_script::Data) {
+        if revision.name != script.name {
+            ui.horizontal(|ui| {
+                ui.label("Name:");
+                ui.label(RichText::new(&revision.name).strikethrough().weak());
+                ui.label("→");
+                ui.label(RichText::new(&script.name).strong());
+            });
+        }
+        let hunks = unified_diff(&revision.command, &script.command);
+        if hunks.is_empty() {
+            ui.weak("Same command as now");
+            return;
+        }
+        egui::ScrollArea::both()
+            .id_salt(("revision_diff", revision.id))
+            .max_height(340.0)
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                for (index, hunk) in hunks.iter().enumerate() {
+                    if index > 0 {
+                        ui.weak("⋯");
+                    }
+                    for line in hunk {
+                        diff_line(ui, line);
+                    }
+                }
+            });
+    }
+}
+
+fn diff_line(ui: &mut Ui, line: &DiffLine) {
+    let (sign, background) = match line.kind {
+        DiffLineKind::Unchanged => (" ", Color32::TRANSPARENT),
+        DiffLineKind::Removed => ("-", Color32::from_rgba_unmultiplied(220, 50, 50, 40)),
+        DiffLineKind::Added => ("+", Color32::from_rgba_unmultiplied(50, 180, 80, 40)),
+    };
+    let line_number = |number: Option<usize>| {
+        number
+            .map(|n| format!("{:>4}", n))
+            .unwrap_or_else(|| "    ".to_string())
+    };
+    let text = format!(
+        "{} {} {} {}",
+        line_number(line.old_line),
+        line_number(line.new_line),
+        sign,
+        line.text
+    );
+    egui::Frame::NONE.fill(background).show(ui, |ui| {
+        ui.set_width(ui.available_width());
+        ui.label(RichText::new(text).monospace());
+    });
+}
@@ -0,0 +1,170 @@
+use crate::component::right_scripts_col::scripts_col::ScriptsColumn;
+use crate::dispatch_execution_command;
+use crate::domain::execution::execution_command_handler::{ExecutionCommand, RunHistoryPrune};
+use crate::prisma::script_run::Data;
+use egui::Ui;
+
+impl ScriptsColumn {
+    pub fn run_history_window(&mut self, ui: &mut Ui, script_id: i32) {
+        let runs = crate::with_execution_state(|state| state.run_history.read().unwrap().clone());
+        let script_name =
+            crate::component::right_scripts_col::scripts_col::with_scritps_from_selected_folder(
+                |scripts| {
+                    scripts
+                        .iter()
+                        .find(|s| s.id == script_id)
+                        .map(|s| s.name.clone())
+                },
+            )
+            .unwrap_or_default();
+
+        egui::Window::new(format!("Run History: {}", script_name))
+            .id(egui::Id::new("run_history_window"))
+            .collapsible(false)
+            .resizable(true)
+            .default_height(450.0)
+            .default_width(760.0)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ui.ctx(), |ui| {
+                if runs.is_empty() {
+                    ui.label("This script has not been run yet...");
+                } else {
+                    ui.columns(2, |columns| {
+                        if let [runs_col, output_col] = columns {
+                            self.run_list(runs_col, &runs);
+                            let selected_run = runs
+                                .iter()
+                                .find(|r| Some(r.id) == self.selected_history_run_id)
+                                .or(runs.first());
+                            if let Some(run) = selected_run {
+                                Self::run_detail(output_col, run);
+                            }
+                        }
+                    });
+                }
+                ui.separator();
+                self.prune_controls(ui, script_id);
+                ui.add_space(10.0);
+                if ui.button("Close").clicked() {
+                    crate::with_execution_state_reducer(|r| r.close_run_history());
+                    self.selected_history_run_id = None;
+                }
+            });
+    }
+
+    fn run_list(&mut self, ui: &mut Ui, runs: &[Data]) {
+        egui::ScrollArea::vertical()
+            .id_salt("run_history_list")
+            .max_height(320.0)
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                let selected_id = self.selected_history_run_id.or(runs.first().map(|r| r.id));
+                for run in runs.iter() {
+                    let text = format!(
+                        "{} {}  ·  {}  ·  {}",
+                        run_status_icon(run),
+                        run.started_at_hk,
+                        run_duration_text(run),
+                        run_exit_text(run)
+                    );
+                    if ui
+                        .selectable_label(selected_id == Some(run.id), text)
+                        .clicked()
+                    {
+                        self.selected_history_run_id = Some(run.id);
+                    }
+                }
+            });
+    }
+
+    fn run_detail(ui: &mut Ui, run: &Data) {
+        ui.label(format!("Shell: {}", run.shell));
+        ui.label("Command:");
+        egui::Frame::NONE
+            .fill(ui.visuals().code_bg_color)
+            .show(ui, |ui| {
+                ui.add(
+                    egui::TextEdit::multiline(&mut run.command.clone())
+                        .font(egui::TextStyle::Monospace)
+                        .interactive(false)
+                        .frame(false)
+                        .desired_rows(2)
+                        .desired_width(f32::INFINITY),
+                );
+            });
+        ui.add_space(4.0);
+        ui.label("Output:");
+        egui::ScrollArea::vertical()
+            .id_salt(("run_history_output", run.id))
+            .max_height(220.0)
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                ui.add(
+                    egui::TextEdit::multiline(&mut run.output.clone())
+                        .font(egui::TextStyle::Monospace)
+                        .interactive(false)
+                        .desired_width(f32::INFINITY),
+                );
+            });
+    }
+
+    fn prune_controls(&mut self, ui: &mut Ui, script_id: i32) {
+        let scope = if self.prune_all_scripts {
+            None
+        } else {
+            Some(script_id)
+        };
+        ui.horizontal(|ui| {
+            ui.label("Keep latest");
+            ui.add(egui::DragValue::new(&mut self.prune_keep_latest).range(0..=10000));
+            ui.label("runs");
+            if ui.button("Prune").clicked() {
+                dispatch_execution_command(ExecutionCommand::PruneRunHistory {
+                    script_id: scope,
+                    prune: RunHistoryPrune::KeepLatest(self.prune_keep_latest),
+                });
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Delete runs older than");
+            ui.add(egui::DragValue::new(&mut self.prune_older_than_days).range(0..=3650));
+            ui.label("days");
+            if ui.button("Prune").clicked() {
+                dispatch_execution_command(ExecutionCommand::PruneRunHistory {
+                    script_id: scope,
+                    prune: RunHistoryPrune::OlderThanDays(self.prune_older_than_days),
+                });
+            }
+        });
+        ui.checkbox(&mut self.prune_all_scripts, "Apply to all scripts");
+    }
+}
+
+fn run_status_icon(run: &Data) -> &'static str {
+    match (run.finished_at, run.exit_code) {
+        (None, _) => "⏳",
+        (Some(_), Some(0)) => "✅",
+        (Some(_), _) => "❌",
+    }
+}
+
+fn run_exit_text(run: &Data) -> String {
+    match (run.finished_at, run.exit_code) {
+        (None, _) => "running".to_string(),
+        (Some(_), Some(code)) => format!("exit {}", code),
+        (Some(_), None) => "no exit code".to_string(),
+    }
+}
+
+fn run_duration_text(run: &Data) -> String {
+    let Some(finished_at) = run.finished_at else {
+        return "-".to_string();
+    };
+    let seconds = ((finished_at - run.started_at) / 1000.0).max(0.0);
+    if seconds < 60.0 {
+        format!("{:.1}s", seconds)
+    } else {
+        let total_seconds = seconds.round() as u64;
+        format!("{}m {:02}s", total_seconds / 60, total_seconds % 60)
+    }
+}
@@ -0,0 +1,79 @@
+use crate::component::right_scripts_col::scripts_col::{
+    ScriptsColumn, with_scritps_from_selected_folder,
+};
+use crate::component::right_scripts_col::scripts_of_selected_folder::folders_of_script;
+use crate::dispatch_folder_command;
+use crate::domain::folder::folder_command_handler::FolderCommand;
+use crate::domain::folder::folder_tree::visible_rows;
+use egui::Ui;
+use std::collections::HashSet;
+
+impl ScriptsColumn {
+    // "Add to folder…": one checkbox per folder, the script is shared rather than copied
+    pub fn script_folders_window(&mut self, ui: &mut Ui, script_id: i32) {
+        let Some(script) = with_scritps_from_selected_folder(|scripts| {
+            scripts.iter().find(|s| s.id == script_id).cloned()
+        }) else {
+            // Removed from the open folder in the meantime
+            self.folders_script_id = None;
+            return;
+        };
+        let folders = crate::with_folder_state(|state| state.folder_list.read().unwrap().clone());
+        let member_ids: HashSet<i32> = folders_of_script(&script).iter().map(|f| f.id).collect();
+
+        let mut open = true;
+        egui::Window::new(format!("Folders of \"{}\"", script.name))
+            .id(egui::Id::new("script_folders_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ui.ctx(), |ui| {
+                ui.label(
+                    "The script is the same in every checked folder, edits show up in all of them.",
+                );
+                ui.add_space(10.0);
+                egui::ScrollArea::vertical()
+                    .max_height(360.0)
+                    .show(ui, |ui| {
+                        for row in visible_rows(&folders, &HashSet::new()) {
+                            let folder = row.folder;
+                            let is_member = member_ids.contains(&folder.id);
+                            // The last folder cannot be unchecked, Delete removes the script
+                            let can_toggle = !is_member || member_ids.len() > 1;
+                            ui.horizontal(|ui| {
+                                ui.add_space(row.depth as f32 * 16.0);
+                                let mut checked = is_member;
+                                if ui
+                                    .add_enabled(
+                                        can_toggle,
+                                        egui::Checkbox::new(&mut checked, &folder.name),
+                                    )
+                                    .changed()
+                                {
+                                    let command = if checked {
+                                        FolderCommand::LinkScriptToFolder {
+                                            script_id,
+                                            folder_id: folder.id,
+                                        }
+                                    } else {
+                                        FolderCommand::RemoveScriptFromFolder {
+                                            script_id,
+                                            folder_id: folder.id,
+                                        }
+                                    };
+                                    dispatch_folder_command(command);
+                                }
+                            });
+                        }
+                    });
+                ui.add_space(10.0);
+                if ui.button("Done").clicked() {
+                    self.folders_script_id = None;
+                }
+            });
+        if !open {
+            self.folders_script_id = None;
+        }
+    }
+}
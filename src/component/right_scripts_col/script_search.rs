@@ -0,0 +1,122 @@
+use crate::component::right_scripts_col::scripts_col::ScriptsColumn;
+use crate::db::repository::script_repository::{
+    SEARCH_HIGHLIGHT_END, SEARCH_HIGHLIGHT_START, ScriptSearchHit,
+};
+use crate::dispatch_folder_command;
+use crate::domain::execution::script_execution::ScriptExecution;
+use crate::domain::folder::folder_command_handler::FolderCommand;
+use egui::Ui;
+use egui::text::LayoutJob;
+
+impl ScriptsColumn {
+    pub fn script_search_box(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.search_query)
+                    .hint_text("🔍 Search all scripts...")
+                    .desired_width(ui.available_width() - 30.0),
+            );
+            let cleared = !self.search_query.is_empty() && ui.button("✖").clicked();
+            if cleared {
+                self.search_query.clear();
+            }
+            if response.changed() || cleared {
+                crate::with_folder_state_reducer(|r| r.set_script_search_query(&self.search_query));
+                if !self.search_query.trim().is_empty() {
+                    dispatch_folder_command(FolderCommand::SearchScripts {
+                        query: self.search_query.clone(),
+                    });
+                }
+            }
+        });
+    }
+
+    pub fn script_search_results(&mut self, ui: &mut Ui) {
+        let hits =
+            crate::with_folder_state(|state| state.script_search_hits.read().unwrap().clone());
+        if hits.is_empty() {
+            ui.weak("No matching scripts");
+            return;
+        }
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for hit in hits.iter() {
+                self.search_hit_item(ui, hit);
+                ui.add_space(4.0);
+            }
+        });
+    }
+
+    fn search_hit_item(&mut self, ui: &mut Ui, hit: &ScriptSearchHit) {
+        let frame = egui::Frame::group(ui.style()).fill(ui.visuals().faint_bg_color);
+        frame.show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.horizontal(|ui| {
+                ui.label(highlighted_text(
+                    ui,
+                    &hit.name_highlight,
+                    egui::FontId::proportional(16.0),
+                ));
+                let folder_names: Vec<&str> = hit
+                    .script
+                    .rel_scriptsfolder_shellscript()
+                    .map(|rels| {
+                        rels.iter()
+                            .filter_map(|rel| rel.scripts_folder().ok())
+                            .map(|folder| folder.name.as_str())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                ui.weak(folder_names.join(", "));
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Execute").clicked() {
+                        crate::execute_script(ScriptExecution::from(&hit.script));
+                    }
+                    if ui.button("Edit").clicked() {
+                        self.begin_edit(&hit.script);
+                    }
+                });
+            });
+            ui.label(highlighted_text(
+                ui,
+                &hit.command_snippet,
+                egui::FontId::monospace(12.0),
+            ));
+            if hit.description_snippet.contains(SEARCH_HIGHLIGHT_START) {
+                ui.label(highlighted_text(
+                    ui,
+                    &hit.description_snippet,
+                    egui::FontId::proportional(12.0),
+                ));
+            }
+        });
+    }
+}
+
+// Turns the markers placed by the FTS `highlight` / `snippet` functions into highlighted sections
+fn highlighted_text(ui: &Ui, marked: &str, font_id: egui::FontId) -> LayoutJob {
+    let normal = egui::TextFormat {
+        font_id: font_id.clone(),
+        color: ui.visuals().text_color(),
+        ..Default::default()
+    };
+    let highlighted = egui::TextFormat {
+        font_id,
+        color: ui.visuals().strong_text_color(),
+        background: ui.visuals().selection.bg_fill,
+        ..Default::default()
+    };
+
+    let mut job = LayoutJob::default();
+    let mut rest = marked;
+    while let Some(start) = rest.find(SEARCH_HIGHLIGHT_START) {
+        job.append(&rest[..start], 0.0, normal.clone());
+        rest = &rest[start + SEARCH_HIGHLIGHT_START.len_utf8()..];
+        let end = rest.find(SEARCH_HIGHLIGHT_END).unwrap_or(rest.len());
+        job.append(&rest[..end], 0.0, highlighted.clone());
+        rest = rest
+            .get(end + SEARCH_HIGHLIGHT_END.len_utf8()..)
+            .unwrap_or_default();
+    }
+    job.append(rest, 0.0, normal);
+    job
+}
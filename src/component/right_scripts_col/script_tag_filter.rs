@@ -0,0 +1,108 @@
+use crate::component::common::tag_chips::tag_chips;
+use crate::component::right_scripts_col::scripts_col::ScriptsColumn;
+use crate::component::right_scripts_col::scripts_of_selected_folder::folders_of_script;
+use crate::dispatch_folder_command;
+use crate::domain::execution::script_execution::ScriptExecution;
+use crate::domain::folder::folder_command_handler::FolderCommand;
+use crate::domain::folder::script_tags::tags_of_script;
+use crate::prisma::shell_script::Data;
+use egui::Ui;
+
+impl ScriptsColumn {
+    pub fn tag_filter_bar(&mut self, ui: &mut Ui) {
+        let (tags, tag_filter, all_folders) = crate::with_folder_state(|state| {
+            (
+                state.tag_list.read().unwrap().clone(),
+                state.tag_filter.read().unwrap().clone(),
+                *state.tag_filter_all_folders.read().unwrap(),
+            )
+        });
+        if tags.is_empty() && tag_filter.is_none() {
+            return;
+        }
+
+        let mut selected_tag = tag_filter.clone();
+        let mut selected_all_folders = all_folders;
+        ui.horizontal(|ui| {
+            ui.label("Tag:");
+            let selected_text = selected_tag
+                .as_ref()
+                .map(|tag| format!("#{}", tag))
+                .unwrap_or_else(|| "Any".to_string());
+            egui::ComboBox::from_id_salt("script_tag_filter")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut selected_tag, None, "Any");
+                    for tag in tags.iter() {
+                        ui.selectable_value(
+                            &mut selected_tag,
+                            Some(tag.clone()),
+                            format!("#{}", tag),
+                        );
+                    }
+                });
+            ui.add_enabled(
+                selected_tag.is_some(),
+                egui::Checkbox::new(&mut selected_all_folders, "All folders"),
+            );
+        });
+
+        if selected_tag != tag_filter || selected_all_folders != all_folders {
+            apply_tag_filter(selected_tag, selected_all_folders);
+        }
+    }
+
+    // "All folders": every script carrying the tag, wherever it lives
+    pub fn tagged_scripts(&mut self, ui: &mut Ui) {
+        let scripts =
+            crate::with_folder_state(|state| state.scripts_with_tag.read().unwrap().clone());
+        if scripts.is_empty() {
+            ui.weak("No scripts with this tag");
+            return;
+        }
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for script in scripts.iter() {
+                self.tagged_script_item(ui, script);
+                ui.add_space(4.0);
+            }
+        });
+    }
+
+    fn tagged_script_item(&mut self, ui: &mut Ui, script: &Data) {
+        let frame = egui::Frame::group(ui.style()).fill(ui.visuals().faint_bg_color);
+        frame.show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(&script.name).strong().size(16.0));
+                let folder_names: Vec<&str> = folders_of_script(script)
+                    .into_iter()
+                    .map(|folder| folder.name.as_str())
+                    .collect();
+                ui.weak(folder_names.join(", "));
+                if let Some(tag) = tag_chips(ui, &tags_of_script(script)) {
+                    apply_tag_filter(Some(tag), true);
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Execute").clicked() {
+                        crate::execute_script(ScriptExecution::from(script));
+                    }
+                    if ui.button("Edit").clicked() {
+                        self.begin_edit(script);
+                    }
+                });
+            });
+            let first_line = script.command.lines().next().unwrap_or_default();
+            ui.label(egui::RichText::new(first_line).monospace().weak());
+        });
+    }
+}
+
+pub fn apply_tag_filter(tag: Option<String>, all_folders: bool) {
+    crate::with_folder_state_reducer(|r| {
+        r.set_tag_filter(tag.clone());
+        r.set_tag_filter_all_folders(all_folders);
+    });
+    if let Some(tag) = tag {
+        dispatch_folder_command(FolderCommand::FilterScriptsByTag { tag });
+    }
+}
@@ -1,4 +1,6 @@
 use crate::component::common::horizontal_filled_button::horizontal_filled_button;
+use crate::domain::execution::interpreter::InterpreterSettings;
+use crate::state::folder_state::VirtualFolder;
 use crate::with_folder_state;
 use egui::Ui;
 use std::sync::Arc;
@@ -7,11 +9,25 @@ pub struct ScriptsColumn {
     pub adding_new_script: bool,
     pub adding_code: String,
     pub code_lang: String,
+    pub adding_interpreter_settings: InterpreterSettings,
     pub editing_script_id: Option<i32>,
     pub editing_command: String,
+    pub editing_description: String,
+    pub editing_working_directory: String,
+    pub editing_env_vars: Vec<(String, String)>,
+    pub editing_interpreter_settings: InterpreterSettings,
+    pub editing_tags: String,
     pub renaming_script_id: Option<i32>,
     pub renaming_name: String,
     pub script_to_delete: Option<i32>,
+    pub folders_script_id: Option<i32>,
+    pub selected_history_run_id: Option<i32>,
+    pub selected_revision_id: Option<i32>,
+    pub prune_keep_latest: usize,
+    pub prune_older_than_days: u32,
+    pub prune_all_scripts: bool,
+    pub search_query: String,
+    pub confirming_empty_trash: bool,
 }
 
 impl ScriptsColumn {
@@ -19,12 +35,26 @@ impl ScriptsColumn {
         Self {
             adding_new_script: false,
             adding_code: "# add your code here ...".into(),
-            code_lang: "bash".into(),
+            code_lang: InterpreterSettings::default().code_lang(),
+            adding_interpreter_settings: InterpreterSettings::default(),
             editing_script_id: None,
             editing_command: String::new(),
+            editing_description: String::new(),
+            editing_working_directory: String::new(),
+            editing_env_vars: vec![],
+            editing_interpreter_settings: InterpreterSettings::default(),
+            editing_tags: String::new(),
             renaming_script_id: None,
             renaming_name: String::new(),
             script_to_delete: None,
+            folders_script_id: None,
+            selected_history_run_id: None,
+            selected_revision_id: None,
+            prune_keep_latest: 50,
+            prune_older_than_days: 30,
+            prune_all_scripts: false,
+            search_query: String::new(),
+            confirming_empty_trash: false,
         }
     }
 
@@ -33,10 +63,31 @@ impl ScriptsColumn {
             ui.add_space(-6.0);
             ui.add_space(10.0);
             Self::header(ui);
+            self.script_search_box(ui);
+            self.tag_filter_bar(ui);
             ui.add_space(10.0);
-            self.add_script_button(ui);
-            ui.add_space(10.0);
-            self.scripts_of_selected_folder(ui);
+            let (tag_filter_all_folders, virtual_folder) = with_folder_state(|state| {
+                (
+                    state.tag_filter.read().unwrap().is_some()
+                        && *state.tag_filter_all_folders.read().unwrap(),
+                    *state.virtual_folder.read().unwrap(),
+                )
+            });
+            // Search results and "All folders" tag results span all folders and replace the
+            // selected folder's scripts
+            if !self.search_query.trim().is_empty() {
+                self.script_search_results(ui);
+            } else if tag_filter_all_folders {
+                self.tagged_scripts(ui);
+            } else if virtual_folder == Some(VirtualFolder::Pinned) {
+                self.pinned_scripts(ui);
+            } else if virtual_folder == Some(VirtualFolder::Trash) {
+                self.trash_list(ui);
+            } else {
+                self.add_script_button(ui);
+                ui.add_space(10.0);
+                self.scripts_of_selected_folder(ui);
+            }
 
             // Pop-up windows
             self.conditionally_popup_windows(ui);
@@ -44,6 +95,10 @@ impl ScriptsColumn {
     }
 
     fn conditionally_popup_windows(&mut self, ui: &mut Ui) {
+        // Edits requested from outside of this column, e.g. Shift+Enter in the command palette
+        if let Some(script) = crate::with_folder_state_reducer(|r| r.take_script_to_edit()) {
+            self.begin_edit(&script);
+        }
         if self.adding_new_script {
             self.new_script_window(ui);
         }
@@ -56,6 +111,19 @@ impl ScriptsColumn {
         if let Some(script_id) = self.script_to_delete {
             self.confirm_delete_script_window(ui, script_id);
         }
+        if let Some(script_id) = self.folders_script_id {
+            self.script_folders_window(ui, script_id);
+        }
+        let run_history_script_id =
+            crate::with_execution_state(|state| *state.run_history_script_id.read().unwrap());
+        if let Some(script_id) = run_history_script_id {
+            self.run_history_window(ui, script_id);
+        }
+        let revision_history_script_id =
+            with_folder_state(|state| *state.revision_history_script_id.read().unwrap());
+        if let Some(script_id) = revision_history_script_id {
+            self.revision_history_window(ui, script_id);
+        }
     }
 
     fn add_script_button(&mut self, ui: &mut Ui) {
@@ -78,6 +146,23 @@ impl ScriptsColumn {
     }
 
     fn header(ui: &mut Ui) {
+        let virtual_folder = with_folder_state(|state| *state.virtual_folder.read().unwrap());
+        if let Some(virtual_folder) = virtual_folder {
+            let (icon, title) = match virtual_folder {
+                VirtualFolder::Pinned => ("📌", "Pinned"),
+                VirtualFolder::Trash => ("🗑", "Trash"),
+            };
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(icon).font(egui::FontId::proportional(30.0)));
+                ui.label(
+                    egui::RichText::new(title)
+                        .strong()
+                        .font(egui::FontId::proportional(16.0)),
+                );
+            });
+            ui.separator();
+            return;
+        }
         with_selected_folder(|selected_folder| {
             let selected_folder_name = selected_folder.map(|f| f.name.clone());
             let display_name = selected_folder_name
@@ -1,16 +1,64 @@
+use crate::component::common::markdown_view::markdown_view;
+use crate::component::common::tag_chips::tag_chips;
+use crate::component::right_scripts_col::pinned_scripts::pin_button;
+use crate::component::right_scripts_col::script_tag_filter::apply_tag_filter;
 use crate::component::right_scripts_col::scripts_col::ScriptsColumn;
+use crate::domain::execution::execution_command_handler::ExecutionCommand;
+use crate::domain::execution::interpreter::Interpreter;
+use crate::domain::execution::script_execution::ScriptExecution;
+use crate::domain::folder::folder_command_handler::FolderCommand;
+use crate::domain::folder::script_tags::tags_of_script;
 use crate::prisma::shell_script::Data;
+use crate::{dispatch_execution_command, dispatch_folder_command};
+use eframe::emath::Pos2;
 use eframe::epaint::Color32;
-use egui::Ui;
+use egui::{Id, Response, Ui};
+use std::sync::Arc;
+
+// Dragged by the " :: " handle of a script card
+#[derive(Clone, PartialEq, Eq, Copy, Debug)]
+pub struct ScriptDragPayload {
+    pub script_id: i32,
+    pub folder_id: i32,
+    pub row_index: usize,
+}
 
 impl ScriptsColumn {
     pub fn scripts_of_selected_folder(&mut self, ui: &mut Ui) {
         crate::with_folder_state(|state| {
+            let Some(folder_id) = *state.selected_folder_id.read().unwrap() else {
+                return;
+            };
             egui::ScrollArea::vertical().show(ui, |ui| {
                 crate::component::right_scripts_col::scripts_col::with_scritps_from_selected_folder(
                     |scripts| {
-                        for script in scripts.iter() {
-                            self.script_item(ui, &script);
+                        let tag_filter = state.tag_filter.read().unwrap().clone();
+                        for (row_idx, script) in scripts.iter().enumerate() {
+                            // Skipped rows keep their index, reordering works on the whole folder
+                            if let Some(tag) = tag_filter.as_deref()
+                                && !tags_of_script(script).contains(&tag)
+                            {
+                                continue;
+                            }
+                            let payload = ScriptDragPayload {
+                                script_id: script.id,
+                                folder_id,
+                                row_index: row_idx,
+                            };
+                            let response = self.script_item(ui, &script, payload);
+
+                            if let (Some(pointer), Some(hovered_payload)) = (
+                                ui.input(|i| i.pointer.interact_pos()),
+                                response.dnd_hover_payload::<ScriptDragPayload>(),
+                            ) {
+                                Self::handle_script_drop_event(
+                                    ui,
+                                    row_idx,
+                                    response,
+                                    pointer,
+                                    hovered_payload,
+                                );
+                            }
                         }
                     },
                 );
@@ -18,25 +66,97 @@ impl ScriptsColumn {
         })
     }
 
-    fn script_item(&mut self, ui: &mut Ui, script: &&Data) {
+    fn handle_script_drop_event(
+        ui: &mut Ui,
+        row_idx: usize,
+        response: Response,
+        pointer: Pos2,
+        hovered_payload: Arc<ScriptDragPayload>,
+    ) {
+        let rect = response.rect;
+
+        // Preview insertion:
+        let stroke = egui::Stroke::new(2.0, Color32::from_black_alpha(60));
+        let insert_row_idx = if hovered_payload.row_index == row_idx {
+            // We are dragged onto ourselves
+            return;
+        } else if pointer.y < rect.center().y {
+            // Above us
+            ui.painter().hline(rect.x_range(), rect.top() - 5.0, stroke);
+            row_idx
+        } else {
+            // Below us
+            ui.painter()
+                .hline(rect.x_range(), rect.bottom() + 5.0, stroke);
+            row_idx + 1
+        };
+
+        if let Some(dragged_payload) = response.dnd_release_payload::<ScriptDragPayload>() {
+            let from_index = dragged_payload.row_index;
+            // If moving downwards, the target index shifts since the script is removed first
+            let to_index = if insert_row_idx > from_index {
+                insert_row_idx - 1
+            } else {
+                insert_row_idx
+            };
+            if from_index != to_index {
+                dispatch_folder_command(FolderCommand::ReorderScripts {
+                    folder_id: dragged_payload.folder_id,
+                    from_index,
+                    to_index,
+                });
+            }
+        }
+    }
+
+    fn script_item(&mut self, ui: &mut Ui, script: &&Data, payload: ScriptDragPayload) -> Response {
         let frame = egui::Frame::group(ui.style()).fill(ui.visuals().faint_bg_color);
         let frame_response = frame.show(ui, |ui| {
             ui.horizontal(|ui| {
+                ui.dnd_drag_source(Id::new(("script_drag_source", script.id)), payload, |ui| {
+                    ui.label(egui::RichText::new(" :: "));
+                });
                 // Use built-in bold font
                 ui.label(egui::RichText::new(&script.name).strong().size(16.0));
+                let interpreter = Interpreter::from_db_value(&script.interpreter);
+                if interpreter != Interpreter::LoginShell {
+                    ui.label(egui::RichText::new(interpreter.label()).small().weak());
+                }
 
                 if ui.button("Rename").clicked() {
                     self.renaming_script_id = Some(script.id);
                     self.renaming_name = script.name.clone();
                 }
+                self.folders_menu(ui, script);
+                pin_button(ui, script);
+                if let Some(tag) = tag_chips(ui, &tags_of_script(script)) {
+                    apply_tag_filter(Some(tag), true);
+                }
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     if ui.button("Execute").clicked() {
                         // Execute the script command
-                        crate::run_terminal_command(script.command.clone());
+                        crate::execute_script(ScriptExecution::from(*script));
+                    }
+                    let running_run_ids = running_run_ids_of_script(script.id);
+                    if !running_run_ids.is_empty() && ui.button("Stop").clicked() {
+                        for run_id in running_run_ids {
+                            dispatch_execution_command(ExecutionCommand::StopRun { run_id });
+                        }
                     }
                     if ui.button("Edit").clicked() {
-                        self.editing_script_id = Some(script.id);
-                        self.editing_command = script.command.clone();
+                        self.begin_edit(script);
+                    }
+                    if ui.button("History").clicked() {
+                        self.selected_history_run_id = None;
+                        dispatch_execution_command(ExecutionCommand::OpenRunHistory {
+                            script_id: script.id,
+                        });
+                    }
+                    if ui.button("Versions").clicked() {
+                        self.selected_revision_id = None;
+                        dispatch_folder_command(FolderCommand::OpenRevisionHistory {
+                            script_id: script.id,
+                        });
                     }
                     if ui.button("Copy").clicked() {
                         ui.ctx().copy_text(script.command.clone());
@@ -47,6 +167,36 @@ impl ScriptsColumn {
                     }
                 });
             });
+            let selected_folder_id =
+                crate::with_folder_state(|state| *state.selected_folder_id.read().unwrap());
+            let other_folder_names: Vec<&str> = folders_of_script(script)
+                .into_iter()
+                .filter(|folder| Some(folder.id) != selected_folder_id)
+                .map(|folder| folder.name.as_str())
+                .collect();
+            if !other_folder_names.is_empty() {
+                ui.label(
+                    egui::RichText::new(format!("🔗 Also in {}", other_folder_names.join(", ")))
+                        .small()
+                        .weak(),
+                );
+            }
+            if !script.description.trim().is_empty() {
+                egui::CollapsingHeader::new("Description")
+                    .id_salt(("script_description", script.id))
+                    .show(ui, |ui| markdown_view(ui, &script.description));
+            }
+            if let Some(working_directory) = script
+                .working_directory
+                .as_deref()
+                .filter(|dir| !dir.trim().is_empty())
+            {
+                ui.label(
+                    egui::RichText::new(format!("📂 {}", working_directory))
+                        .small()
+                        .weak(),
+                );
+            }
             ui.label("Command:");
             ui.add_space(2.0);
             egui::Frame::NONE
@@ -79,11 +229,65 @@ impl ScriptsColumn {
         }) {
             if let Some(pos) = ui.input(|i| i.pointer.interact_pos()) {
                 if frame_response.response.rect.contains(pos) {
-                    crate::run_terminal_command(script.command.clone());
+                    crate::execute_script(ScriptExecution::from(*script));
                 }
             }
         }
 
         ui.add_space(10.0);
+        frame_response.response
     }
 }
+
+impl ScriptsColumn {
+    fn folders_menu(&mut self, ui: &mut Ui, script: &Data) {
+        let selected_folder_id =
+            crate::with_folder_state(|state| *state.selected_folder_id.read().unwrap());
+        let is_shared = folders_of_script(script).len() > 1;
+        ui.menu_button("📁", |ui| {
+            if ui.button("Add to folder…").clicked() {
+                self.folders_script_id = Some(script.id);
+                ui.close();
+            }
+            // The script stays in its other folders, Delete is for the last one
+            if ui
+                .add_enabled(is_shared, egui::Button::new("Remove from this folder"))
+                .clicked()
+            {
+                if let Some(folder_id) = selected_folder_id {
+                    dispatch_folder_command(FolderCommand::RemoveScriptFromFolder {
+                        script_id: script.id,
+                        folder_id,
+                    });
+                }
+                ui.close();
+            }
+        });
+    }
+}
+
+// Every folder the script is linked to, requires the relations to be fetched with the script
+pub fn folders_of_script(script: &Data) -> Vec<&crate::prisma::scripts_folder::Data> {
+    script
+        .rel_scriptsfolder_shellscript()
+        .map(|rels| {
+            rels.iter()
+                .filter_map(|rel| rel.scripts_folder().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Runs of this script that are still alive and have not been asked to stop yet
+fn running_run_ids_of_script(script_id: i32) -> Vec<u64> {
+    crate::with_execution_state(|state| {
+        state
+            .runs
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|r| r.script_id == script_id && r.is_running() && !r.stop_requested)
+            .map(|r| r.run_id)
+            .collect()
+    })
+}
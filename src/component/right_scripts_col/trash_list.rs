@@ -0,0 +1,177 @@
+use crate::component::right_scripts_col::scripts_col::ScriptsColumn;
+use crate::db::repository::script_run_repository::now_millis;
+use crate::db::repository::trash_repository::{
+    DEFAULT_TRASH_RETENTION_DAYS, TrashedFolder, TrashedScript,
+};
+use crate::dispatch_folder_command;
+use crate::domain::folder::folder_command_handler::FolderCommand;
+use egui::{RichText, Ui};
+
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+
+impl ScriptsColumn {
+    pub fn trash_list(&mut self, ui: &mut Ui) {
+        let (trash, retention_days) = crate::with_folder_state(|state| {
+            let retention_days = state
+                .app_state
+                .read()
+                .unwrap()
+                .as_ref()
+                .as_ref()
+                .map(|app_state| app_state.trash_retention_days.max(1) as u32)
+                .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS);
+            (state.trash.read().unwrap().clone(), retention_days)
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Delete items for good after");
+            let mut days = retention_days;
+            if ui
+                .add(egui::DragValue::new(&mut days).range(1..=3650))
+                .changed()
+            {
+                crate::with_folder_state_reducer(|r| r.set_trash_retention_days(days));
+                dispatch_folder_command(FolderCommand::SetTrashRetentionDays { days });
+            }
+            ui.label("days");
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .add_enabled(!trash.is_empty(), egui::Button::new("Empty Trash"))
+                    .clicked()
+                {
+                    self.confirming_empty_trash = true;
+                }
+            });
+        });
+        ui.add_space(10.0);
+
+        if trash.is_empty() {
+            ui.weak("The trash is empty");
+        } else {
+            let now = now_millis();
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for trashed in trash.folders.iter() {
+                    trashed_folder_item(ui, trashed, now, retention_days);
+                    ui.add_space(6.0);
+                }
+                for trashed in trash.scripts.iter() {
+                    trashed_script_item(ui, trashed, now, retention_days);
+                    ui.add_space(6.0);
+                }
+            });
+        }
+
+        if self.confirming_empty_trash {
+            self.confirm_empty_trash_window(ui, trash.len());
+        }
+    }
+
+    fn confirm_empty_trash_window(&mut self, ui: &mut Ui, item_count: usize) {
+        egui::Window::new("Empty Trash")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ui.ctx(), |ui| {
+                ui.label(format!(
+                    "Delete the {} item(s) in the trash for good? This cannot be undone.",
+                    item_count
+                ));
+                ui.add_space(20.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        self.confirming_empty_trash = false;
+                    }
+                    if ui.button("Empty Trash").clicked() {
+                        dispatch_folder_command(FolderCommand::EmptyTrash);
+                        self.confirming_empty_trash = false;
+                    }
+                });
+            });
+    }
+}
+
+fn trashed_folder_item(ui: &mut Ui, trashed: &TrashedFolder, now: f64, retention_days: u32) {
+    let origin = match trashed.parent_name.as_deref() {
+        Some(parent_name) => format!("in {}", parent_name),
+        None => "top level".to_string(),
+    };
+    let mut contents = vec![format!("{} script(s)", trashed.script_count)];
+    if trashed.subfolder_count > 0 {
+        contents.push(format!("{} subfolder(s)", trashed.subfolder_count));
+    }
+    let details = format!(
+        "{}  ·  {}  ·  {}",
+        origin,
+        contents.join(", "),
+        deleted_ago(trashed.folder.deleted_at, now, retention_days)
+    );
+    trash_item(
+        ui,
+        &format!("📁 {}", trashed.folder.name),
+        &details,
+        FolderCommand::RestoreTrashedFolder {
+            folder_id: trashed.folder.id,
+        },
+        FolderCommand::DeleteFolderPermanently {
+            folder_id: trashed.folder.id,
+        },
+    );
+}
+
+fn trashed_script_item(ui: &mut Ui, trashed: &TrashedScript, now: f64, retention_days: u32) {
+    let details = format!(
+        "from {}  ·  {}",
+        trashed.folder_names.join(", "),
+        deleted_ago(trashed.script.deleted_at, now, retention_days)
+    );
+    trash_item(
+        ui,
+        &trashed.script.name,
+        &details,
+        FolderCommand::RestoreTrashedScript {
+            script_id: trashed.script.id,
+        },
+        FolderCommand::DeleteScriptPermanently {
+            script_id: trashed.script.id,
+        },
+    );
+}
+
+fn trash_item(
+    ui: &mut Ui,
+    title: &str,
+    details: &str,
+    restore_command: FolderCommand,
+    delete_command: FolderCommand,
+) {
+    let frame = egui::Frame::group(ui.style()).fill(ui.visuals().faint_bg_color);
+    frame.show(ui, |ui| {
+        ui.set_width(ui.available_width());
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(title).strong().size(16.0));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button("Delete Forever").clicked() {
+                    dispatch_folder_command(delete_command);
+                }
+                if ui.button("Restore").clicked() {
+                    dispatch_folder_command(restore_command);
+                }
+            });
+        });
+        ui.weak(details);
+    });
+}
+
+// e.g. "deleted 3 days ago, gone in 27 day(s)", expired items go at the next start
+fn deleted_ago(deleted_at: Option<f64>, now: f64, retention_days: u32) -> String {
+    let days_ago = ((now - deleted_at.unwrap_or(now)) / MILLIS_PER_DAY)
+        .floor()
+        .max(0.0) as u32;
+    let deleted = match days_ago {
+        0 => "deleted today".to_string(),
+        1 => "deleted yesterday".to_string(),
+        days => format!("deleted {} days ago", days),
+    };
+    let days_left = retention_days.saturating_sub(days_ago);
+    format!("{}, gone in {} day(s)", deleted, days_left.max(1))
+}
@@ -0,0 +1,78 @@
+use crate::state::notification_state::{NotificationLevel, Toast};
+use crate::{with_notification_state, with_notification_state_reducer};
+use egui::{Context, RichText, Ui};
+use std::time::Duration;
+
+const TOAST_WIDTH: f32 = 360.0;
+
+// Stack of toasts in the bottom right corner, above every window
+pub fn toast_overlay(ctx: &Context) {
+    with_notification_state_reducer(|r| r.dismiss_expired());
+    let toasts = with_notification_state(|state| state.toasts.read().unwrap().clone());
+    if toasts.is_empty() {
+        return;
+    }
+    // Toasts go away on their own, which needs a frame after they expire
+    ctx.request_repaint_after(Duration::from_millis(500));
+
+    egui::Area::new(egui::Id::new("toast_overlay"))
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -12.0))
+        .show(ctx, |ui| {
+            ui.set_max_width(TOAST_WIDTH);
+            for toast in toasts.iter() {
+                toast_frame(ui, toast);
+                ui.add_space(6.0);
+            }
+        });
+}
+
+fn toast_frame(ui: &mut Ui, toast: &Toast) {
+    let (icon, color) = match toast.notification.level {
+        NotificationLevel::Warning => ("⚠", ui.visuals().warn_fg_color),
+        NotificationLevel::Error => ("❌", ui.visuals().error_fg_color),
+    };
+    egui::Frame::popup(ui.style())
+        .stroke(egui::Stroke::new(1.0, color))
+        .show(ui, |ui| {
+            ui.set_width(TOAST_WIDTH);
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(icon).color(color));
+                let message = if toast.count > 1 {
+                    format!("{} (×{})", toast.notification.message, toast.count)
+                } else {
+                    toast.notification.message.clone()
+                };
+                ui.label(RichText::new(message).strong());
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
+                    if ui.small_button("✖").on_hover_text("Dismiss").clicked() {
+                        with_notification_state_reducer(|r| r.dismiss(toast.id));
+                    }
+                });
+            });
+            if toast.notification.detail.is_empty() {
+                return;
+            }
+            ui.horizontal(|ui| {
+                let toggle_text = if toast.expanded {
+                    "Hide details"
+                } else {
+                    "Show details"
+                };
+                if ui.small_button(toggle_text).clicked() {
+                    with_notification_state_reducer(|r| r.toggle_expanded(toast.id));
+                }
+                if toast.expanded && ui.small_button("Copy").clicked() {
+                    ui.ctx().copy_text(toast.notification.detail.clone());
+                }
+            });
+            if toast.expanded {
+                egui::ScrollArea::vertical()
+                    .id_salt(("toast_detail", toast.id))
+                    .max_height(180.0)
+                    .show(ui, |ui| {
+                        ui.label(RichText::new(&toast.notification.detail).monospace());
+                    });
+            }
+        });
+}
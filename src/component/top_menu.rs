@@ -1,13 +1,28 @@
-use egui::{Context, Ui};
+use crate::domain::folder::folder_command_handler::FolderCommand;
+use egui::{Context, Key, KeyboardShortcut, Modifiers, Ui};
+
+pub const UNDO_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+pub const REDO_SHORTCUT: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::Z);
 
 pub fn top_menu(ctx: &egui::Context) {
+    history_shortcuts(ctx);
     egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
         // The top panel is often a good place for a menu bar:
 
         egui::MenuBar::new().ui(ui, |ui| {
             ui.menu_button("File", |ui| {
+                library_buttons(ui);
+                ui.separator();
                 quit_button(ctx, ui);
             });
+            ui.menu_button("Edit", |ui| {
+                history_buttons(ctx, ui);
+            });
+            ui.menu_button("View", |ui| {
+                output_console_toggle(ui);
+                command_palette_button(ctx, ui);
+            });
             ui.add_space(16.0);
             // egui::widgets::g lobal_theme_preference_buttons(ui);
         });
@@ -19,3 +34,98 @@ fn quit_button(ctx: &Context, ui: &mut Ui) {
         ctx.send_viewport_cmd(egui::ViewportCommand::Close);
     }
 }
+
+// A focused text field keeps Ctrl+Z for its own text
+fn history_shortcuts(ctx: &Context) {
+    if ctx.wants_keyboard_input() {
+        return;
+    }
+    // Ctrl+Z also matches with Shift held, so redo is checked first
+    if ctx.input_mut(|i| i.consume_shortcut(&REDO_SHORTCUT)) {
+        crate::dispatch_folder_command(FolderCommand::Redo);
+    } else if ctx.input_mut(|i| i.consume_shortcut(&UNDO_SHORTCUT)) {
+        crate::dispatch_folder_command(FolderCommand::Undo);
+    }
+}
+
+fn history_buttons(ctx: &Context, ui: &mut Ui) {
+    let (undo_label, redo_label) = crate::with_folder_state(|state| {
+        (
+            state.undo_label.read().unwrap().clone(),
+            state.redo_label.read().unwrap().clone(),
+        )
+    });
+    let undo_text = match &undo_label {
+        Some(label) => format!("Undo {}", label),
+        None => "Undo".to_string(),
+    };
+    if ui
+        .add_enabled(
+            undo_label.is_some(),
+            egui::Button::new(undo_text).shortcut_text(ctx.format_shortcut(&UNDO_SHORTCUT)),
+        )
+        .clicked()
+    {
+        crate::dispatch_folder_command(FolderCommand::Undo);
+        ui.close();
+    }
+    let redo_text = match &redo_label {
+        Some(label) => format!("Redo {}", label),
+        None => "Redo".to_string(),
+    };
+    if ui
+        .add_enabled(
+            redo_label.is_some(),
+            egui::Button::new(redo_text).shortcut_text(ctx.format_shortcut(&REDO_SHORTCUT)),
+        )
+        .clicked()
+    {
+        crate::dispatch_folder_command(FolderCommand::Redo);
+        ui.close();
+    }
+}
+
+fn library_buttons(ui: &mut Ui) {
+    if ui.button("Export Library…").clicked() {
+        ui.close();
+        crate::component::library_import_window::export_library_dialog();
+    }
+    if ui.button("Import Library…").clicked() {
+        ui.close();
+        crate::component::library_import_window::import_library_dialog();
+    }
+    if ui.button("Import from Project…").clicked() {
+        ui.close();
+        crate::component::library_import_window::import_project_dialog();
+    }
+    if ui.button("Import Shell Scripts…").clicked() {
+        ui.close();
+        crate::component::library_import_window::import_shell_scripts_dialog();
+    }
+    if ui.button("Import from Shell History…").clicked() {
+        ui.close();
+        crate::dispatch_library_command(
+            crate::domain::library::library_command_handler::LibraryCommand::LoadShellHistory,
+        );
+    }
+}
+
+fn output_console_toggle(ui: &mut Ui) {
+    let mut console_open = crate::with_execution_state(|state| *state.console_open.read().unwrap());
+    if ui.checkbox(&mut console_open, "Output Console").changed() {
+        crate::with_execution_state_reducer(|r| r.set_console_open(console_open));
+    }
+}
+
+fn command_palette_button(ctx: &Context, ui: &mut Ui) {
+    let shortcut = ctx.format_shortcut(&crate::component::command_palette::PALETTE_SHORTCUT);
+    if ui
+        .add(egui::Button::new("Command Palette").shortcut_text(shortcut))
+        .clicked()
+    {
+        crate::dispatch_palette_command(
+            crate::domain::palette::palette_command_handler::PaletteCommand::Open,
+        );
+        ui.close();
+    }
+}
@@ -4,6 +4,25 @@ pub fn get_db() -> &'static PrismaClient {
     crate::PRISMA_CLIENT.get().unwrap()
 }
 
+// Shared by the app and the `ssm` command line tool so that both open the same database
+pub fn database_path() -> std::path::PathBuf {
+    // Choose database location based on build mode
+    if cfg!(debug_assertions) {
+        // In debug mode, use current directory for easier development
+        std::env::current_dir().unwrap().join("database.db")
+    } else {
+        // In release mode, use proper app data directory
+        let app_data_dir = dirs::data_dir()
+            .unwrap_or_else(|| std::env::current_dir().unwrap())
+            .join("ShellScriptManager");
+
+        // Create directory if it doesn't exist
+        std::fs::create_dir_all(&app_data_dir).ok();
+
+        app_data_dir.join("database.db")
+    }
+}
+
 pub async fn initialize_database(client: &PrismaClient) -> Result<(), Box<dyn std::error::Error>> {
     println!("Initializing database...");
 
@@ -1,3 +1,6 @@
+use crate::db::repository::script_repository::delete_script_dependents_in;
+use crate::db::repository::script_run_repository::now_millis;
+use crate::domain::folder::folder_tree::{child_folders, subtree_ids, trashed_together};
 use crate::prisma;
 use crate::prisma::PrismaClient;
 use crate::prisma::application_state::Data as AppStateData;
@@ -19,18 +22,25 @@ impl FolderRepository {
         Self { db }
     }
 
-    pub async fn get_folder_count(&self) -> i64 {
-        let total_num_folders = self.db.scripts_folder().count(vec![]).exec().await.unwrap();
-        total_num_folders
+    pub async fn get_folder_count(&self) -> prisma_client_rust::Result<i64> {
+        self.db.scripts_folder().count(vec![]).exec().await
     }
 
+    // Folders in the trash are left out
     pub async fn get_all_folders(&self) -> prisma_client_rust::Result<Vec<Data>> {
+        live_folders(self.db).await
+    }
+
+    pub async fn get_child_folder_count(
+        &self,
+        parent_id: Option<i32>,
+    ) -> prisma_client_rust::Result<i64> {
         self.db
             .scripts_folder()
-            .find_many(vec![])
-            .order_by(crate::prisma::scripts_folder::ordering::order(
-                prisma_client_rust::Direction::Asc,
-            ))
+            .count(vec![
+                crate::prisma::scripts_folder::parent_id::equals(parent_id),
+                crate::prisma::scripts_folder::deleted_at::equals(None),
+            ])
             .exec()
             .await
     }
@@ -39,10 +49,15 @@ impl FolderRepository {
         &self,
         folder_name: &String,
         ordering: i32,
+        parent_id: Option<i32>,
     ) -> prisma_client_rust::Result<Data> {
         self.db
             .scripts_folder()
-            .create(folder_name.clone(), ordering, vec![])
+            .create(
+                folder_name.clone(),
+                ordering,
+                vec![crate::prisma::scripts_folder::parent_id::set(parent_id)],
+            )
             .exec()
             .await
     }
@@ -67,105 +82,268 @@ impl FolderRepository {
         self.db.application_state().find_first(vec![]).exec().await
     }
 
-    pub async fn delete_script_folder(&self, id: i32) -> Result<(), QueryError> {
-        // 1. Find all scripts related to this folder
-        let related_scripts = self
-            .db
-            .rel_scriptsfolder_shellscript()
-            .find_many(vec![
-                crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::equals(id),
-            ])
-            .with(crate::prisma::rel_scriptsfolder_shellscript::shell_script::fetch())
-            .exec()
-            .await?;
-
-        // 2. Delete relationship records first
+    // Moves the folder with its subfolders to the trash, all of them stamped with the same
+    // `deleted_at`. Their scripts go along, except those still linked to a folder outside of the
+    // trash. Links are kept, a restore puts everything back where it was.
+    pub async fn trash_folder(&self, id: i32) -> Result<(), QueryError> {
         self.db
-            .rel_scriptsfolder_shellscript()
-            .delete_many(vec![
-                crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::equals(id),
-            ])
-            .exec()
-            .await?;
-
-        // 3. Delete scripts that are only used by this folder
-        for relation in related_scripts {
-            if let Some(script) = relation.shell_script {
-                // Check if this script is used by other folders
-                let other_relations = self
-                    .db
-                    .rel_scriptsfolder_shellscript()
-                    .find_many(vec![
-                        crate::prisma::rel_scriptsfolder_shellscript::shell_script_id::equals(
-                            script.id,
-                        ),
-                    ])
-                    .exec()
-                    .await?;
-
-                // Only delete if no other folders reference this script
-                if other_relations.is_empty() {
-                    self.db
-                        .shell_script()
-                        .delete_many(vec![crate::prisma::shell_script::id::equals(script.id)])
-                        .exec()
-                        .await?;
-                }
-            }
-        }
+            ._transaction()
+            .run(|tx| async move { trash_folder_in(&tx, id).await })
+            .await
+    }
 
-        // 4. Finally delete the folder
+    // Deletes a folder of the trash for good, together with the subfolders trashed along with it,
+    // deepest first, each by the same rules. Subfolders that were trashed on their own before
+    // stay in the trash as top-level folders. All or nothing.
+    pub async fn delete_folder_permanently(&self, id: i32) -> Result<(), QueryError> {
         self.db
-            .scripts_folder()
-            .delete_many(vec![crate::prisma::scripts_folder::id::equals(id)])
-            .exec()
-            .await?;
-
-        Ok(())
+            ._transaction()
+            .run(|tx| async move { delete_folder_permanently_in(&tx, id).await })
+            .await
     }
-    pub async fn reorder_folders(
+
+    // Makes the folder the child of `parent_id` at `index` among its new siblings. Moving a folder
+    // into itself or one of its descendants is ignored.
+    pub async fn move_folder(
         &self,
-        from_index: usize,
-        to_index: usize,
+        folder_id: i32,
+        parent_id: Option<i32>,
+        index: usize,
     ) -> Result<(), QueryError> {
-        let mut folders = self.get_all_folders().await?;
-        if from_index >= folders.len() || to_index >= folders.len() {
-            return Ok(());
-        }
-        let folder = folders.remove(from_index);
-        folders.insert(to_index, folder);
-
-        for (ordering, folder) in folders.iter().enumerate() {
-            self.db
-                .scripts_folder()
-                .update_many(
-                    vec![crate::prisma::scripts_folder::id::equals(folder.id)],
-                    vec![crate::prisma::scripts_folder::ordering::set(
-                        ordering as i32,
-                    )],
-                )
-                .exec()
-                .await?;
-        }
-        Ok(())
+        self.db
+            ._transaction()
+            .run(|tx| async move { move_folder_in(&tx, folder_id, parent_id, index).await })
+            .await
     }
 
     pub async fn batch_order_update(
         &self,
         order_updates: Vec<FolderOrderUpdate>,
     ) -> Result<(), QueryError> {
-        for (update) in order_updates {
-            let folder_id = update.folder_id;
-            let new_ordering = update.new_ordering;
-            self.db
-                .scripts_folder()
-                .update_many(
-                    vec![crate::prisma::scripts_folder::id::equals(folder_id)],
-                    vec![crate::prisma::scripts_folder::ordering::set(new_ordering)],
-                )
+        write_folder_orderings(self.db, order_updates).await
+    }
+}
+
+pub async fn delete_folder_permanently_in(db: &PrismaClient, id: i32) -> Result<(), QueryError> {
+    let folders = db.scripts_folder().find_many(vec![]).exec().await?;
+    if !folders
+        .iter()
+        .any(|folder| folder.id == id && folder.deleted_at.is_some())
+    {
+        return Ok(());
+    }
+    let deleted_ids = trashed_together(&folders, id);
+    let orphaned_ids: Vec<i32> = folders
+        .iter()
+        .filter(|folder| {
+            folder
+                .parent_id
+                .is_some_and(|parent_id| deleted_ids.contains(&parent_id))
+                && !deleted_ids.contains(&folder.id)
+        })
+        .map(|folder| folder.id)
+        .collect();
+    db.scripts_folder()
+        .update_many(
+            vec![crate::prisma::scripts_folder::id::in_vec(orphaned_ids)],
+            vec![crate::prisma::scripts_folder::parent_id::set(None)],
+        )
+        .exec()
+        .await?;
+    for folder_id in deleted_ids.into_iter().rev() {
+        delete_single_folder_in(db, folder_id).await?;
+    }
+    Ok(())
+}
+
+async fn delete_single_folder_in(db: &PrismaClient, id: i32) -> Result<(), QueryError> {
+    // 1. Find all scripts related to this folder
+    let related_scripts = db
+        .rel_scriptsfolder_shellscript()
+        .find_many(vec![
+            crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::equals(id),
+        ])
+        .with(crate::prisma::rel_scriptsfolder_shellscript::shell_script::fetch())
+        .exec()
+        .await?;
+
+    // 2. Delete relationship records first
+    db.rel_scriptsfolder_shellscript()
+        .delete_many(vec![
+            crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::equals(id),
+        ])
+        .exec()
+        .await?;
+
+    // 3. Delete scripts that are only used by this folder
+    for relation in related_scripts {
+        if let Some(script) = relation.shell_script {
+            // Check if this script is used by other folders
+            let other_relations = db
+                .rel_scriptsfolder_shellscript()
+                .find_many(vec![
+                    crate::prisma::rel_scriptsfolder_shellscript::shell_script_id::equals(
+                        script.id,
+                    ),
+                ])
                 .exec()
                 .await?;
+
+            // Only delete if no other folders reference this script
+            if other_relations.is_empty() {
+                delete_script_dependents_in(db, script.id).await?;
+                db.shell_script()
+                    .delete_many(vec![crate::prisma::shell_script::id::equals(script.id)])
+                    .exec()
+                    .await?;
+            }
         }
-        Ok(())
     }
+
+    // 4. Finally delete the folder
+    db.scripts_folder()
+        .delete_many(vec![crate::prisma::scripts_folder::id::equals(id)])
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+async fn live_folders(db: &PrismaClient) -> Result<Vec<Data>, QueryError> {
+    db.scripts_folder()
+        .find_many(vec![crate::prisma::scripts_folder::deleted_at::equals(
+            None,
+        )])
+        .order_by(crate::prisma::scripts_folder::ordering::order(
+            prisma_client_rust::Direction::Asc,
+        ))
+        .exec()
+        .await
+}
+
+async fn trash_folder_in(db: &PrismaClient, id: i32) -> Result<(), QueryError> {
+    let folders = live_folders(db).await?;
+    let parent_id = folders
+        .iter()
+        .find(|folder| folder.id == id)
+        .and_then(|folder| folder.parent_id);
+    let trashed_ids = subtree_ids(&folders, id);
+    // Whole milliseconds, stored exactly in the REAL column, so that comparing the stamps finds
+    // everything trashed here
+    let deleted_at = Some(now_millis());
+
+    db.scripts_folder()
+        .update_many(
+            vec![crate::prisma::scripts_folder::id::in_vec(
+                trashed_ids.clone(),
+            )],
+            vec![crate::prisma::scripts_folder::deleted_at::set(deleted_at)],
+        )
+        .exec()
+        .await?;
+    db.shell_script()
+        .update_many(
+            vec![
+                crate::prisma::shell_script::deleted_at::equals(None),
+                crate::prisma::shell_script::rel_scriptsfolder_shellscript::some(vec![
+                    crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::in_vec(
+                        trashed_ids,
+                    ),
+                ]),
+                crate::prisma::shell_script::rel_scriptsfolder_shellscript::none(vec![
+                    crate::prisma::rel_scriptsfolder_shellscript::scripts_folder::is(vec![
+                        crate::prisma::scripts_folder::deleted_at::equals(None),
+                    ]),
+                ]),
+            ],
+            vec![crate::prisma::shell_script::deleted_at::set(deleted_at)],
+        )
+        .exec()
+        .await?;
+    renumber_child_folders_in(db, parent_id).await
+}
+
+// Also used by restores from the trash, inside their transaction
+pub async fn move_folder_in(
+    db: &PrismaClient,
+    folder_id: i32,
+    parent_id: Option<i32>,
+    index: usize,
+) -> Result<(), QueryError> {
+    let folders = live_folders(db).await?;
+    let Some(folder) = folders.iter().find(|f| f.id == folder_id) else {
+        return Ok(());
+    };
+    if let Some(parent_id) = parent_id
+        && subtree_ids(&folders, folder_id).contains(&parent_id)
+    {
+        return Ok(());
+    }
+    let old_parent_id = folder.parent_id;
+
+    let mut siblings: Vec<i32> = child_folders(&folders, parent_id)
+        .into_iter()
+        .map(|f| f.id)
+        .filter(|id| *id != folder_id)
+        .collect();
+    siblings.insert(index.min(siblings.len()), folder_id);
+
+    db.scripts_folder()
+        .update_many(
+            vec![crate::prisma::scripts_folder::id::equals(folder_id)],
+            vec![crate::prisma::scripts_folder::parent_id::set(parent_id)],
+        )
+        .exec()
+        .await?;
+    write_folder_orderings(
+        db,
+        siblings
+            .into_iter()
+            .enumerate()
+            .map(|(ordering, folder_id)| FolderOrderUpdate {
+                folder_id,
+                new_ordering: ordering as i32,
+            })
+            .collect(),
+    )
+    .await?;
+    if old_parent_id != parent_id {
+        renumber_child_folders_in(db, old_parent_id).await?;
+    }
+    Ok(())
+}
+
+// Closes the gaps in the ordering left by a deleted or moved folder
+async fn renumber_child_folders_in(
+    db: &PrismaClient,
+    parent_id: Option<i32>,
+) -> Result<(), QueryError> {
+    let folders = live_folders(db).await?;
+    let order_updates = child_folders(&folders, parent_id)
+        .into_iter()
+        .enumerate()
+        .map(|(ordering, folder)| FolderOrderUpdate {
+            folder_id: folder.id,
+            new_ordering: ordering as i32,
+        })
+        .collect();
+    write_folder_orderings(db, order_updates).await
+}
+
+async fn write_folder_orderings(
+    db: &PrismaClient,
+    order_updates: Vec<FolderOrderUpdate>,
+) -> Result<(), QueryError> {
+    for update in order_updates {
+        db.scripts_folder()
+            .update_many(
+                vec![crate::prisma::scripts_folder::id::equals(update.folder_id)],
+                vec![crate::prisma::scripts_folder::ordering::set(
+                    update.new_ordering,
+                )],
+            )
+            .exec()
+            .await?;
+    }
+    Ok(())
 }
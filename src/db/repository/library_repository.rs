@@ -0,0 +1,685 @@
+use crate::db::repository::script_repository::{
+    env_vars_fetch, tags_fetch, write_script_orderings,
+};
+use crate::db::repository::script_revision_repository::update_name_and_command;
+use crate::db::repository::script_run_repository::now_millis;
+use crate::db::repository::tag_repository::{delete_unused_tags, set_tags};
+use crate::domain::folder::folder_tree::child_folders;
+use crate::domain::folder::script_tags::{normalize_tag, tags_of_script};
+use crate::domain::library::library_document::{
+    ConflictPolicy, ImportMode, LibraryDocument, LibraryEnvVar, LibraryFolder, LibraryScript,
+};
+use crate::domain::library::project_tasks::ProjectTask;
+use crate::prisma::PrismaClient;
+use prisma_client_rust::QueryError;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+#[derive(Debug, Clone, Default)]
+pub struct ImportSummary {
+    pub folders_created: usize,
+    pub scripts_created: usize,
+    pub scripts_updated: usize,
+    pub scripts_skipped: usize,
+    pub scripts_unchanged: usize,
+    pub scripts_trashed: usize,
+}
+
+pub struct LibraryRepository {
+    db: &'static PrismaClient,
+}
+
+impl LibraryRepository {
+    pub fn new() -> Self {
+        let db = crate::db::get_db::get_db();
+        Self { db }
+    }
+
+    // The trash is not exported
+    pub async fn export_library(&self) -> prisma_client_rust::Result<LibraryDocument> {
+        let folders = self
+            .db
+            .scripts_folder()
+            .find_many(vec![crate::prisma::scripts_folder::deleted_at::equals(
+                None,
+            )])
+            .order_by(crate::prisma::scripts_folder::ordering::order(
+                prisma_client_rust::Direction::Asc,
+            ))
+            .with(
+                crate::prisma::scripts_folder::rel_scriptsfolder_shellscript::fetch(live_scripts())
+                    .with(
+                        crate::prisma::rel_scriptsfolder_shellscript::shell_script::fetch()
+                            .with(env_vars_fetch())
+                            .with(tags_fetch()),
+                    ),
+            )
+            .exec()
+            .await?;
+
+        let folders = library_folders(&folders, None);
+        Ok(LibraryDocument::new(folders))
+    }
+
+    // All or nothing: a failing import leaves the library as it was
+    pub async fn import_library(
+        &self,
+        document: LibraryDocument,
+        mode: ImportMode,
+    ) -> prisma_client_rust::Result<ImportSummary> {
+        self.db
+            ._transaction()
+            .run(|tx| async move { import_document(&tx, document, mode).await })
+            .await
+    }
+
+    // Returns the id of the project's folder. Tasks already imported into it are updated in place.
+    pub async fn import_project_tasks(
+        &self,
+        folder_name: String,
+        working_directory: String,
+        tasks: Vec<ProjectTask>,
+    ) -> prisma_client_rust::Result<(i32, ImportSummary)> {
+        self.db
+            ._transaction()
+            .run(|tx| async move { import_tasks(&tx, folder_name, working_directory, tasks).await })
+            .await
+    }
+}
+
+// Subfolders are nested into their parents, as shown in the left column
+fn library_folders(
+    folders: &[crate::prisma::scripts_folder::Data],
+    parent_id: Option<i32>,
+) -> Vec<LibraryFolder> {
+    child_folders(folders, parent_id)
+        .into_iter()
+        .map(|folder| {
+            let mut scripts: Vec<LibraryScript> = folder
+                .rel_scriptsfolder_shellscript()
+                .map(|rels| {
+                    rels.iter()
+                        .filter_map(|rel| {
+                            rel.shell_script()
+                                .ok()
+                                .map(|script| library_script(script, rel.ordering))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            scripts.sort_by_key(|script| (script.ordering, script.id));
+            LibraryFolder {
+                name: folder.name.clone(),
+                ordering: folder.ordering,
+                scripts,
+                folders: library_folders(folders, Some(folder.id)),
+            }
+        })
+        .collect()
+}
+
+// `ordering` is the position of the script in the folder it is exported from
+fn library_script(script: &crate::prisma::shell_script::Data, ordering: i32) -> LibraryScript {
+    let env_vars = script
+        .shell_script_env_var()
+        .map(|env_vars| {
+            env_vars
+                .iter()
+                .map(|env_var| LibraryEnvVar {
+                    key: env_var.key.clone(),
+                    value: env_var.value.clone(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    LibraryScript {
+        id: script.id,
+        name: script.name.clone(),
+        command: script.command.clone(),
+        description: script.description.clone(),
+        ordering,
+        working_directory: script.working_directory.clone(),
+        interpreter: script.interpreter.clone(),
+        shebang: script.shebang.clone(),
+        source_rc: script.source_rc,
+        env_vars,
+        tags: tags_of_script(script)
+            .into_iter()
+            .map(|tag| tag.to_string())
+            .collect(),
+    }
+}
+
+async fn import_document(
+    db: &PrismaClient,
+    document: LibraryDocument,
+    mode: ImportMode,
+) -> Result<ImportSummary, QueryError> {
+    let conflict_policy = match mode {
+        ImportMode::Merge(conflict_policy) => conflict_policy,
+        ImportMode::Replace => {
+            clear_library(db).await?;
+            // Nothing left to conflict with
+            ConflictPolicy::KeepBoth
+        }
+    };
+
+    // Folders and scripts in the trash are not merged into, the import creates new ones
+    let existing_folders = db
+        .scripts_folder()
+        .find_many(vec![crate::prisma::scripts_folder::deleted_at::equals(
+            None,
+        )])
+        .with(
+            crate::prisma::scripts_folder::rel_scriptsfolder_shellscript::fetch(live_scripts())
+                .with(crate::prisma::rel_scriptsfolder_shellscript::shell_script::fetch()),
+        )
+        .exec()
+        .await?;
+    // Parent id -> ordering of the next folder created in it, after the last existing one
+    let mut next_folder_ordering: HashMap<Option<i32>, i32> = HashMap::new();
+    for folder in existing_folders.iter() {
+        let next_ordering = next_folder_ordering.entry(folder.parent_id).or_default();
+        *next_ordering = (*next_ordering).max(folder.ordering + 1);
+    }
+
+    let mut summary = ImportSummary::default();
+    // Id in the document -> id of the script created for it, shared scripts are created only once
+    let mut created_script_ids: HashMap<i32, i32> = HashMap::new();
+
+    let mut folders = document.folders;
+    folders.sort_by_key(|folder| folder.ordering);
+    // Parents are imported before their subfolders, which then know the id of their parent
+    let mut pending_folders: VecDeque<(Option<i32>, LibraryFolder)> =
+        folders.into_iter().map(|folder| (None, folder)).collect();
+    while let Some((parent_id, mut folder)) = pending_folders.pop_front() {
+        let existing_folder = existing_folders
+            .iter()
+            .find(|f| f.name == folder.name && f.parent_id == parent_id);
+        let existing_scripts: Vec<&crate::prisma::shell_script::Data> = existing_folder
+            .and_then(|f| f.rel_scriptsfolder_shellscript().ok())
+            .map(|rels| {
+                rels.iter()
+                    .filter_map(|rel| rel.shell_script().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        // Script name -> id of the scripts already in the target folder
+        let mut scripts_in_folder: HashMap<String, i32> = existing_scripts
+            .iter()
+            .map(|script| (script.name.clone(), script.id))
+            .collect();
+        let folder_id = match existing_folder {
+            Some(existing_folder) => existing_folder.id,
+            None => {
+                let ordering = next_folder_ordering.entry(parent_id).or_default();
+                let created = db
+                    .scripts_folder()
+                    .create(
+                        folder.name.clone(),
+                        *ordering,
+                        vec![crate::prisma::scripts_folder::parent_id::set(parent_id)],
+                    )
+                    .exec()
+                    .await?;
+                *ordering += 1;
+                summary.folders_created += 1;
+                created.id
+            }
+        };
+        let mut subfolders = std::mem::take(&mut folder.folders);
+        subfolders.sort_by_key(|subfolder| subfolder.ordering);
+        pending_folders.extend(
+            subfolders
+                .into_iter()
+                .map(|subfolder| (Some(folder_id), subfolder)),
+        );
+        // Imported scripts go after the ones the folder already has
+        let ordering_offset = existing_folder
+            .and_then(|f| f.rel_scriptsfolder_shellscript().ok())
+            .and_then(|rels| rels.iter().map(|rel| rel.ordering + 1).max())
+            .unwrap_or(0);
+
+        for script in folder.scripts {
+            let conflicting_script_id = scripts_in_folder.get(&script.name).copied();
+            match (conflicting_script_id, conflict_policy) {
+                (Some(_), ConflictPolicy::Skip) => {
+                    summary.scripts_skipped += 1;
+                }
+                (Some(existing_script_id), ConflictPolicy::Overwrite) => {
+                    let script_id = detach_shared_script(db, existing_script_id, folder_id).await?;
+                    overwrite_script(db, script_id, &script).await?;
+                    scripts_in_folder.insert(script.name.clone(), script_id);
+                    summary.scripts_updated += 1;
+                }
+                _ => {
+                    let script_id = match created_script_ids.get(&script.id) {
+                        Some(script_id) => *script_id,
+                        None => {
+                            let name = match conflicting_script_id {
+                                Some(_) => keep_both_name(&script.name, &scripts_in_folder),
+                                None => script.name.clone(),
+                            };
+                            let script_id = create_script(db, name, &script).await?;
+                            summary.scripts_created += 1;
+                            created_script_ids.insert(script.id, script_id);
+                            script_id
+                        }
+                    };
+                    // A shared script gets the position it has in each of its folders
+                    db.rel_scriptsfolder_shellscript()
+                        .create(
+                            crate::prisma::shell_script::UniqueWhereParam::IdEquals(script_id),
+                            crate::prisma::scripts_folder::UniqueWhereParam::IdEquals(folder_id),
+                            vec![crate::prisma::rel_scriptsfolder_shellscript::ordering::set(
+                                ordering_offset + script.ordering,
+                            )],
+                        )
+                        .exec()
+                        .await?;
+                    scripts_in_folder.insert(script.name.clone(), script_id);
+                }
+            }
+        }
+    }
+    delete_unused_tags(db).await?;
+    renumber_folders(db).await?;
+    Ok(summary)
+}
+
+// Orderings 0, 1, 2, ... among the siblings of every folder, e.g. after an import put new folders
+// after existing ones with gaps in their orderings
+async fn renumber_folders(db: &PrismaClient) -> Result<(), QueryError> {
+    let folders = db
+        .scripts_folder()
+        .find_many(vec![crate::prisma::scripts_folder::deleted_at::equals(
+            None,
+        )])
+        .exec()
+        .await?;
+    let mut parent_ids: Vec<Option<i32>> = folders.iter().map(|folder| folder.parent_id).collect();
+    parent_ids.sort_unstable();
+    parent_ids.dedup();
+    for parent_id in parent_ids {
+        for (ordering, folder) in child_folders(&folders, parent_id).into_iter().enumerate() {
+            if folder.ordering == ordering as i32 {
+                continue;
+            }
+            db.scripts_folder()
+                .update_many(
+                    vec![crate::prisma::scripts_folder::id::equals(folder.id)],
+                    vec![crate::prisma::scripts_folder::ordering::set(
+                        ordering as i32,
+                    )],
+                )
+                .exec()
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+// Overwriting a script that is shared with other folders would change it there too. Such a
+// script is copied and the folder linked to the copy instead, which is then overwritten.
+async fn detach_shared_script(
+    db: &PrismaClient,
+    script_id: i32,
+    folder_id: i32,
+) -> Result<i32, QueryError> {
+    let link_count = db
+        .rel_scriptsfolder_shellscript()
+        .count(vec![
+            crate::prisma::rel_scriptsfolder_shellscript::shell_script_id::equals(script_id),
+        ])
+        .exec()
+        .await?;
+    if link_count <= 1 {
+        return Ok(script_id);
+    }
+    let Some(link) = db
+        .rel_scriptsfolder_shellscript()
+        .find_first(vec![
+            crate::prisma::rel_scriptsfolder_shellscript::shell_script_id::equals(script_id),
+            crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::equals(folder_id),
+        ])
+        .exec()
+        .await?
+    else {
+        return Ok(script_id);
+    };
+    let Some(shared_script) = db
+        .shell_script()
+        .find_unique(crate::prisma::shell_script::id::equals(script_id))
+        .with(env_vars_fetch())
+        .with(tags_fetch())
+        .exec()
+        .await?
+    else {
+        return Ok(script_id);
+    };
+    let copy_id = create_script(
+        db,
+        shared_script.name.clone(),
+        &library_script(&shared_script, link.ordering),
+    )
+    .await?;
+    db.rel_scriptsfolder_shellscript()
+        .delete_many(vec![
+            crate::prisma::rel_scriptsfolder_shellscript::shell_script_id::equals(script_id),
+            crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::equals(folder_id),
+        ])
+        .exec()
+        .await?;
+    db.rel_scriptsfolder_shellscript()
+        .create(
+            crate::prisma::shell_script::UniqueWhereParam::IdEquals(copy_id),
+            crate::prisma::scripts_folder::UniqueWhereParam::IdEquals(folder_id),
+            vec![crate::prisma::rel_scriptsfolder_shellscript::ordering::set(
+                link.ordering,
+            )],
+        )
+        .exec()
+        .await?;
+    Ok(copy_id)
+}
+
+async fn import_tasks(
+    db: &PrismaClient,
+    folder_name: String,
+    working_directory: String,
+    tasks: Vec<ProjectTask>,
+) -> Result<(i32, ImportSummary), QueryError> {
+    let mut summary = ImportSummary::default();
+    let folders = db
+        .scripts_folder()
+        .find_many(vec![crate::prisma::scripts_folder::deleted_at::equals(
+            None,
+        )])
+        .with(
+            crate::prisma::scripts_folder::rel_scriptsfolder_shellscript::fetch(live_scripts())
+                .with(crate::prisma::rel_scriptsfolder_shellscript::shell_script::fetch()),
+        )
+        .exec()
+        .await?;
+    // In the order shown in the folder
+    let scripts_of = |folder: &crate::prisma::scripts_folder::Data| {
+        folder
+            .rel_scriptsfolder_shellscript()
+            .map(|rels| {
+                let mut rels = rels.iter().collect::<Vec<_>>();
+                rels.sort_by_key(|rel| rel.ordering);
+                rels.into_iter()
+                    .filter_map(|rel| rel.shell_script().ok())
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    };
+
+    // A folder holding scripts of this project wins, so that renaming the folder does not break
+    // re-imports. A folder that merely has the same name is only taken when none of its scripts
+    // belongs to another project, e.g. `/work/a/api` and `/work/b/api` get a folder each.
+    let existing_folder = folders
+        .iter()
+        .find(|folder| {
+            scripts_of(folder)
+                .iter()
+                .any(|script| script.working_directory.as_deref() == Some(&working_directory))
+        })
+        .or_else(|| {
+            folders.iter().find(|folder| {
+                folder.name == folder_name
+                    && scripts_of(folder)
+                        .iter()
+                        .all(|script| script.working_directory.is_none())
+            })
+        });
+    let (folder_id, existing_scripts) = match existing_folder {
+        Some(folder) => (folder.id, scripts_of(folder)),
+        None => {
+            let ordering = folders
+                .iter()
+                .filter(|f| f.parent_id.is_none())
+                .map(|f| f.ordering + 1)
+                .max()
+                .unwrap_or(0);
+            let created = db
+                .scripts_folder()
+                .create(folder_name, ordering, vec![])
+                .exec()
+                .await?;
+            summary.folders_created += 1;
+            (created.id, vec![])
+        }
+    };
+
+    let task_names: HashSet<String> = tasks.iter().map(|task| task.name.clone()).collect();
+    let mut created_ids = vec![];
+    for task in tasks {
+        match existing_scripts
+            .iter()
+            .find(|script| script.name == task.name)
+        {
+            Some(script)
+                if script.command == task.command
+                    && script.working_directory.as_deref() == Some(&working_directory) =>
+            {
+                summary.scripts_unchanged += 1;
+            }
+            Some(script) => {
+                update_name_and_command(db, script.id, None, Some(task.command)).await?;
+                db.shell_script()
+                    .update_many(
+                        vec![crate::prisma::shell_script::id::equals(script.id)],
+                        vec![crate::prisma::shell_script::working_directory::set(Some(
+                            working_directory.clone(),
+                        ))],
+                    )
+                    .exec()
+                    .await?;
+                summary.scripts_updated += 1;
+            }
+            None => {
+                let created = db
+                    .shell_script()
+                    .create(
+                        task.name,
+                        task.command,
+                        0,
+                        vec![crate::prisma::shell_script::working_directory::set(Some(
+                            working_directory.clone(),
+                        ))],
+                    )
+                    .exec()
+                    .await?;
+                db.rel_scriptsfolder_shellscript()
+                    .create(
+                        crate::prisma::shell_script::UniqueWhereParam::IdEquals(created.id),
+                        crate::prisma::scripts_folder::UniqueWhereParam::IdEquals(folder_id),
+                        vec![],
+                    )
+                    .exec()
+                    .await?;
+                created_ids.push(created.id);
+                summary.scripts_created += 1;
+            }
+        }
+    }
+
+    // Scripts an earlier import created for tasks the project no longer has would only fail when
+    // run. Scripts added to the folder by hand have no working directory of the project.
+    let stale_ids: Vec<i32> = existing_scripts
+        .iter()
+        .filter(|script| {
+            script.working_directory.as_deref() == Some(&working_directory)
+                && !task_names.contains(&script.name)
+        })
+        .map(|script| script.id)
+        .collect();
+    if !stale_ids.is_empty() {
+        db.shell_script()
+            .update_many(
+                vec![crate::prisma::shell_script::id::in_vec(stale_ids.clone())],
+                vec![crate::prisma::shell_script::deleted_at::set(Some(
+                    now_millis(),
+                ))],
+            )
+            .exec()
+            .await?;
+        summary.scripts_trashed = stale_ids.len();
+    }
+
+    // The remaining scripts keep their order and the new ones follow without gaps
+    let script_ids = existing_scripts
+        .iter()
+        .map(|script| script.id)
+        .filter(|id| !stale_ids.contains(id))
+        .chain(created_ids)
+        .collect();
+    write_script_orderings(db, folder_id, script_ids).await?;
+    Ok((folder_id, summary))
+}
+
+// Links of a folder to its scripts outside of the trash
+fn live_scripts() -> Vec<crate::prisma::rel_scriptsfolder_shellscript::WhereParam> {
+    vec![
+        crate::prisma::rel_scriptsfolder_shellscript::shell_script::is(vec![
+            crate::prisma::shell_script::deleted_at::equals(None),
+        ]),
+    ]
+}
+
+// Children before parents, the foreign keys are RESTRICT
+async fn clear_library(db: &PrismaClient) -> Result<(), QueryError> {
+    db.script_run().delete_many(vec![]).exec().await?;
+    db.script_placeholder_value()
+        .delete_many(vec![])
+        .exec()
+        .await?;
+    db.shell_script_env_var().delete_many(vec![]).exec().await?;
+    db.rel_shellscript_tag().delete_many(vec![]).exec().await?;
+    db.tag().delete_many(vec![]).exec().await?;
+    db.shell_script_revision()
+        .delete_many(vec![])
+        .exec()
+        .await?;
+    db.rel_scriptsfolder_shellscript()
+        .delete_many(vec![])
+        .exec()
+        .await?;
+    db.shell_script().delete_many(vec![]).exec().await?;
+    db.scripts_folder().delete_many(vec![]).exec().await?;
+    db.application_state()
+        .update_many(
+            vec![],
+            vec![crate::prisma::application_state::last_opened_folder_id::set(None)],
+        )
+        .exec()
+        .await?;
+    Ok(())
+}
+
+async fn create_script(
+    db: &PrismaClient,
+    name: String,
+    script: &LibraryScript,
+) -> Result<i32, QueryError> {
+    let mut params = script_params(script);
+    params.push(crate::prisma::shell_script::description::set(
+        script.description.clone(),
+    ));
+    let created = db
+        .shell_script()
+        .create(name, script.command.clone(), 0, params)
+        .exec()
+        .await?;
+    create_env_vars(db, created.id, script).await?;
+    set_tags(db, created.id, &document_tags(script)).await?;
+    Ok(created.id)
+}
+
+// The name is kept, everything else is taken from the document
+async fn overwrite_script(
+    db: &PrismaClient,
+    script_id: i32,
+    script: &LibraryScript,
+) -> Result<(), QueryError> {
+    // An overwritten command can be restored from the script's versions
+    update_name_and_command(db, script_id, None, Some(script.command.clone())).await?;
+    let mut params = script_params(script);
+    // Same as for tags, shell script files carry no description
+    if !script.description.is_empty() {
+        params.push(crate::prisma::shell_script::description::set(
+            script.description.clone(),
+        ));
+    }
+    db.shell_script()
+        .update_many(
+            vec![crate::prisma::shell_script::id::equals(script_id)],
+            params,
+        )
+        .exec()
+        .await?;
+    db.shell_script_env_var()
+        .delete_many(vec![
+            crate::prisma::shell_script_env_var::shell_script_id::equals(script_id),
+        ])
+        .exec()
+        .await?;
+    create_env_vars(db, script_id, script).await?;
+    // Documents without tags, e.g. read from shell script files, keep the tags of the script
+    if !script.tags.is_empty() {
+        set_tags(db, script_id, &document_tags(script)).await?;
+    }
+    Ok(())
+}
+
+fn document_tags(script: &LibraryScript) -> Vec<String> {
+    let mut tags: Vec<String> = script
+        .tags
+        .iter()
+        .filter_map(|tag| normalize_tag(tag))
+        .collect();
+    tags.sort_unstable();
+    tags.dedup();
+    tags
+}
+
+async fn create_env_vars(
+    db: &PrismaClient,
+    script_id: i32,
+    script: &LibraryScript,
+) -> Result<(), QueryError> {
+    for (ordering, env_var) in script.env_vars.iter().enumerate() {
+        db.shell_script_env_var()
+            .create(
+                env_var.key.clone(),
+                env_var.value.clone(),
+                ordering as i32,
+                crate::prisma::shell_script::UniqueWhereParam::IdEquals(script_id),
+                vec![],
+            )
+            .exec()
+            .await?;
+    }
+    Ok(())
+}
+
+fn script_params(script: &LibraryScript) -> Vec<crate::prisma::shell_script::SetParam> {
+    vec![
+        crate::prisma::shell_script::working_directory::set(script.working_directory.clone()),
+        crate::prisma::shell_script::interpreter::set(script.interpreter.clone()),
+        crate::prisma::shell_script::shebang::set(script.shebang.clone()),
+        crate::prisma::shell_script::source_rc::set(script.source_rc),
+    ]
+}
+
+// `deploy` -> `deploy (imported)`, `deploy (imported 2)`, ... whichever is still free in the folder
+fn keep_both_name(name: &str, scripts_in_folder: &HashMap<String, i32>) -> String {
+    (1..)
+        .map(|n| match n {
+            1 => format!("{} (imported)", name),
+            n => format!("{} (imported {})", name, n),
+        })
+        .find(|candidate| !scripts_in_folder.contains_key(candidate))
+        .unwrap()
+}
@@ -1,2 +1,7 @@
 pub mod folder_repository;
+pub mod library_repository;
 pub mod script_repository;
+pub mod script_revision_repository;
+pub mod script_run_repository;
+pub mod tag_repository;
+pub mod trash_repository;
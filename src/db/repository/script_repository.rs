@@ -1,5 +1,52 @@
+use crate::db::repository::script_revision_repository::update_name_and_command;
+use crate::db::repository::script_run_repository::now_millis;
+use crate::db::repository::tag_repository::{delete_unused_tags, set_tags};
+use crate::domain::execution::interpreter::InterpreterSettings;
 use crate::prisma::PrismaClient;
 use crate::prisma::shell_script::Data;
+use std::collections::HashMap;
+
+// Markers around the matched terms in `ScriptSearchHit`, control characters never typed into a script
+pub const SEARCH_HIGHLIGHT_START: char = '\u{2}';
+pub const SEARCH_HIGHLIGHT_END: char = '\u{3}';
+
+#[derive(Debug, Clone)]
+pub struct ScriptSearchHit {
+    pub script: Data,
+    // The whole name, with the matched terms wrapped in SEARCH_HIGHLIGHT_START / SEARCH_HIGHLIGHT_END
+    pub name_highlight: String,
+    // Excerpt of the command around the matched terms, marked the same way
+    pub command_snippet: String,
+    // Excerpt of the description, without markers when only the name or command matched
+    pub description_snippet: String,
+}
+
+#[derive(serde::Deserialize)]
+struct FtsRow {
+    id: i64,
+    name_highlight: String,
+    command_snippet: String,
+    description_snippet: String,
+}
+
+// A script to create, e.g. one of the commands picked from the shell history
+#[derive(Debug, Clone)]
+pub struct NewScript {
+    pub name: String,
+    pub command: String,
+    pub interpreter_settings: InterpreterSettings,
+}
+
+// Everything the edit window saves of a script at once, the tags normalized
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptDetails {
+    pub command: String,
+    pub description: String,
+    pub interpreter_settings: InterpreterSettings,
+    pub working_directory: Option<String>,
+    pub env_vars: Vec<(String, String)>,
+    pub tags: Vec<String>,
+}
 
 pub struct ScriptRepository {
     db: &'static PrismaClient,
@@ -15,129 +62,1020 @@ impl ScriptRepository {
         &self,
         name: String,
         command: String,
+        interpreter_settings: InterpreterSettings,
     ) -> prisma_client_rust::Result<Data> {
         self.db
             .shell_script()
-            .create(name, command, 0, vec![])
+            .create(
+                name,
+                command,
+                0,
+                interpreter_settings_params(interpreter_settings),
+            )
             .exec()
             .await
     }
 
+    // The script goes to the end of the folder
     pub async fn create_script_relationship(
         &self,
         folder_id: i32,
         script_id: i32,
     ) -> prisma_client_rust::Result<()> {
+        let ordering = next_script_ordering_in(self.db, folder_id).await?;
         self.db
             .rel_scriptsfolder_shellscript()
             .create(
                 crate::prisma::shell_script::UniqueWhereParam::IdEquals(script_id),
                 crate::prisma::scripts_folder::UniqueWhereParam::IdEquals(folder_id),
-                vec![],
+                vec![crate::prisma::rel_scriptsfolder_shellscript::ordering::set(
+                    ordering,
+                )],
             )
             .exec()
             .await?;
         Ok(())
     }
 
+    // The replaced command is kept in `shell_script_revision`
     pub async fn update_script_command(
         &self,
         script_id: i32,
         new_command: String,
     ) -> prisma_client_rust::Result<()> {
         self.db
-            .shell_script()
-            .update_many(
-                vec![crate::prisma::shell_script::id::equals(script_id)],
-                vec![crate::prisma::shell_script::command::set(new_command)],
-            )
-            .exec()
-            .await?;
-        Ok(())
+            ._transaction()
+            .run(|tx| async move {
+                update_name_and_command(&tx, script_id, None, Some(new_command))
+                    .await
+                    .map(|_| ())
+            })
+            .await
     }
 
+    // Same as for the command, the old name is kept as a revision
     pub async fn update_script_name(
         &self,
         script_id: i32,
         new_name: String,
+    ) -> prisma_client_rust::Result<()> {
+        self.db
+            ._transaction()
+            .run(|tx| async move {
+                update_name_and_command(&tx, script_id, Some(new_name), None)
+                    .await
+                    .map(|_| ())
+            })
+            .await
+    }
+
+    // All or nothing, a changed command is kept as a revision the same as in
+    // `update_script_command`
+    pub async fn update_script_details(
+        &self,
+        script_id: i32,
+        details: ScriptDetails,
+    ) -> prisma_client_rust::Result<()> {
+        self.db
+            ._transaction()
+            .run(|tx| async move { write_script_details(&tx, script_id, details).await })
+            .await
+    }
+
+    pub async fn update_script_interpreter(
+        &self,
+        script_id: i32,
+        interpreter_settings: InterpreterSettings,
     ) -> prisma_client_rust::Result<()> {
         self.db
             .shell_script()
             .update_many(
                 vec![crate::prisma::shell_script::id::equals(script_id)],
-                vec![crate::prisma::shell_script::name::set(new_name)],
+                interpreter_settings_params(interpreter_settings),
             )
             .exec()
             .await?;
         Ok(())
     }
 
+    // The working directory and all env vars are replaced in one transaction, a failure keeps the
+    // old environment
+    pub async fn update_script_environment(
+        &self,
+        script_id: i32,
+        working_directory: Option<String>,
+        env_vars: Vec<(String, String)>,
+    ) -> prisma_client_rust::Result<()> {
+        self.db
+            ._transaction()
+            .run(|tx| async move {
+                write_script_environment(&tx, script_id, working_directory, env_vars).await
+            })
+            .await
+    }
+
+    // A script in the trash is not found
+    pub async fn get_script_by_id(
+        &self,
+        script_id: i32,
+    ) -> prisma_client_rust::Result<Option<Data>> {
+        self.db
+            .shell_script()
+            .find_first(vec![
+                crate::prisma::shell_script::id::equals(script_id),
+                crate::prisma::shell_script::deleted_at::equals(None),
+            ])
+            .with(folders_fetch())
+            .with(tags_fetch())
+            .with(env_vars_fetch())
+            .exec()
+            .await
+    }
+
+    // Together with every folder each script is linked to, not only this one. The order is the
+    // one of the folder's links, a script shared by several folders has a position in each.
     pub async fn get_scripts_by_folder(
         &self,
         folder_id: i32,
     ) -> prisma_client_rust::Result<Vec<Data>> {
-        self.db
-            .shell_script()
+        let links = self
+            .db
+            .rel_scriptsfolder_shellscript()
             .find_many(vec![
-                crate::prisma::shell_script::rel_scriptsfolder_shellscript::some(vec![
-                    crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::equals(
-                        folder_id,
-                    ),
+                crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::equals(folder_id),
+                crate::prisma::rel_scriptsfolder_shellscript::shell_script::is(vec![
+                    crate::prisma::shell_script::deleted_at::equals(None),
                 ]),
             ])
+            .order_by(
+                crate::prisma::rel_scriptsfolder_shellscript::ordering::order(
+                    prisma_client_rust::Direction::Asc,
+                ),
+            )
+            .order_by(
+                crate::prisma::rel_scriptsfolder_shellscript::shell_script_id::order(
+                    prisma_client_rust::Direction::Asc,
+                ),
+            )
+            .with(
+                crate::prisma::rel_scriptsfolder_shellscript::shell_script::fetch()
+                    .with(folders_fetch())
+                    .with(tags_fetch())
+                    .with(env_vars_fetch()),
+            )
             .exec()
+            .await?;
+        Ok(links
+            .into_iter()
+            .filter_map(|link| link.shell_script.map(|script| *script))
+            .collect())
+    }
+
+    // All or nothing, the scripts go to the end of the folder in the given order. Returns the ids
+    // of the created scripts.
+    pub async fn add_scripts_to_folder(
+        &self,
+        folder_id: i32,
+        scripts: Vec<NewScript>,
+    ) -> prisma_client_rust::Result<Vec<i32>> {
+        self.db
+            ._transaction()
+            .run(|tx| async move { create_scripts_in_folder(&tx, folder_id, scripts).await })
             .await
     }
 
-    pub async fn get_scripts_with_relations_by_folder(
+    // Moves the script at `from_index` of the folder to `to_index`. All orderings of the folder
+    // are rewritten in one transaction, a failure keeps the old order.
+    pub async fn reorder_scripts(
         &self,
         folder_id: i32,
-    ) -> prisma_client_rust::Result<Vec<crate::prisma::shell_script::Data>> {
-        let scripts = self
-            .db
+        from_index: usize,
+        to_index: usize,
+    ) -> prisma_client_rust::Result<()> {
+        let mut script_ids: Vec<i32> = self
+            .get_scripts_by_folder(folder_id)
+            .await?
+            .iter()
+            .map(|script| script.id)
+            .collect();
+        if from_index >= script_ids.len() || to_index >= script_ids.len() {
+            return Ok(());
+        }
+        let script_id = script_ids.remove(from_index);
+        script_ids.insert(to_index, script_id);
+
+        self.db
+            ._transaction()
+            .run(|tx| async move { write_script_orderings(&tx, folder_id, script_ids).await })
+            .await
+    }
+
+    // Pinned scripts of every folder, in the ordering of the "Pinned" list
+    pub async fn get_pinned_scripts(&self) -> prisma_client_rust::Result<Vec<Data>> {
+        self.db
             .shell_script()
-            .find_many(vec![])
-            .with(
-                crate::prisma::shell_script::rel_scriptsfolder_shellscript::fetch(vec![
-                    crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::equals(
-                        folder_id,
-                    ),
-                ]),
+            .find_many(vec![
+                crate::prisma::shell_script::pinned::equals(true),
+                crate::prisma::shell_script::deleted_at::equals(None),
+            ])
+            .order_by(crate::prisma::shell_script::pinned_ordering::order(
+                prisma_client_rust::Direction::Asc,
+            ))
+            .order_by(crate::prisma::shell_script::id::order(
+                prisma_client_rust::Direction::Asc,
+            ))
+            .with(folders_fetch())
+            .with(tags_fetch())
+            .with(env_vars_fetch())
+            .exec()
+            .await
+    }
+
+    // A newly pinned script goes to the end of the "Pinned" list
+    pub async fn set_script_pinned(
+        &self,
+        script_id: i32,
+        pinned: bool,
+    ) -> prisma_client_rust::Result<()> {
+        let mut params = vec![crate::prisma::shell_script::pinned::set(pinned)];
+        if pinned {
+            let last_pinned = self
+                .db
+                .shell_script()
+                .find_first(vec![crate::prisma::shell_script::pinned::equals(true)])
+                .order_by(crate::prisma::shell_script::pinned_ordering::order(
+                    prisma_client_rust::Direction::Desc,
+                ))
+                .exec()
+                .await?;
+            let pinned_ordering = last_pinned
+                .map(|script| script.pinned_ordering + 1)
+                .unwrap_or(0);
+            params.push(crate::prisma::shell_script::pinned_ordering::set(
+                pinned_ordering,
+            ));
+        }
+        self.db
+            .shell_script()
+            .update_many(
+                vec![crate::prisma::shell_script::id::equals(script_id)],
+                params,
             )
             .exec()
             .await?;
+        Ok(())
+    }
 
-        // Filter scripts that actually belong to this folder
-        let folder_scripts: Vec<_> = scripts
-            .into_iter()
-            .filter(|script| {
-                script
-                    .rel_scriptsfolder_shellscript
-                    .as_ref()
-                    .map(|rels| !rels.is_empty())
-                    .unwrap_or(false)
-            })
+    // Same as `reorder_scripts`, on the "Pinned" list whose ordering is separate from the folders'
+    pub async fn reorder_pinned_scripts(
+        &self,
+        from_index: usize,
+        to_index: usize,
+    ) -> prisma_client_rust::Result<()> {
+        let mut script_ids: Vec<i32> = self
+            .get_pinned_scripts()
+            .await?
+            .iter()
+            .map(|script| script.id)
             .collect();
+        if from_index >= script_ids.len() || to_index >= script_ids.len() {
+            return Ok(());
+        }
+        let script_id = script_ids.remove(from_index);
+        script_ids.insert(to_index, script_id);
 
-        Ok(folder_scripts)
+        self.db
+            ._transaction()
+            .run(|tx| async move { write_pinned_orderings(&tx, script_ids).await })
+            .await
     }
 
-    pub async fn delete_script(&self, script_id: i32) -> prisma_client_rust::Result<()> {
-        // First delete the relationship record to avoid foreign key constraint error
+    // Links an existing script into one more folder, nothing happens when it is already there
+    pub async fn add_script_to_folder(
+        &self,
+        folder_id: i32,
+        script_id: i32,
+    ) -> prisma_client_rust::Result<()> {
+        let existing = self
+            .db
+            .rel_scriptsfolder_shellscript()
+            .count(vec![
+                crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::equals(folder_id),
+                crate::prisma::rel_scriptsfolder_shellscript::shell_script_id::equals(script_id),
+            ])
+            .exec()
+            .await?;
+        if existing > 0 {
+            return Ok(());
+        }
+        self.create_script_relationship(folder_id, script_id).await
+    }
+
+    // Moves the script's link from one folder to the other, to `index` among the scripts there or
+    // to the end. Returns the position it had in the folder it left, `None` without changing
+    // anything when the script already is in the target folder: dropping the source link then
+    // would take the script out of a folder without a way back.
+    pub async fn move_script_to_folder(
+        &self,
+        script_id: i32,
+        from_folder_id: i32,
+        to_folder_id: i32,
+        index: Option<usize>,
+    ) -> prisma_client_rust::Result<Option<usize>> {
+        self.db
+            ._transaction()
+            .run(|tx| async move {
+                move_script_in(&tx, script_id, from_folder_id, to_folder_id, index).await
+            })
+            .await
+    }
+
+    // Unlinks the script from the folder. A script that would be left without any folder goes to
+    // the trash instead, the same rule `trash_folder` follows, and keeps its link so that a
+    // restore puts it back into this folder.
+    pub async fn remove_script_from_folder(
+        &self,
+        folder_id: i32,
+        script_id: i32,
+    ) -> prisma_client_rust::Result<()> {
+        let other_folders = self
+            .db
+            .rel_scriptsfolder_shellscript()
+            .count(vec![
+                crate::prisma::rel_scriptsfolder_shellscript::shell_script_id::equals(script_id),
+                crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::not(folder_id),
+                crate::prisma::rel_scriptsfolder_shellscript::scripts_folder::is(vec![
+                    crate::prisma::scripts_folder::deleted_at::equals(None),
+                ]),
+            ])
+            .exec()
+            .await?;
+        if other_folders == 0 {
+            return self.trash_script(script_id).await;
+        }
         self.db
             .rel_scriptsfolder_shellscript()
             .delete_many(vec![
+                crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::equals(folder_id),
                 crate::prisma::rel_scriptsfolder_shellscript::shell_script_id::equals(script_id),
             ])
             .exec()
             .await?;
+        Ok(())
+    }
 
-        // Then delete the script itself
+    // Every script outside of the trash together with the folders it is linked to
+    pub async fn get_all_scripts_with_folders(&self) -> prisma_client_rust::Result<Vec<Data>> {
         self.db
             .shell_script()
-            .delete_many(vec![crate::prisma::shell_script::id::equals(script_id)])
+            .find_many(vec![crate::prisma::shell_script::deleted_at::equals(None)])
+            .with(folders_fetch())
+            .with(tags_fetch())
+            .with(env_vars_fetch())
+            .exec()
+            .await
+    }
+
+    // Ranked full-text search over names, commands and descriptions, backed by the `shell_script_fts` table
+    pub async fn search_scripts(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> prisma_client_rust::Result<Vec<ScriptSearchHit>> {
+        let Some(match_expression) = fts_match_expression(query) else {
+            return Ok(vec![]);
+        };
+        // The index still has the scripts in the trash, they are left out before the LIMIT
+        let rows: Vec<FtsRow> = self
+            .db
+            ._query_raw(prisma_client_rust::raw!(
+                "SELECT shell_script_fts.rowid AS id, \
+                    highlight(shell_script_fts, 0, char(2), char(3)) AS name_highlight, \
+                    snippet(shell_script_fts, 1, char(2), char(3), '…', 16) AS command_snippet, \
+                    snippet(shell_script_fts, 2, char(2), char(3), '…', 16) AS description_snippet \
+                FROM shell_script_fts \
+                JOIN shell_script ON shell_script.id = shell_script_fts.rowid \
+                WHERE shell_script_fts MATCH {} AND shell_script.deleted_at IS NULL \
+                ORDER BY bm25(shell_script_fts, 10.0, 1.0, 3.0) \
+                LIMIT {}",
+                prisma_client_rust::PrismaValue::String(match_expression),
+                prisma_client_rust::PrismaValue::Int(limit as i64)
+            ))
             .exec()
             .await?;
+
+        let ids: Vec<i32> = rows.iter().map(|row| row.id as i32).collect();
+        let mut scripts: HashMap<i32, Data> = self
+            .db
+            .shell_script()
+            .find_many(vec![crate::prisma::shell_script::id::in_vec(ids)])
+            .with(folders_fetch())
+            .with(tags_fetch())
+            .with(env_vars_fetch())
+            .exec()
+            .await?
+            .into_iter()
+            .map(|script| (script.id, script))
+            .collect();
+
+        // Keep the bm25 order of the index
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                scripts
+                    .remove(&(row.id as i32))
+                    .map(|script| ScriptSearchHit {
+                        script,
+                        name_highlight: row.name_highlight,
+                        command_snippet: row.command_snippet,
+                        description_snippet: row.description_snippet,
+                    })
+            })
+            .collect())
+    }
+
+    pub async fn get_placeholder_values(
+        &self,
+        script_id: i32,
+    ) -> prisma_client_rust::Result<Vec<crate::prisma::script_placeholder_value::Data>> {
+        self.db
+            .script_placeholder_value()
+            .find_many(vec![
+                crate::prisma::script_placeholder_value::shell_script_id::equals(script_id),
+            ])
+            .exec()
+            .await
+    }
+
+    pub async fn save_placeholder_values(
+        &self,
+        script_id: i32,
+        values: Vec<(String, String)>,
+    ) -> prisma_client_rust::Result<()> {
+        // Replace the previously remembered values of these placeholders
+        self.db
+            .script_placeholder_value()
+            .delete_many(vec![
+                crate::prisma::script_placeholder_value::shell_script_id::equals(script_id),
+                crate::prisma::script_placeholder_value::name::in_vec(
+                    values.iter().map(|(name, _)| name.clone()).collect(),
+                ),
+            ])
+            .exec()
+            .await?;
+
+        for (name, value) in values {
+            self.db
+                .script_placeholder_value()
+                .create(
+                    name,
+                    value,
+                    crate::prisma::shell_script::UniqueWhereParam::IdEquals(script_id),
+                    vec![],
+                )
+                .exec()
+                .await?;
+        }
         Ok(())
     }
+
+    // The script leaves all of its folders at once, its links are kept for a restore
+    pub async fn trash_script(&self, script_id: i32) -> prisma_client_rust::Result<()> {
+        self.db
+            .shell_script()
+            .update_many(
+                vec![crate::prisma::shell_script::id::equals(script_id)],
+                vec![crate::prisma::shell_script::deleted_at::set(Some(
+                    now_millis(),
+                ))],
+            )
+            .exec()
+            .await?;
+        Ok(())
+    }
+
+    // Stamped with the same `deleted_at`, in one statement
+    pub async fn trash_scripts(&self, script_ids: Vec<i32>) -> prisma_client_rust::Result<()> {
+        self.db
+            .shell_script()
+            .update_many(
+                vec![crate::prisma::shell_script::id::in_vec(script_ids)],
+                vec![crate::prisma::shell_script::deleted_at::set(Some(
+                    now_millis(),
+                ))],
+            )
+            .exec()
+            .await?;
+        Ok(())
+    }
+
+    // For good, scripts are only deleted this way from the trash. All or nothing.
+    pub async fn delete_script(&self, script_id: i32) -> prisma_client_rust::Result<()> {
+        self.db
+            ._transaction()
+            .run(|tx| async move { delete_script_in(&tx, script_id).await })
+            .await
+    }
+}
+
+// Rows owned by a script that have to go before the script itself (foreign keys are RESTRICT)
+pub async fn delete_script_dependents_in(
+    db: &PrismaClient,
+    script_id: i32,
+) -> Result<(), prisma_client_rust::QueryError> {
+    db.script_run()
+        .delete_many(vec![crate::prisma::script_run::shell_script_id::equals(
+            script_id,
+        )])
+        .exec()
+        .await?;
+    db.script_placeholder_value()
+        .delete_many(vec![
+            crate::prisma::script_placeholder_value::shell_script_id::equals(script_id),
+        ])
+        .exec()
+        .await?;
+    db.shell_script_env_var()
+        .delete_many(vec![
+            crate::prisma::shell_script_env_var::shell_script_id::equals(script_id),
+        ])
+        .exec()
+        .await?;
+    db.rel_shellscript_tag()
+        .delete_many(vec![
+            crate::prisma::rel_shellscript_tag::shell_script_id::equals(script_id),
+        ])
+        .exec()
+        .await?;
+    db.shell_script_revision()
+        .delete_many(vec![
+            crate::prisma::shell_script_revision::shell_script_id::equals(script_id),
+        ])
+        .exec()
+        .await?;
+    delete_unused_tags(db).await?;
+    Ok(())
+}
+
+// The dependent rows and the links go first, then the script itself
+pub async fn delete_script_in(
+    db: &PrismaClient,
+    script_id: i32,
+) -> Result<(), prisma_client_rust::QueryError> {
+    delete_script_dependents_in(db, script_id).await?;
+    db.rel_scriptsfolder_shellscript()
+        .delete_many(vec![
+            crate::prisma::rel_scriptsfolder_shellscript::shell_script_id::equals(script_id),
+        ])
+        .exec()
+        .await?;
+    db.shell_script()
+        .delete_many(vec![crate::prisma::shell_script::id::equals(script_id)])
+        .exec()
+        .await?;
+    Ok(())
+}
+
+// Scripts are always loaded together with their environment overrides, the runner needs them
+pub fn env_vars_fetch() -> crate::prisma::shell_script::WithParam {
+    crate::prisma::shell_script::shell_script_env_var::fetch(vec![])
+        .order_by(crate::prisma::shell_script_env_var::ordering::order(
+            prisma_client_rust::Direction::Asc,
+        ))
+        .into()
+}
+
+// Position that puts a new script of the folder after all of its current ones
+async fn next_script_ordering_in(
+    db: &PrismaClient,
+    folder_id: i32,
+) -> Result<i32, prisma_client_rust::QueryError> {
+    let last_link = db
+        .rel_scriptsfolder_shellscript()
+        .find_first(vec![
+            crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::equals(folder_id),
+        ])
+        .order_by(
+            crate::prisma::rel_scriptsfolder_shellscript::ordering::order(
+                prisma_client_rust::Direction::Desc,
+            ),
+        )
+        .exec()
+        .await?;
+    Ok(last_link.map(|link| link.ordering + 1).unwrap_or(0))
+}
+
+async fn move_script_in(
+    db: &PrismaClient,
+    script_id: i32,
+    from_folder_id: i32,
+    to_folder_id: i32,
+    index: Option<usize>,
+) -> Result<Option<usize>, prisma_client_rust::QueryError> {
+    let target_links = db
+        .rel_scriptsfolder_shellscript()
+        .count(vec![
+            crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::equals(to_folder_id),
+            crate::prisma::rel_scriptsfolder_shellscript::shell_script_id::equals(script_id),
+        ])
+        .exec()
+        .await?;
+    if target_links > 0 {
+        return Ok(None);
+    }
+    let source_ids = live_script_ids_in(db, from_folder_id).await?;
+    let source_index = source_ids
+        .iter()
+        .position(|id| *id == script_id)
+        .unwrap_or(source_ids.len());
+
+    let ordering = next_script_ordering_in(db, to_folder_id).await?;
+    db.rel_scriptsfolder_shellscript()
+        .create(
+            crate::prisma::shell_script::UniqueWhereParam::IdEquals(script_id),
+            crate::prisma::scripts_folder::UniqueWhereParam::IdEquals(to_folder_id),
+            vec![crate::prisma::rel_scriptsfolder_shellscript::ordering::set(
+                ordering,
+            )],
+        )
+        .exec()
+        .await?;
+    db.rel_scriptsfolder_shellscript()
+        .delete_many(vec![
+            crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::equals(from_folder_id),
+            crate::prisma::rel_scriptsfolder_shellscript::shell_script_id::equals(script_id),
+        ])
+        .exec()
+        .await?;
+
+    if let Some(index) = index {
+        let mut target_ids = live_script_ids_in(db, to_folder_id).await?;
+        target_ids.retain(|id| *id != script_id);
+        target_ids.insert(index.min(target_ids.len()), script_id);
+        write_script_orderings(db, to_folder_id, target_ids).await?;
+    }
+    Ok(Some(source_index))
+}
+
+// The scripts of the folder outside of the trash, in the order of `get_scripts_by_folder`
+async fn live_script_ids_in(
+    db: &PrismaClient,
+    folder_id: i32,
+) -> Result<Vec<i32>, prisma_client_rust::QueryError> {
+    let links = db
+        .rel_scriptsfolder_shellscript()
+        .find_many(vec![
+            crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::equals(folder_id),
+            crate::prisma::rel_scriptsfolder_shellscript::shell_script::is(vec![
+                crate::prisma::shell_script::deleted_at::equals(None),
+            ]),
+        ])
+        .order_by(
+            crate::prisma::rel_scriptsfolder_shellscript::ordering::order(
+                prisma_client_rust::Direction::Asc,
+            ),
+        )
+        .order_by(
+            crate::prisma::rel_scriptsfolder_shellscript::shell_script_id::order(
+                prisma_client_rust::Direction::Asc,
+            ),
+        )
+        .exec()
+        .await?;
+    Ok(links.into_iter().map(|link| link.shell_script_id).collect())
+}
+
+async fn create_scripts_in_folder(
+    db: &PrismaClient,
+    folder_id: i32,
+    scripts: Vec<NewScript>,
+) -> Result<Vec<i32>, prisma_client_rust::QueryError> {
+    let first_ordering = next_script_ordering_in(db, folder_id).await?;
+    let mut script_ids = vec![];
+    for (index, script) in scripts.into_iter().enumerate() {
+        let created = db
+            .shell_script()
+            .create(
+                script.name,
+                script.command,
+                0,
+                interpreter_settings_params(script.interpreter_settings),
+            )
+            .exec()
+            .await?;
+        db.rel_scriptsfolder_shellscript()
+            .create(
+                crate::prisma::shell_script::UniqueWhereParam::IdEquals(created.id),
+                crate::prisma::scripts_folder::UniqueWhereParam::IdEquals(folder_id),
+                vec![crate::prisma::rel_scriptsfolder_shellscript::ordering::set(
+                    first_ordering + index as i32,
+                )],
+            )
+            .exec()
+            .await?;
+        script_ids.push(created.id);
+    }
+    Ok(script_ids)
+}
+
+async fn write_script_details(
+    db: &PrismaClient,
+    script_id: i32,
+    details: ScriptDetails,
+) -> Result<(), prisma_client_rust::QueryError> {
+    update_name_and_command(db, script_id, None, Some(details.command)).await?;
+    let mut params = interpreter_settings_params(details.interpreter_settings);
+    params.push(crate::prisma::shell_script::description::set(
+        details.description,
+    ));
+    db.shell_script()
+        .update_many(
+            vec![crate::prisma::shell_script::id::equals(script_id)],
+            params,
+        )
+        .exec()
+        .await?;
+    write_script_environment(db, script_id, details.working_directory, details.env_vars).await?;
+    set_tags(db, script_id, &details.tags).await?;
+    delete_unused_tags(db).await
+}
+
+async fn write_script_environment(
+    db: &PrismaClient,
+    script_id: i32,
+    working_directory: Option<String>,
+    env_vars: Vec<(String, String)>,
+) -> Result<(), prisma_client_rust::QueryError> {
+    db.shell_script()
+        .update_many(
+            vec![crate::prisma::shell_script::id::equals(script_id)],
+            vec![crate::prisma::shell_script::working_directory::set(
+                working_directory,
+            )],
+        )
+        .exec()
+        .await?;
+
+    db.shell_script_env_var()
+        .delete_many(vec![
+            crate::prisma::shell_script_env_var::shell_script_id::equals(script_id),
+        ])
+        .exec()
+        .await?;
+    for (ordering, (key, value)) in env_vars.into_iter().enumerate() {
+        db.shell_script_env_var()
+            .create(
+                key,
+                value,
+                ordering as i32,
+                crate::prisma::shell_script::UniqueWhereParam::IdEquals(script_id),
+                vec![],
+            )
+            .exec()
+            .await?;
+    }
+    Ok(())
+}
+
+// Only the links of this folder, the positions of shared scripts in other folders stay as they are
+pub async fn write_script_orderings(
+    db: &PrismaClient,
+    folder_id: i32,
+    script_ids: Vec<i32>,
+) -> Result<(), prisma_client_rust::QueryError> {
+    for (ordering, script_id) in script_ids.into_iter().enumerate() {
+        db.rel_scriptsfolder_shellscript()
+            .update_many(
+                vec![
+                    crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::equals(
+                        folder_id,
+                    ),
+                    crate::prisma::rel_scriptsfolder_shellscript::shell_script_id::equals(
+                        script_id,
+                    ),
+                ],
+                vec![crate::prisma::rel_scriptsfolder_shellscript::ordering::set(
+                    ordering as i32,
+                )],
+            )
+            .exec()
+            .await?;
+    }
+    Ok(())
+}
+
+async fn write_pinned_orderings(
+    db: &PrismaClient,
+    script_ids: Vec<i32>,
+) -> Result<(), prisma_client_rust::QueryError> {
+    for (pinned_ordering, script_id) in script_ids.into_iter().enumerate() {
+        db.shell_script()
+            .update_many(
+                vec![crate::prisma::shell_script::id::equals(script_id)],
+                vec![crate::prisma::shell_script::pinned_ordering::set(
+                    pinned_ordering as i32,
+                )],
+            )
+            .exec()
+            .await?;
+    }
+    Ok(())
+}
+
+// The folders of a script outside of the trash, through its links in `rel_scriptsfolder_shellscript`
+pub fn folders_fetch() -> crate::prisma::shell_script::WithParam {
+    crate::prisma::shell_script::rel_scriptsfolder_shellscript::fetch(vec![
+        crate::prisma::rel_scriptsfolder_shellscript::scripts_folder::is(vec![
+            crate::prisma::scripts_folder::deleted_at::equals(None),
+        ]),
+    ])
+    .with(crate::prisma::rel_scriptsfolder_shellscript::scripts_folder::fetch())
+    .into()
+}
+
+// The tags of a script, through its links in `rel_shellscript_tag`
+pub fn tags_fetch() -> crate::prisma::shell_script::WithParam {
+    crate::prisma::shell_script::rel_shellscript_tag::fetch(vec![])
+        .with(crate::prisma::rel_shellscript_tag::tag::fetch())
+        .into()
+}
+
+fn interpreter_settings_params(
+    interpreter_settings: InterpreterSettings,
+) -> Vec<crate::prisma::shell_script::SetParam> {
+    vec![
+        crate::prisma::shell_script::interpreter::set(
+            interpreter_settings.interpreter.as_str().to_string(),
+        ),
+        crate::prisma::shell_script::shebang::set(interpreter_settings.shebang),
+        crate::prisma::shell_script::source_rc::set(interpreter_settings.source_rc),
+    ]
+}
+
+// Every word of the user's query becomes a quoted prefix term, so that FTS5 syntax characters
+// (`-`, `:`, `*`, quotes, ...) typed into the search box are searched for instead of failing the query
+fn fts_match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // A database of its own with all migrations applied, leaked like the app's client
+    async fn test_repository() -> ScriptRepository {
+        static NEXT_DATABASE: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "shell-script-manager-test-{}-{}.db",
+            std::process::id(),
+            NEXT_DATABASE.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_file(&path);
+        let db = crate::prisma::new_client_with_url(&format!("file:{}", path.display()))
+            .await
+            .unwrap();
+        db._migrate_deploy().await.unwrap();
+        ScriptRepository {
+            db: Box::leak(Box::new(db)),
+        }
+    }
+
+    async fn create_folder(repository: &ScriptRepository, name: &str, ordering: i32) -> i32 {
+        repository
+            .db
+            .scripts_folder()
+            .create(name.to_string(), ordering, vec![])
+            .exec()
+            .await
+            .unwrap()
+            .id
+    }
+
+    async fn create_script(repository: &ScriptRepository) -> i32 {
+        repository
+            .create_script(
+                "Deploy".to_string(),
+                "./deploy".to_string(),
+                InterpreterSettings::default(),
+            )
+            .await
+            .unwrap()
+            .id
+    }
+
+    async fn folder_ids_of(repository: &ScriptRepository, script_id: i32) -> Vec<i32> {
+        let mut folder_ids: Vec<i32> = repository
+            .db
+            .rel_scriptsfolder_shellscript()
+            .find_many(vec![
+                crate::prisma::rel_scriptsfolder_shellscript::shell_script_id::equals(script_id),
+            ])
+            .exec()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|link| link.scripts_folder_id)
+            .collect();
+        folder_ids.sort_unstable();
+        folder_ids
+    }
+
+    #[tokio::test]
+    async fn moves_the_link_to_the_end_of_the_target_folder() {
+        let repository = test_repository().await;
+        let from_folder_id = create_folder(&repository, "From", 0).await;
+        let to_folder_id = create_folder(&repository, "To", 1).await;
+        let other_script_id = create_script(&repository).await;
+        let script_id = create_script(&repository).await;
+        repository
+            .create_script_relationship(to_folder_id, other_script_id)
+            .await
+            .unwrap();
+        repository
+            .create_script_relationship(from_folder_id, script_id)
+            .await
+            .unwrap();
+
+        let moved = repository
+            .move_script_to_folder(script_id, from_folder_id, to_folder_id, None)
+            .await
+            .unwrap();
+
+        assert_eq!(moved, Some(0));
+        assert_eq!(
+            folder_ids_of(&repository, script_id).await,
+            vec![to_folder_id]
+        );
+        let scripts = repository
+            .get_scripts_by_folder(to_folder_id)
+            .await
+            .unwrap();
+        let ids: Vec<i32> = scripts.iter().map(|script| script.id).collect();
+        assert_eq!(ids, vec![other_script_id, script_id]);
+    }
+
+    #[tokio::test]
+    async fn moving_into_a_folder_that_already_holds_the_script_changes_nothing() {
+        let repository = test_repository().await;
+        let from_folder_id = create_folder(&repository, "From", 0).await;
+        let to_folder_id = create_folder(&repository, "To", 1).await;
+        let script_id = create_script(&repository).await;
+        repository
+            .create_script_relationship(from_folder_id, script_id)
+            .await
+            .unwrap();
+        repository
+            .create_script_relationship(to_folder_id, script_id)
+            .await
+            .unwrap();
+
+        let moved = repository
+            .move_script_to_folder(script_id, from_folder_id, to_folder_id, None)
+            .await
+            .unwrap();
+
+        assert_eq!(moved, None);
+        assert_eq!(
+            folder_ids_of(&repository, script_id).await,
+            vec![from_folder_id, to_folder_id]
+        );
+    }
+
+    #[tokio::test]
+    async fn moving_back_to_the_returned_position_restores_the_order() {
+        let repository = test_repository().await;
+        let from_folder_id = create_folder(&repository, "From", 0).await;
+        let to_folder_id = create_folder(&repository, "To", 1).await;
+        let mut script_ids = vec![];
+        for _ in 0..3 {
+            let script_id = create_script(&repository).await;
+            repository
+                .create_script_relationship(from_folder_id, script_id)
+                .await
+                .unwrap();
+            script_ids.push(script_id);
+        }
+
+        let index = repository
+            .move_script_to_folder(script_ids[1], from_folder_id, to_folder_id, None)
+            .await
+            .unwrap();
+        repository
+            .move_script_to_folder(script_ids[1], to_folder_id, from_folder_id, index)
+            .await
+            .unwrap();
+
+        assert_eq!(index, Some(1));
+        let scripts = repository
+            .get_scripts_by_folder(from_folder_id)
+            .await
+            .unwrap();
+        let ids: Vec<i32> = scripts.iter().map(|script| script.id).collect();
+        assert_eq!(ids, script_ids);
+    }
 }
@@ -0,0 +1,111 @@
+use crate::prisma::PrismaClient;
+use crate::prisma::shell_script_revision::Data;
+use prisma_client_rust::QueryError;
+
+pub struct ScriptRevisionRepository {
+    db: &'static PrismaClient,
+}
+
+impl ScriptRevisionRepository {
+    pub fn new() -> Self {
+        let db = crate::db::get_db::get_db();
+        Self { db }
+    }
+
+    // Latest first
+    pub async fn get_revisions_by_script(
+        &self,
+        script_id: i32,
+    ) -> prisma_client_rust::Result<Vec<Data>> {
+        self.db
+            .shell_script_revision()
+            .find_many(vec![
+                crate::prisma::shell_script_revision::shell_script_id::equals(script_id),
+            ])
+            .order_by(crate::prisma::shell_script_revision::created_at::order(
+                prisma_client_rust::Direction::Desc,
+            ))
+            .order_by(crate::prisma::shell_script_revision::id::order(
+                prisma_client_rust::Direction::Desc,
+            ))
+            .exec()
+            .await
+    }
+
+    // Puts the name and command of the revision back. The replaced version becomes a revision
+    // itself, so a restore can be undone from the same window or by restoring the returned
+    // revision. `None` when nothing changed.
+    pub async fn restore_revision(
+        &self,
+        script_id: i32,
+        revision_id: i32,
+    ) -> prisma_client_rust::Result<Option<i32>> {
+        self.db
+            ._transaction()
+            .run(|tx| async move {
+                let Some(revision) = tx
+                    .shell_script_revision()
+                    .find_unique(crate::prisma::shell_script_revision::id::equals(
+                        revision_id,
+                    ))
+                    .exec()
+                    .await?
+                else {
+                    return Ok(None);
+                };
+                update_name_and_command(&tx, script_id, Some(revision.name), Some(revision.command))
+                    .await
+            })
+            .await
+    }
+}
+
+// Every change of the name or command goes through here. The current version is kept as a
+// revision first, unless nothing actually changes, e.g. when the edit window saves an untouched
+// command. Returns the id of that revision.
+pub async fn update_name_and_command(
+    db: &PrismaClient,
+    script_id: i32,
+    name: Option<String>,
+    command: Option<String>,
+) -> Result<Option<i32>, QueryError> {
+    let Some(script) = db
+        .shell_script()
+        .find_unique(crate::prisma::shell_script::id::equals(script_id))
+        .exec()
+        .await?
+    else {
+        return Ok(None);
+    };
+    let name = name.filter(|name| *name != script.name);
+    let command = command.filter(|command| *command != script.command);
+    if name.is_none() && command.is_none() {
+        return Ok(None);
+    }
+
+    let revision = db
+        .shell_script_revision()
+        .create(
+            script.name,
+            script.command,
+            crate::prisma::shell_script::UniqueWhereParam::IdEquals(script_id),
+            vec![],
+        )
+        .exec()
+        .await?;
+    let mut params = vec![];
+    if let Some(name) = name {
+        params.push(crate::prisma::shell_script::name::set(name));
+    }
+    if let Some(command) = command {
+        params.push(crate::prisma::shell_script::command::set(command));
+    }
+    db.shell_script()
+        .update_many(
+            vec![crate::prisma::shell_script::id::equals(script_id)],
+            params,
+        )
+        .exec()
+        .await?;
+    Ok(Some(revision.id))
+}
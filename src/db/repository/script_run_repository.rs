@@ -0,0 +1,173 @@
+use crate::prisma::PrismaClient;
+use crate::prisma::script_run::Data;
+use std::collections::HashMap;
+
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunStats {
+    pub run_count: usize,
+    pub last_started_at: Option<f64>,
+}
+
+pub struct ScriptRunRepository {
+    db: &'static PrismaClient,
+}
+
+impl ScriptRunRepository {
+    pub fn new() -> Self {
+        let db = crate::db::get_db::get_db();
+        Self { db }
+    }
+
+    pub async fn create_run(
+        &self,
+        script_id: i32,
+        shell: String,
+        command: String,
+    ) -> prisma_client_rust::Result<Data> {
+        self.db
+            .script_run()
+            .create(
+                shell,
+                command,
+                crate::prisma::shell_script::UniqueWhereParam::IdEquals(script_id),
+                vec![],
+            )
+            .exec()
+            .await
+    }
+
+    pub async fn finish_run(
+        &self,
+        run_id: i32,
+        exit_code: Option<i32>,
+        output: String,
+    ) -> prisma_client_rust::Result<()> {
+        self.db
+            .script_run()
+            .update_many(
+                vec![crate::prisma::script_run::id::equals(run_id)],
+                vec![
+                    crate::prisma::script_run::exit_code::set(exit_code),
+                    crate::prisma::script_run::output::set(output),
+                    crate::prisma::script_run::finished_at::set(Some(now_millis())),
+                ],
+            )
+            .exec()
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_runs_by_script(
+        &self,
+        script_id: i32,
+    ) -> prisma_client_rust::Result<Vec<Data>> {
+        self.db
+            .script_run()
+            .find_many(vec![crate::prisma::script_run::shell_script_id::equals(
+                script_id,
+            )])
+            .order_by(crate::prisma::script_run::started_at::order(
+                prisma_client_rust::Direction::Desc,
+            ))
+            .exec()
+            .await
+    }
+
+    // Deletes runs started more than `days` ago, either for one script or for all of them.
+    pub async fn prune_runs_older_than_days(
+        &self,
+        script_id: Option<i32>,
+        days: u32,
+    ) -> prisma_client_rust::Result<i64> {
+        let cutoff = now_millis() - f64::from(days) * MILLIS_PER_DAY;
+        let mut filters = vec![crate::prisma::script_run::started_at::lt(cutoff)];
+        if let Some(script_id) = script_id {
+            filters.push(crate::prisma::script_run::shell_script_id::equals(
+                script_id,
+            ));
+        }
+        self.db.script_run().delete_many(filters).exec().await
+    }
+
+    // Number of runs and the latest start time of every script that has been run
+    pub async fn get_run_stats(&self) -> prisma_client_rust::Result<HashMap<i32, RunStats>> {
+        // Only the two columns needed, the captured output can be large
+        let runs = self
+            .db
+            .script_run()
+            .find_many(vec![])
+            .select(crate::prisma::script_run::select!({
+                shell_script_id
+                started_at
+            }))
+            .exec()
+            .await?;
+
+        let mut stats: HashMap<i32, RunStats> = HashMap::new();
+        for run in runs {
+            let entry = stats.entry(run.shell_script_id).or_default();
+            entry.run_count += 1;
+            entry.last_started_at = Some(
+                entry
+                    .last_started_at
+                    .map_or(run.started_at, |last| last.max(run.started_at)),
+            );
+        }
+        Ok(stats)
+    }
+
+    // Keeps only the latest `keep` runs of each script in scope and deletes the rest.
+    pub async fn prune_runs_keeping_latest(
+        &self,
+        script_id: Option<i32>,
+        keep: usize,
+    ) -> prisma_client_rust::Result<i64> {
+        let filters = script_id
+            .map(|id| vec![crate::prisma::script_run::shell_script_id::equals(id)])
+            .unwrap_or_default();
+        // Only the columns needed to pick the runs, the captured output can be large
+        let runs = self
+            .db
+            .script_run()
+            .find_many(filters)
+            .order_by(crate::prisma::script_run::started_at::order(
+                prisma_client_rust::Direction::Desc,
+            ))
+            .select(crate::prisma::script_run::select!({
+                id
+                shell_script_id
+                started_at
+            }))
+            .exec()
+            .await?;
+
+        let mut kept_per_script: HashMap<i32, usize> = HashMap::new();
+        let mut ids_to_delete = vec![];
+        for run in runs {
+            let kept = kept_per_script.entry(run.shell_script_id).or_insert(0);
+            if *kept < keep {
+                *kept += 1;
+            } else {
+                ids_to_delete.push(run.id);
+            }
+        }
+
+        if ids_to_delete.is_empty() {
+            return Ok(0);
+        }
+        self.db
+            .script_run()
+            .delete_many(vec![crate::prisma::script_run::id::in_vec(ids_to_delete)])
+            .exec()
+            .await
+    }
+}
+
+pub fn now_millis() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as f64)
+        .unwrap_or_default()
+}
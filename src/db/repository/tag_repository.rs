@@ -0,0 +1,101 @@
+use crate::db::repository::script_repository::{env_vars_fetch, folders_fetch, tags_fetch};
+use crate::prisma::PrismaClient;
+use crate::prisma::shell_script::Data;
+use prisma_client_rust::QueryError;
+
+pub struct TagRepository {
+    db: &'static PrismaClient,
+}
+
+impl TagRepository {
+    pub fn new() -> Self {
+        let db = crate::db::get_db::get_db();
+        Self { db }
+    }
+
+    // Names of the tags used by at least one script outside of the trash, alphabetically
+    pub async fn get_all_tag_names(&self) -> prisma_client_rust::Result<Vec<String>> {
+        let tags = self
+            .db
+            .tag()
+            .find_many(vec![crate::prisma::tag::rel_shellscript_tag::some(vec![
+                crate::prisma::rel_shellscript_tag::shell_script::is(vec![
+                    crate::prisma::shell_script::deleted_at::equals(None),
+                ]),
+            ])])
+            .order_by(crate::prisma::tag::name::order(
+                prisma_client_rust::Direction::Asc,
+            ))
+            .exec()
+            .await?;
+        Ok(tags.into_iter().map(|tag| tag.name).collect())
+    }
+
+    // Scripts of every folder carrying the tag, by name
+    pub async fn get_scripts_with_tag(
+        &self,
+        tag_name: &str,
+    ) -> prisma_client_rust::Result<Vec<Data>> {
+        self.db
+            .shell_script()
+            .find_many(vec![
+                crate::prisma::shell_script::rel_shellscript_tag::some(vec![
+                    crate::prisma::rel_shellscript_tag::tag::is(vec![
+                        crate::prisma::tag::name::equals(tag_name.to_string()),
+                    ]),
+                ]),
+                crate::prisma::shell_script::deleted_at::equals(None),
+            ])
+            .order_by(crate::prisma::shell_script::name::order(
+                prisma_client_rust::Direction::Asc,
+            ))
+            .with(folders_fetch())
+            .with(tags_fetch())
+            .with(env_vars_fetch())
+            .exec()
+            .await
+    }
+}
+
+// Tags are created on first use, shared by name
+pub async fn set_tags(
+    db: &PrismaClient,
+    script_id: i32,
+    tag_names: &[String],
+) -> Result<(), QueryError> {
+    db.rel_shellscript_tag()
+        .delete_many(vec![
+            crate::prisma::rel_shellscript_tag::shell_script_id::equals(script_id),
+        ])
+        .exec()
+        .await?;
+    for tag_name in tag_names {
+        let tag = db
+            .tag()
+            .upsert(
+                crate::prisma::tag::name::equals(tag_name.clone()),
+                crate::prisma::tag::create(tag_name.clone(), vec![]),
+                vec![],
+            )
+            .exec()
+            .await?;
+        db.rel_shellscript_tag()
+            .create(
+                crate::prisma::shell_script::UniqueWhereParam::IdEquals(script_id),
+                crate::prisma::tag::UniqueWhereParam::IdEquals(tag.id),
+                vec![],
+            )
+            .exec()
+            .await?;
+    }
+    Ok(())
+}
+
+// Keeps the tag filters free of tags nothing is tagged with anymore
+pub async fn delete_unused_tags(db: &PrismaClient) -> Result<(), QueryError> {
+    db.tag()
+        .delete_many(vec![crate::prisma::tag::rel_shellscript_tag::none(vec![])])
+        .exec()
+        .await?;
+    Ok(())
+}
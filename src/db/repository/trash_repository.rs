@@ -0,0 +1,342 @@
+use crate::db::repository::folder_repository::{delete_folder_permanently_in, move_folder_in};
+use crate::db::repository::script_repository::delete_script_in;
+use crate::db::repository::script_run_repository::now_millis;
+use crate::domain::folder::folder_tree::trashed_together;
+use crate::prisma::PrismaClient;
+use crate::prisma::{scripts_folder, shell_script};
+use prisma_client_rust::QueryError;
+
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+pub const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+// A folder moved to the trash, with the subfolders and scripts that went along with it
+#[derive(Debug, Clone)]
+pub struct TrashedFolder {
+    pub folder: scripts_folder::Data,
+    // Where a restore puts it back, `None` for the top level
+    pub parent_name: Option<String>,
+    pub subfolder_count: usize,
+    pub script_count: usize,
+}
+
+// A script moved to the trash on its own, not with one of its folders
+#[derive(Debug, Clone)]
+pub struct TrashedScript {
+    pub script: shell_script::Data,
+    pub folder_names: Vec<String>,
+}
+
+// What the trash lists, latest first. Items trashed together with a listed folder are not
+// listed themselves, they are restored and deleted with it.
+#[derive(Debug, Clone, Default)]
+pub struct TrashContents {
+    pub folders: Vec<TrashedFolder>,
+    pub scripts: Vec<TrashedScript>,
+}
+
+impl TrashContents {
+    pub fn len(&self) -> usize {
+        self.folders.len() + self.scripts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+pub struct TrashRepository {
+    db: &'static PrismaClient,
+}
+
+impl TrashRepository {
+    pub fn new() -> Self {
+        let db = crate::db::get_db::get_db();
+        Self { db }
+    }
+
+    pub async fn get_trash(&self) -> prisma_client_rust::Result<TrashContents> {
+        trash_contents(self.db).await
+    }
+
+    // Brings the folder back with everything that was trashed together with it
+    pub async fn restore_folder(&self, folder_id: i32) -> Result<(), QueryError> {
+        self.db
+            ._transaction()
+            .run(|tx| async move { restore_folder_in(&tx, folder_id).await })
+            .await
+    }
+
+    // A script whose folders are all in the trash brings them back too, without the rest of their
+    // contents, so that it shows up where it was
+    pub async fn restore_script(&self, script_id: i32) -> Result<(), QueryError> {
+        self.db
+            ._transaction()
+            .run(|tx| async move { restore_script_in(&tx, script_id).await })
+            .await
+    }
+
+    // All or nothing, same as a single restore
+    pub async fn restore_scripts(&self, script_ids: &[i32]) -> Result<(), QueryError> {
+        let script_ids = script_ids.to_vec();
+        self.db
+            ._transaction()
+            .run(|tx| async move { restore_scripts_in(&tx, script_ids).await })
+            .await
+    }
+
+    // Deletes the listed items that have been in the trash for more than `days` for good, all of
+    // them for `None`. Returns how many of the listed items went. All or nothing.
+    pub async fn purge(&self, older_than_days: Option<u32>) -> Result<usize, QueryError> {
+        let cutoff = older_than_days
+            .map(|days| now_millis() - f64::from(days) * MILLIS_PER_DAY)
+            .unwrap_or(f64::INFINITY);
+        self.db
+            ._transaction()
+            .run(|tx| async move { purge_in(&tx, cutoff).await })
+            .await
+    }
+
+    pub async fn get_retention_days(&self) -> prisma_client_rust::Result<u32> {
+        let app_state = self
+            .db
+            .application_state()
+            .find_first(vec![])
+            .exec()
+            .await?;
+        Ok(app_state
+            .map(|app_state| app_state.trash_retention_days.max(1) as u32)
+            .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS))
+    }
+
+    pub async fn set_retention_days(&self, days: u32) -> prisma_client_rust::Result<()> {
+        let days = days.max(1) as i32;
+        self.db
+            .application_state()
+            .upsert(
+                crate::prisma::application_state::id::equals(1),
+                vec![crate::prisma::application_state::trash_retention_days::set(
+                    days,
+                )],
+                vec![crate::prisma::application_state::trash_retention_days::set(
+                    days,
+                )],
+            )
+            .exec()
+            .await?;
+        Ok(())
+    }
+}
+
+async fn trash_contents(db: &PrismaClient) -> Result<TrashContents, QueryError> {
+    let folders = db.scripts_folder().find_many(vec![]).exec().await?;
+    let scripts = db
+        .shell_script()
+        .find_many(vec![shell_script::deleted_at::not(None)])
+        .order_by(shell_script::deleted_at::order(
+            prisma_client_rust::Direction::Desc,
+        ))
+        .with(
+            shell_script::rel_scriptsfolder_shellscript::fetch(vec![])
+                .with(crate::prisma::rel_scriptsfolder_shellscript::scripts_folder::fetch()),
+        )
+        .exec()
+        .await?;
+    let folders_of = |script: &shell_script::Data| -> Vec<scripts_folder::Data> {
+        script
+            .rel_scriptsfolder_shellscript()
+            .map(|rels| {
+                rels.iter()
+                    .filter_map(|rel| rel.scripts_folder().ok())
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut trashed_folders: Vec<TrashedFolder> = folders
+        .iter()
+        .filter(|folder| folder.deleted_at.is_some())
+        .filter(|folder| {
+            let parent = folder
+                .parent_id
+                .and_then(|parent_id| folders.iter().find(|f| f.id == parent_id));
+            !parent.is_some_and(|parent| parent.deleted_at == folder.deleted_at)
+        })
+        .map(|folder| {
+            let group_ids = trashed_together(&folders, folder.id);
+            let script_count = scripts
+                .iter()
+                .filter(|script| {
+                    script.deleted_at == folder.deleted_at
+                        && folders_of(script).iter().any(|f| group_ids.contains(&f.id))
+                })
+                .count();
+            let parent_name = folder
+                .parent_id
+                .and_then(|parent_id| folders.iter().find(|f| f.id == parent_id))
+                .filter(|parent| parent.deleted_at.is_none())
+                .map(|parent| parent.name.clone());
+            TrashedFolder {
+                folder: folder.clone(),
+                parent_name,
+                subfolder_count: group_ids.len() - 1,
+                script_count,
+            }
+        })
+        .collect();
+    trashed_folders.sort_by(|a, b| {
+        let deleted_at = |trashed: &TrashedFolder| trashed.folder.deleted_at.unwrap_or_default();
+        deleted_at(b).total_cmp(&deleted_at(a))
+    });
+
+    let trashed_scripts = scripts
+        .iter()
+        .filter_map(|script| {
+            let script_folders = folders_of(script);
+            let trashed_with_folder = script_folders
+                .iter()
+                .any(|folder| folder.deleted_at == script.deleted_at);
+            (!trashed_with_folder).then(|| TrashedScript {
+                script: script.clone(),
+                folder_names: script_folders.into_iter().map(|f| f.name).collect(),
+            })
+        })
+        .collect();
+
+    Ok(TrashContents {
+        folders: trashed_folders,
+        scripts: trashed_scripts,
+    })
+}
+
+async fn purge_in(db: &PrismaClient, cutoff: f64) -> Result<usize, QueryError> {
+    let is_expired = |deleted_at: Option<f64>| deleted_at.is_some_and(|t| t < cutoff);
+    let trash = trash_contents(db).await?;
+    let mut purged = 0;
+    for trashed in trash.folders {
+        if is_expired(trashed.folder.deleted_at) {
+            delete_folder_permanently_in(db, trashed.folder.id).await?;
+            purged += 1;
+        }
+    }
+    for trashed in trash.scripts {
+        if is_expired(trashed.script.deleted_at) {
+            delete_script_in(db, trashed.script.id).await?;
+            purged += 1;
+        }
+    }
+    Ok(purged)
+}
+
+async fn restore_folder_in(db: &PrismaClient, folder_id: i32) -> Result<(), QueryError> {
+    let folders = db.scripts_folder().find_many(vec![]).exec().await?;
+    let Some(folder) = folders.iter().find(|f| f.id == folder_id) else {
+        return Ok(());
+    };
+    let Some(deleted_at) = folder.deleted_at else {
+        return Ok(());
+    };
+    let group_ids = trashed_together(&folders, folder_id);
+
+    untrash_folder(db, &folders, folder).await?;
+    db.scripts_folder()
+        .update_many(
+            vec![scripts_folder::id::in_vec(group_ids.clone())],
+            vec![scripts_folder::deleted_at::set(None)],
+        )
+        .exec()
+        .await?;
+    db.shell_script()
+        .update_many(
+            vec![
+                shell_script::deleted_at::equals(Some(deleted_at)),
+                shell_script::rel_scriptsfolder_shellscript::some(vec![
+                    crate::prisma::rel_scriptsfolder_shellscript::scripts_folder_id::in_vec(
+                        group_ids,
+                    ),
+                ]),
+            ],
+            vec![shell_script::deleted_at::set(None)],
+        )
+        .exec()
+        .await?;
+    Ok(())
+}
+
+async fn restore_script_in(db: &PrismaClient, script_id: i32) -> Result<(), QueryError> {
+    let folders = db.scripts_folder().find_many(vec![]).exec().await?;
+    let folder_ids: Vec<i32> = db
+        .rel_scriptsfolder_shellscript()
+        .find_many(vec![
+            crate::prisma::rel_scriptsfolder_shellscript::shell_script_id::equals(script_id),
+        ])
+        .exec()
+        .await?
+        .into_iter()
+        .map(|rel| rel.scripts_folder_id)
+        .collect();
+    let script_folders: Vec<&scripts_folder::Data> = folders
+        .iter()
+        .filter(|folder| folder_ids.contains(&folder.id))
+        .collect();
+    if script_folders
+        .iter()
+        .all(|folder| folder.deleted_at.is_some())
+    {
+        for folder in script_folders {
+            untrash_folder(db, &folders, folder).await?;
+        }
+    }
+    db.shell_script()
+        .update_many(
+            vec![shell_script::id::equals(script_id)],
+            vec![shell_script::deleted_at::set(None)],
+        )
+        .exec()
+        .await?;
+    Ok(())
+}
+
+async fn restore_scripts_in(db: &PrismaClient, script_ids: Vec<i32>) -> Result<(), QueryError> {
+    for script_id in script_ids {
+        restore_script_in(db, script_id).await?;
+    }
+    Ok(())
+}
+
+// Takes a single folder out of the trash, back at its old place among the children of its old
+// parent, or at the end of the top level when that parent is gone or still in the trash
+async fn untrash_folder(
+    db: &PrismaClient,
+    folders: &[scripts_folder::Data],
+    folder: &scripts_folder::Data,
+) -> Result<(), QueryError> {
+    let parent_id = folder.parent_id.filter(|parent_id| {
+        folders
+            .iter()
+            .any(|f| f.id == *parent_id && f.deleted_at.is_none())
+    });
+    let ordering = db
+        .scripts_folder()
+        .count(vec![
+            scripts_folder::parent_id::equals(parent_id),
+            scripts_folder::deleted_at::equals(None),
+        ])
+        .exec()
+        .await?;
+    db.scripts_folder()
+        .update_many(
+            vec![scripts_folder::id::equals(folder.id)],
+            vec![
+                scripts_folder::parent_id::set(parent_id),
+                scripts_folder::ordering::set(ordering as i32),
+                scripts_folder::deleted_at::set(None),
+            ],
+        )
+        .exec()
+        .await?;
+    if parent_id == folder.parent_id {
+        move_folder_in(db, folder.id, parent_id, folder.ordering.max(0) as usize).await?;
+    }
+    Ok(())
+}
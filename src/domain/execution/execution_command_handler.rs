@@ -0,0 +1,119 @@
+use crate::bus::Command;
+use crate::db::repository::script_repository::ScriptRepository;
+use crate::db::repository::script_run_repository::ScriptRunRepository;
+use crate::domain::execution::execution_event_handler::ExecutionEvent;
+use crate::domain::execution::placeholder::render_command;
+use crate::domain::execution::process_registry;
+use crate::domain::execution::script_execution::ScriptExecution;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy)]
+pub enum RunHistoryPrune {
+    OlderThanDays(u32),
+    KeepLatest(usize),
+}
+
+#[derive(Debug)]
+pub enum ExecutionCommand {
+    OpenRunHistory {
+        script_id: i32,
+    },
+    PruneRunHistory {
+        // None prunes the history of every script
+        script_id: Option<i32>,
+        prune: RunHistoryPrune,
+    },
+    StopRun {
+        run_id: u64,
+    },
+    PromptPlaceholders {
+        execution: ScriptExecution,
+    },
+    RunWithPlaceholderValues {
+        execution: ScriptExecution,
+        values: Vec<(String, String)>,
+    },
+}
+
+impl Command for ExecutionCommand {
+    type Output = ();
+}
+
+pub struct ExecutionCommandHandler {
+    script_repository: Arc<ScriptRepository>,
+    script_run_repository: Arc<ScriptRunRepository>,
+}
+
+impl ExecutionCommandHandler {
+    pub fn new() -> Self {
+        Self {
+            script_repository: Arc::new(ScriptRepository::new()),
+            script_run_repository: Arc::new(ScriptRunRepository::new()),
+        }
+    }
+
+    pub fn handle(&self, command: ExecutionCommand) {
+        match command {
+            ExecutionCommand::OpenRunHistory { script_id } => {
+                crate::dispatch_execution_event(ExecutionEvent::RunHistoryOpened { script_id });
+            }
+            ExecutionCommand::PruneRunHistory { script_id, prune } => {
+                let script_run_repository = self.script_run_repository.clone();
+                crate::spawn_task(async move {
+                    let result = match prune {
+                        RunHistoryPrune::OlderThanDays(days) => {
+                            script_run_repository
+                                .prune_runs_older_than_days(script_id, days)
+                                .await
+                        }
+                        RunHistoryPrune::KeepLatest(keep) => {
+                            script_run_repository
+                                .prune_runs_keeping_latest(script_id, keep)
+                                .await
+                        }
+                    };
+                    match result {
+                        Ok(deleted) => {
+                            log::debug!("Pruned {} script runs ({:?})", deleted, prune);
+                            crate::dispatch_execution_event(ExecutionEvent::RunHistoryPruned);
+                        }
+                        Err(e) => crate::report_error("Failed to prune run history", &e),
+                    }
+                });
+            }
+            ExecutionCommand::StopRun { run_id } => {
+                if process_registry::request_stop(run_id) {
+                    crate::dispatch_execution_event(ExecutionEvent::RunStopRequested { run_id });
+                } else {
+                    log::debug!("Run {} is not running or already stopping", run_id);
+                }
+            }
+            ExecutionCommand::PromptPlaceholders { execution } => {
+                crate::dispatch_execution_event(ExecutionEvent::PlaceholderPromptRequested {
+                    execution,
+                });
+            }
+            ExecutionCommand::RunWithPlaceholderValues { execution, values } => {
+                let script_repository = self.script_repository.clone();
+                let script_id = execution.script_id;
+                let values_to_remember = values.clone();
+                crate::spawn_task(async move {
+                    if let Err(e) = script_repository
+                        .save_placeholder_values(script_id, values_to_remember)
+                        .await
+                    {
+                        crate::report_error("Failed to remember placeholder values", &e);
+                    }
+                });
+
+                let values: HashMap<String, String> = values.into_iter().collect();
+                let command = render_command(&execution.command, &values);
+                crate::run_terminal_command(ScriptExecution {
+                    command,
+                    ..execution
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::bus::Event;
+use crate::db::repository::script_repository::ScriptRepository;
+use crate::db::repository::script_run_repository::ScriptRunRepository;
+use crate::domain::execution::placeholder::{parse_placeholders, prefill_values};
+use crate::domain::execution::script_execution::ScriptExecution;
+use crate::state::execution_state::{OutputStream, PlaceholderForm, RunStatus};
+use crate::{with_execution_state, with_execution_state_reducer};
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub enum ExecutionEvent {
+    RunStarted {
+        run_id: u64,
+        script_id: i32,
+        script_name: String,
+        command: String,
+    },
+    OutputReceived {
+        run_id: u64,
+        stream: OutputStream,
+        line: String,
+    },
+    RunStopRequested {
+        run_id: u64,
+    },
+    RunFinished {
+        run_id: u64,
+        script_id: i32,
+        exit_code: Option<i32>,
+        // Set instead of the exit code when a signal ended the script
+        signal: Option<i32>,
+    },
+    RunFailedToStart {
+        run_id: u64,
+        script_id: i32,
+        error: String,
+    },
+    RunHistoryOpened {
+        script_id: i32,
+    },
+    RunHistoryPruned,
+    PlaceholderPromptRequested {
+        execution: ScriptExecution,
+    },
+}
+
+impl Event for ExecutionEvent {}
+
+pub struct ExecutionEventHandler {
+    script_repository: Arc<ScriptRepository>,
+    script_run_repository: Arc<ScriptRunRepository>,
+}
+
+impl ExecutionEventHandler {
+    pub fn new() -> Self {
+        Self {
+            script_repository: Arc::new(ScriptRepository::new()),
+            script_run_repository: Arc::new(ScriptRunRepository::new()),
+        }
+    }
+
+    pub fn handle(&self, event: ExecutionEvent) {
+        match event {
+            ExecutionEvent::RunStarted {
+                run_id,
+                script_id,
+                script_name,
+                command,
+            } => {
+                with_execution_state_reducer(|r| {
+                    r.start_run(run_id, script_id, script_name, command)
+                });
+                self.reload_run_history_of(script_id);
+            }
+            ExecutionEvent::OutputReceived {
+                run_id,
+                stream,
+                line,
+            } => {
+                with_execution_state_reducer(|r| r.append_output(run_id, stream, line));
+            }
+            ExecutionEvent::RunStopRequested { run_id } => {
+                with_execution_state_reducer(|r| r.mark_stop_requested(run_id));
+            }
+            ExecutionEvent::RunFinished {
+                run_id,
+                script_id,
+                exit_code,
+                ..
+            } => {
+                with_execution_state_reducer(|r| {
+                    r.finish_run(run_id, RunStatus::Finished { exit_code })
+                });
+                self.reload_run_history_of(script_id);
+            }
+            ExecutionEvent::RunFailedToStart {
+                run_id,
+                script_id,
+                error,
+            } => {
+                with_execution_state_reducer(|r| {
+                    r.finish_run(run_id, RunStatus::FailedToStart { error })
+                });
+                self.reload_run_history_of(script_id);
+            }
+            ExecutionEvent::RunHistoryOpened { script_id } => {
+                with_execution_state_reducer(|r| r.open_run_history(script_id));
+                self.reload_run_history_of(script_id);
+            }
+            ExecutionEvent::RunHistoryPruned => {
+                let opened_script_id =
+                    with_execution_state(|state| *state.run_history_script_id.read().unwrap());
+                if let Some(script_id) = opened_script_id {
+                    self.reload_run_history_of(script_id);
+                }
+            }
+            ExecutionEvent::PlaceholderPromptRequested { execution } => {
+                let script_repository = self.script_repository.clone();
+                crate::spawn_task(async move {
+                    let last_values = script_repository
+                        .get_placeholder_values(execution.script_id)
+                        .await
+                        .unwrap_or_else(|e| {
+                            crate::report_error("Failed to load last placeholder values", &e);
+                            vec![]
+                        });
+
+                    let placeholders = parse_placeholders(&execution.command);
+                    let values = prefill_values(&placeholders, &last_values);
+
+                    with_execution_state_reducer(|r| {
+                        r.open_placeholder_form(PlaceholderForm {
+                            execution,
+                            placeholders,
+                            values,
+                        })
+                    });
+                });
+            }
+        }
+    }
+
+    // Only refetch when the history window is showing this script
+    fn reload_run_history_of(&self, script_id: i32) {
+        let opened_script_id =
+            with_execution_state(|state| *state.run_history_script_id.read().unwrap());
+        if opened_script_id != Some(script_id) {
+            return;
+        }
+        let script_run_repository = self.script_run_repository.clone();
+        crate::spawn_task(async move {
+            match script_run_repository.get_runs_by_script(script_id).await {
+                Ok(runs) => {
+                    with_execution_state_reducer(|r| r.set_run_history(script_id, runs));
+                }
+                Err(e) => crate::report_error("Failed to load run history", &e),
+            }
+        });
+    }
+}
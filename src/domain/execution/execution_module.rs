@@ -0,0 +1,20 @@
+use crate::bus::{Bus, DomainModule, Reply};
+use crate::domain::execution::execution_command_handler::{
+    ExecutionCommand, ExecutionCommandHandler,
+};
+use crate::domain::execution::execution_event_handler::{ExecutionEvent, ExecutionEventHandler};
+
+pub struct ExecutionModule;
+
+impl DomainModule for ExecutionModule {
+    fn register(self, bus: &mut Bus) {
+        let command_handler = ExecutionCommandHandler::new();
+        // Runs report back through events, there is nothing to reply
+        bus.handle_commands(move |command: ExecutionCommand, reply: Reply<()>| {
+            command_handler.handle(command);
+            reply.send(());
+        });
+        let event_handler = ExecutionEventHandler::new();
+        bus.subscribe(move |event: ExecutionEvent| event_handler.handle(event));
+    }
+}
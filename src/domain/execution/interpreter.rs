@@ -0,0 +1,279 @@
+use crate::prisma::shell_script::Data;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpreter {
+    // The shell detected from `$SHELL` / `/etc/passwd`, the behaviour before interpreters existed
+    #[default]
+    LoginShell,
+    Bash,
+    Zsh,
+    Sh,
+    Fish,
+    Python3,
+    Node,
+    // Runs the script through the interpreter of `shell_script.shebang`
+    Custom,
+}
+
+impl Interpreter {
+    pub const ALL: [Interpreter; 8] = [
+        Interpreter::LoginShell,
+        Interpreter::Bash,
+        Interpreter::Zsh,
+        Interpreter::Sh,
+        Interpreter::Fish,
+        Interpreter::Python3,
+        Interpreter::Node,
+        Interpreter::Custom,
+    ];
+
+    // Value stored in `shell_script.interpreter`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Interpreter::LoginShell => "login_shell",
+            Interpreter::Bash => "bash",
+            Interpreter::Zsh => "zsh",
+            Interpreter::Sh => "sh",
+            Interpreter::Fish => "fish",
+            Interpreter::Python3 => "python3",
+            Interpreter::Node => "node",
+            Interpreter::Custom => "custom",
+        }
+    }
+
+    // Unknown values fall back to the login shell so that old rows keep working
+    pub fn from_db_value(value: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|interpreter| interpreter.as_str() == value)
+            .unwrap_or_default()
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Interpreter::LoginShell => "Login shell",
+            Interpreter::Bash => "bash",
+            Interpreter::Zsh => "zsh",
+            Interpreter::Sh => "sh",
+            Interpreter::Fish => "fish",
+            Interpreter::Python3 => "python3",
+            Interpreter::Node => "node",
+            Interpreter::Custom => "Custom shebang",
+        }
+    }
+
+    pub fn is_shell(&self) -> bool {
+        matches!(
+            self,
+            Interpreter::LoginShell
+                | Interpreter::Bash
+                | Interpreter::Zsh
+                | Interpreter::Sh
+                | Interpreter::Fish
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterpreterSettings {
+    pub interpreter: Interpreter,
+    // Only used by `Interpreter::Custom`, e.g. `#!/usr/bin/env ruby -w`
+    pub shebang: Option<String>,
+    pub source_rc: bool,
+}
+
+impl Default for InterpreterSettings {
+    fn default() -> Self {
+        Self {
+            interpreter: Interpreter::LoginShell,
+            shebang: None,
+            source_rc: true,
+        }
+    }
+}
+
+impl From<&Data> for InterpreterSettings {
+    fn from(script: &Data) -> Self {
+        Self {
+            interpreter: Interpreter::from_db_value(&script.interpreter),
+            shebang: script.shebang.clone(),
+            source_rc: script.source_rc,
+        }
+    }
+}
+
+// How a script is actually spawned: `program args...`
+#[derive(Debug)]
+pub struct LaunchPlan {
+    pub program: String,
+    pub args: Vec<String>,
+    // Custom shebang scripts are written to a file that has to be removed after the run
+    pub script_file: Option<PathBuf>,
+}
+
+impl LaunchPlan {
+    pub fn cleanup(&self) {
+        if let Some(script_file) = &self.script_file {
+            let _ = std::fs::remove_file(script_file);
+        }
+    }
+}
+
+impl InterpreterSettings {
+    // Language name handed to the syntax highlighter of the script editors
+    pub fn code_lang(&self) -> String {
+        let program = match self.interpreter {
+            Interpreter::Custom => self
+                .shebang_parts()
+                .map(|(program, args)| {
+                    // `#!/usr/bin/env python3` names the interpreter in its first argument
+                    if program.ends_with("/env") {
+                        args.into_iter().next().unwrap_or(program)
+                    } else {
+                        program
+                    }
+                })
+                .unwrap_or_default(),
+            interpreter => interpreter.as_str().to_string(),
+        };
+        let program = program.rsplit('/').next().unwrap_or_default();
+        if program.starts_with("python") {
+            "py".into()
+        } else if program.starts_with("node") || program.starts_with("deno") {
+            "js".into()
+        } else if program.starts_with("ruby") {
+            "rb".into()
+        } else {
+            "sh".into()
+        }
+    }
+
+    fn shebang_parts(&self) -> Option<(String, Vec<String>)> {
+        let shebang = self.shebang.as_deref()?.trim();
+        let shebang = shebang.strip_prefix("#!").unwrap_or(shebang);
+        let mut parts = shebang.split_whitespace().map(|part| part.to_string());
+        let program = parts.next()?;
+        Some((program, parts.collect()))
+    }
+
+    pub fn launch_plan(
+        &self,
+        command: &str,
+        login_shell: &str,
+        run_id: u64,
+    ) -> std::io::Result<LaunchPlan> {
+        if self.interpreter.is_shell() {
+            let program = match self.interpreter {
+                Interpreter::LoginShell => login_shell.to_string(),
+                interpreter => interpreter.as_str().to_string(),
+            };
+            let args = shell_args(&program, self.source_rc, command);
+            return Ok(LaunchPlan {
+                program,
+                args,
+                script_file: None,
+            });
+        }
+
+        let (program, args, script_file) = match self.interpreter {
+            Interpreter::Python3 => (
+                "python3".to_string(),
+                vec!["-c".into(), command.into()],
+                None,
+            ),
+            Interpreter::Node => ("node".to_string(), vec!["-e".into(), command.into()], None),
+            _ => {
+                let Some((program, mut args)) = self.shebang_parts() else {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        "Custom interpreter selected but the shebang is empty",
+                    ));
+                };
+                // What the kernel does with a shebang: `interpreter [arg] <script file>`
+                let script_file = std::env::temp_dir().join(format!(
+                    "shell-script-manager-{}-{}",
+                    std::process::id(),
+                    run_id
+                ));
+                write_script_file(&script_file, command)?;
+                args.push(script_file.to_string_lossy().to_string());
+                (program, args, Some(script_file))
+            }
+        };
+
+        if !self.source_rc {
+            return Ok(LaunchPlan {
+                program,
+                args,
+                script_file,
+            });
+        }
+
+        // Let the login shell load the rc files (PATH, nvm, pyenv, ...) and then exec the interpreter.
+        // fish does not understand `"$@"`, so it hands over to sh instead.
+        let wrapper = if login_shell.contains("fish") {
+            "/bin/sh".to_string()
+        } else {
+            login_shell.to_string()
+        };
+        let mut wrapper_args = shell_args(&wrapper, true, "exec \"$0\" \"$@\"");
+        wrapper_args.push(program);
+        wrapper_args.extend(args);
+        Ok(LaunchPlan {
+            program: wrapper,
+            args: wrapper_args,
+            script_file,
+        })
+    }
+}
+
+// Only readable by the user, the command may hold secrets. Fails instead of writing through a
+// file or symlink that is already there under the predictable name.
+fn write_script_file(path: &std::path::Path, command: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(command.as_bytes())
+}
+
+fn shell_args(shell: &str, source_rc: bool, command: &str) -> Vec<String> {
+    let shell_name = shell.rsplit('/').next().unwrap_or(shell);
+    let mut args: Vec<String> = vec![];
+    if source_rc {
+        args.push("-l".into()); // Login shell
+        args.push("-c".into());
+        args.push(format!("{}{}", rc_prefix(shell_name), command));
+    } else {
+        if shell_name.contains("zsh") {
+            args.push("-f".into());
+        } else if shell_name.contains("bash") {
+            args.push("--noprofile".into());
+            args.push("--norc".into());
+        } else if shell_name.contains("fish") {
+            args.push("--no-config".into());
+        }
+        args.push("-c".into());
+        args.push(command.to_string());
+    }
+    args
+}
+
+// Sources the shell config files before running, fish loads its config by itself
+fn rc_prefix(shell_name: &str) -> &'static str {
+    if shell_name.contains("zsh") {
+        "source ~/.zshrc 2>/dev/null; source ~/.zprofile 2>/dev/null; "
+    } else if shell_name.contains("bash") {
+        "source ~/.bash_profile 2>/dev/null; source ~/.bashrc 2>/dev/null; "
+    } else if shell_name.contains("fish") {
+        ""
+    } else {
+        ". ~/.profile 2>/dev/null; "
+    }
+}
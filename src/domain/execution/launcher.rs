@@ -0,0 +1,117 @@
+use crate::domain::execution::interpreter::{Interpreter, InterpreterSettings, LaunchPlan};
+use crate::domain::execution::script_execution::ScriptExecution;
+use std::path::PathBuf;
+
+// Resolves everything a run needs from the environment (home, login shell, interpreter) and
+// spawns the process. Shared by the app and the `ssm` command line tool.
+pub struct ScriptLauncher {
+    home: String,
+    working_directory: Option<PathBuf>,
+    env_vars: Vec<(String, String)>,
+    interpreter_settings: InterpreterSettings,
+    launch_plan: std::io::Result<LaunchPlan>,
+}
+
+impl ScriptLauncher {
+    pub fn prepare(execution: &ScriptExecution, run_id: u64) -> Self {
+        let home = home_dir();
+        let shell = login_shell(&home);
+        let launch_plan =
+            execution
+                .interpreter_settings
+                .launch_plan(&execution.command, &shell, run_id);
+
+        log::debug!(
+            "Using launch plan: {:?} for command: {}",
+            launch_plan,
+            execution.command
+        );
+
+        Self {
+            working_directory: execution.resolved_working_directory(&home),
+            home,
+            env_vars: execution.env_vars.clone(),
+            interpreter_settings: execution.interpreter_settings.clone(),
+            launch_plan,
+        }
+    }
+
+    // Recorded in the run history, e.g. `/bin/zsh` or `python3`
+    pub fn interpreter_name(&self) -> String {
+        match &self.launch_plan {
+            Ok(launch_plan) if self.interpreter_settings.interpreter == Interpreter::LoginShell => {
+                launch_plan.program.clone()
+            }
+            _ => self.interpreter_settings.interpreter.as_str().to_string(),
+        }
+    }
+
+    // stdout and stderr are always piped so that callers can capture them
+    pub fn spawn(
+        &self,
+        own_process_group: bool,
+        stdin: std::process::Stdio,
+    ) -> std::io::Result<tokio::process::Child> {
+        let launch_plan = match &self.launch_plan {
+            Ok(launch_plan) => launch_plan,
+            Err(e) => return Err(std::io::Error::new(e.kind(), e.to_string())),
+        };
+        let mut process = tokio::process::Command::new(&launch_plan.program);
+        // Own process group so that stopping the script also stops everything it spawned
+        #[cfg(unix)]
+        if own_process_group {
+            process.process_group(0);
+        }
+        #[cfg(not(unix))]
+        let _ = own_process_group;
+        if let Some(working_directory) = &self.working_directory {
+            process.current_dir(working_directory);
+        }
+        process
+            .args(&launch_plan.args)
+            .env("HOME", &self.home) // Ensure HOME is set
+            .env(
+                "USER",
+                std::env::var("USER").unwrap_or_else(|_| whoami::username()),
+            )
+            .envs(self.env_vars.iter().cloned()) // Per-script overrides win over the inherited environment
+            .stdin(stdin)
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+    }
+
+    pub fn cleanup(&self) {
+        if let Ok(launch_plan) = &self.launch_plan {
+            launch_plan.cleanup();
+        }
+    }
+}
+
+// Get the user's home directory
+pub fn home_dir() -> String {
+    std::env::var("HOME").unwrap_or_else(|_| {
+        dirs::home_dir()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| "/Users".to_string())
+    })
+}
+
+// Detect the user's shell from /etc/passwd or use zsh as default
+pub fn login_shell(home: &str) -> String {
+    std::env::var("SHELL").unwrap_or_else(|_| {
+        // Try to read from /etc/passwd
+        std::fs::read_to_string("/etc/passwd")
+            .ok()
+            .and_then(|content| {
+                content.lines().find_map(|line| {
+                    if line.contains(home) {
+                        line.split(':').last().map(|s| s.to_string())
+                    } else {
+                        None
+                    }
+                })
+            })
+            .unwrap_or_else(|| "/bin/zsh".to_string())
+    })
+}
@@ -0,0 +1,8 @@
+pub mod execution_command_handler;
+pub mod execution_event_handler;
+pub mod execution_module;
+pub mod interpreter;
+pub mod launcher;
+pub mod placeholder;
+pub mod process_registry;
+pub mod script_execution;
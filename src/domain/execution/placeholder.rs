@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+
+// Placeholder syntax inside `shell_script.command`:
+//   {{branch}}                 plain value
+//   {{branch:main}}            value with a default
+//   {{env:staging|prod|dev}}   choice list, the first choice is the default
+//   {{!flags}}                 inserted as typed, any of the forms above can opt into this
+// Values are otherwise shell-quoted into a single word, so that `a b; rm -rf ~` stays an argument.
+// Anything that does not start with an identifier (e.g. docker's `{{.Names}}`) is left untouched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placeholder {
+    pub name: String,
+    pub default: Option<String>,
+    pub choices: Vec<String>,
+}
+
+struct PlaceholderMatch<'a> {
+    start: usize,
+    end: usize,
+    name: &'a str,
+    spec: Option<&'a str>,
+    raw: bool,
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn find_placeholders(command: &str) -> Vec<PlaceholderMatch<'_>> {
+    let mut matches = vec![];
+    let mut offset = 0;
+    while let Some(open) = command[offset..].find("{{") {
+        let start = offset + open;
+        let Some(close) = command[start + 2..].find("}}") else {
+            break;
+        };
+        let end = start + 2 + close + 2;
+        let inner = &command[start + 2..end - 2];
+        let (name, spec) = match inner.split_once(':') {
+            Some((name, spec)) => (name.trim(), Some(spec.trim())),
+            None => (inner.trim(), None),
+        };
+        let (name, raw) = match name.strip_prefix('!') {
+            Some(name) => (name.trim_start(), true),
+            None => (name, false),
+        };
+        if is_valid_name(name) {
+            matches.push(PlaceholderMatch {
+                start,
+                end,
+                name,
+                spec,
+                raw,
+            });
+            offset = end;
+        } else {
+            offset = start + 2;
+        }
+    }
+    matches
+}
+
+// Unique placeholders in order of first appearance
+pub fn parse_placeholders(command: &str) -> Vec<Placeholder> {
+    let mut placeholders: Vec<Placeholder> = vec![];
+    for found in find_placeholders(command) {
+        if placeholders.iter().any(|p| p.name == found.name) {
+            continue;
+        }
+        let choices: Vec<String> = match found.spec {
+            Some(spec) if spec.contains('|') => spec
+                .split('|')
+                .map(|choice| choice.trim().to_string())
+                .filter(|choice| !choice.is_empty())
+                .collect(),
+            _ => vec![],
+        };
+        let default = match found.spec {
+            Some(_) if !choices.is_empty() => choices.first().cloned(),
+            Some(spec) if !spec.is_empty() => Some(spec.to_string()),
+            _ => None,
+        };
+        placeholders.push(Placeholder {
+            name: found.name.to_string(),
+            default,
+            choices,
+        });
+    }
+    placeholders
+}
+
+// Leaves plain words as they are so that rendered commands stay readable
+fn shell_quote(value: &str) -> String {
+    let is_plain_word = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c));
+    if is_plain_word {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+// Substitutes every placeholder, falling back to its default when no value was given
+pub fn render_command(command: &str, values: &HashMap<String, String>) -> String {
+    let placeholders = parse_placeholders(command);
+    let mut rendered = String::with_capacity(command.len());
+    let mut last_end = 0;
+    for found in find_placeholders(command) {
+        rendered.push_str(&command[last_end..found.start]);
+        let value = values.get(found.name).cloned().or_else(|| {
+            placeholders
+                .iter()
+                .find(|p| p.name == found.name)
+                .and_then(|p| p.default.clone())
+        });
+        let value = value.unwrap_or_default();
+        if found.raw {
+            rendered.push_str(&value);
+        } else {
+            rendered.push_str(&shell_quote(&value));
+        }
+        last_end = found.end;
+    }
+    rendered.push_str(&command[last_end..]);
+    rendered
+}
+
+// Prefill with the last used value, then the declared default
+pub fn prefill_values(
+    placeholders: &[Placeholder],
+    last_values: &[crate::prisma::script_placeholder_value::Data],
+) -> Vec<String> {
+    placeholders
+        .iter()
+        .map(|placeholder| {
+            last_values
+                .iter()
+                .find(|v| v.name == placeholder.name)
+                .map(|v| v.value.clone())
+                .filter(|value| {
+                    placeholder.choices.is_empty() || placeholder.choices.contains(value)
+                })
+                .or_else(|| placeholder.default.clone())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholder(name: &str, default: Option<&str>, choices: &[&str]) -> Placeholder {
+        Placeholder {
+            name: name.to_string(),
+            default: default.map(str::to_string),
+            choices: choices.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn parses_plain_default_and_choice_placeholders() {
+        let placeholders = parse_placeholders(
+            "git checkout {{branch}} && deploy {{env:staging|prod|dev}} {{tag:latest}}",
+        );
+        assert_eq!(
+            placeholders,
+            vec![
+                placeholder("branch", None, &[]),
+                placeholder("env", Some("staging"), &["staging", "prod", "dev"]),
+                placeholder("tag", Some("latest"), &[]),
+            ]
+        );
+    }
+
+    #[test]
+    fn trims_names_and_choices_and_drops_empty_choices() {
+        let placeholders = parse_placeholders("{{ env : a | | b }}");
+        assert_eq!(
+            placeholders,
+            vec![placeholder("env", Some("a"), &["a", "b"])]
+        );
+    }
+
+    #[test]
+    fn empty_spec_has_no_default() {
+        assert_eq!(
+            parse_placeholders("{{name:}}"),
+            vec![placeholder("name", None, &[])]
+        );
+    }
+
+    #[test]
+    fn keeps_the_first_declaration_of_a_repeated_name() {
+        let placeholders = parse_placeholders("{{host:a}} {{host:b}} {{host}}");
+        assert_eq!(placeholders, vec![placeholder("host", Some("a"), &[])]);
+    }
+
+    #[test]
+    fn ignores_templates_that_are_not_placeholders() {
+        assert!(parse_placeholders("docker ps --format '{{.Names}} {{ .Status }}'").is_empty());
+        assert!(parse_placeholders("echo {{1st}}").is_empty());
+        assert!(parse_placeholders("echo {{unclosed").is_empty());
+    }
+
+    #[test]
+    fn finds_a_placeholder_after_a_skipped_template() {
+        let placeholders = parse_placeholders("{{.ID}} {{name}}");
+        assert_eq!(placeholders, vec![placeholder("name", None, &[])]);
+    }
+
+    #[test]
+    fn renders_given_values_then_defaults() {
+        let values = HashMap::from([("branch".to_string(), "feature".to_string())]);
+        let rendered = render_command(
+            "checkout {{branch:main}} to {{env:staging|prod}} as {{user}} '{{.Names}}'",
+            &values,
+        );
+        assert_eq!(rendered, "checkout feature to staging as '' '{{.Names}}'");
+    }
+
+    #[test]
+    fn renders_every_occurrence_of_a_repeated_name() {
+        let values = HashMap::from([("x".to_string(), "1".to_string())]);
+        assert_eq!(render_command("{{x}}-{{x:9}}-{{x}}", &values), "1-1-1");
+    }
+
+    #[test]
+    fn quotes_values_with_spaces_or_shell_metacharacters() {
+        let values = HashMap::from([
+            ("message".to_string(), "fix the build".to_string()),
+            ("path".to_string(), "a; rm -rf ~".to_string()),
+            ("name".to_string(), "$(whoami) `id` it's".to_string()),
+        ]);
+        assert_eq!(
+            render_command("git commit -m {{message}} && ls {{path}} {{name}}", &values),
+            r"git commit -m 'fix the build' && ls 'a; rm -rf ~' '$(whoami) `id` it'\''s'"
+        );
+    }
+
+    #[test]
+    fn leaves_plain_words_unquoted() {
+        let values = HashMap::from([("url".to_string(), "https://host:8080/a-b_c.d".to_string())]);
+        assert_eq!(
+            render_command("curl {{url}}", &values),
+            "curl https://host:8080/a-b_c.d"
+        );
+    }
+
+    #[test]
+    fn inserts_opted_in_values_as_typed() {
+        let values = HashMap::from([("flags".to_string(), "-v --depth 1".to_string())]);
+        assert_eq!(
+            render_command("git clone {{!flags}} {{ ! extra:--quiet }} repo", &values),
+            "git clone -v --depth 1 --quiet repo"
+        );
+        assert_eq!(
+            parse_placeholders("{{!flags}} {{flags}}"),
+            vec![placeholder("flags", None, &[])]
+        );
+    }
+
+    #[test]
+    fn renders_commands_without_placeholders_unchanged() {
+        let command = "echo '{{' && echo }}";
+        assert_eq!(render_command(command, &HashMap::new()), command);
+    }
+}
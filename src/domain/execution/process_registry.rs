@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+// How long a stopped script gets to clean up after SIGTERM before it is SIGKILLed
+pub const STOP_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+// How long the output of an exited script is still read, background processes it left behind can
+// keep the pipes open forever
+pub const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_millis(500);
+
+struct RunningProcess {
+    stop_sender: Option<oneshot::Sender<()>>,
+}
+
+// Processes spawned by `run_terminal_command`, keyed by the run id shown in the output console
+static RUNNING_PROCESSES: LazyLock<Mutex<HashMap<u64, RunningProcess>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+pub fn register(run_id: u64) -> oneshot::Receiver<()> {
+    let (stop_sender, stop_receiver) = oneshot::channel();
+    RUNNING_PROCESSES.lock().unwrap().insert(
+        run_id,
+        RunningProcess {
+            stop_sender: Some(stop_sender),
+        },
+    );
+    stop_receiver
+}
+
+pub fn unregister(run_id: u64) {
+    RUNNING_PROCESSES.lock().unwrap().remove(&run_id);
+}
+
+// Returns false when the run is unknown (already exited) or a stop was requested before
+pub fn request_stop(run_id: u64) -> bool {
+    let mut processes = RUNNING_PROCESSES.lock().unwrap();
+    let stop_sender = processes
+        .get_mut(&run_id)
+        .and_then(|process| process.stop_sender.take());
+    match stop_sender {
+        Some(sender) => sender.send(()).is_ok(),
+        None => false,
+    }
+}
+
+// Waits for the child to exit, or terminates its whole process group once a stop is requested
+pub async fn wait_or_stop(
+    child: &mut tokio::process::Child,
+    stop_receiver: oneshot::Receiver<()>,
+) -> std::io::Result<std::process::ExitStatus> {
+    tokio::select! {
+        status = child.wait() => return status,
+        Ok(()) = stop_receiver => {}
+    }
+    terminate(child).await
+}
+
+#[cfg(unix)]
+async fn terminate(child: &mut tokio::process::Child) -> std::io::Result<std::process::ExitStatus> {
+    use nix::sys::signal::{Signal, killpg};
+    use nix::unistd::Pid;
+
+    // The child was spawned as the leader of its own process group, so its pid is the group id
+    let Some(pgid) = child.id().map(|pid| Pid::from_raw(pid as i32)) else {
+        return child.wait().await;
+    };
+
+    if let Err(e) = killpg(pgid, Signal::SIGTERM) {
+        eprintln!("Failed to send SIGTERM to process group {}: {}", pgid, e);
+    }
+    // Once the leader is reaped its pgid may be reused by an unrelated group, so stragglers it
+    // left behind are only dealt with by the output drain timeout
+    if let Ok(status) = tokio::time::timeout(STOP_GRACE_PERIOD, child.wait()).await {
+        return status;
+    }
+    // Until the leader is reaped, even if it exits right after this check, the pgid stays ours
+    if child.try_wait()?.is_none() {
+        log::debug!(
+            "Process group {} still alive after grace period, sending SIGKILL",
+            pgid
+        );
+        // Also takes down stragglers in the group that ignored SIGTERM and keep our pipes open
+        match killpg(pgid, Signal::SIGKILL) {
+            Ok(_) | Err(nix::errno::Errno::ESRCH) => {}
+            Err(e) => eprintln!("Failed to send SIGKILL to process group {}: {}", pgid, e),
+        }
+    }
+    // Returns the cached status when `try_wait` already reaped the child
+    child.wait().await
+}
+
+#[cfg(not(unix))]
+async fn terminate(child: &mut tokio::process::Child) -> std::io::Result<std::process::ExitStatus> {
+    child.start_kill()?;
+    child.wait().await
+}
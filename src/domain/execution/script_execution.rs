@@ -0,0 +1,55 @@
+use crate::domain::execution::interpreter::InterpreterSettings;
+use crate::prisma::shell_script::Data;
+
+// Everything needed to launch a stored script, detached from the prisma row so that
+// callers can tweak the command (or run ad-hoc commands) before handing it over.
+#[derive(Debug, Clone)]
+pub struct ScriptExecution {
+    pub script_id: i32,
+    pub script_name: String,
+    pub command: String,
+    pub working_directory: Option<String>,
+    pub env_vars: Vec<(String, String)>,
+    pub interpreter_settings: InterpreterSettings,
+}
+
+impl From<&Data> for ScriptExecution {
+    fn from(script: &Data) -> Self {
+        let env_vars = script
+            .shell_script_env_var
+            .as_ref()
+            .map(|env_vars| {
+                env_vars
+                    .iter()
+                    .map(|env_var| (env_var.key.clone(), env_var.value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            script_id: script.id,
+            script_name: script.name.clone(),
+            command: script.command.clone(),
+            working_directory: script
+                .working_directory
+                .clone()
+                .filter(|dir| !dir.trim().is_empty()),
+            env_vars,
+            interpreter_settings: InterpreterSettings::from(script),
+        }
+    }
+}
+
+impl ScriptExecution {
+    // Supports `~` and `~/...` the way a shell `cd` would
+    pub fn resolved_working_directory(&self, home: &str) -> Option<std::path::PathBuf> {
+        let dir = self.working_directory.as_deref()?.trim();
+        let resolved = if dir == "~" {
+            std::path::PathBuf::from(home)
+        } else if let Some(rest) = dir.strip_prefix("~/") {
+            std::path::Path::new(home).join(rest)
+        } else {
+            std::path::PathBuf::from(dir)
+        };
+        Some(resolved)
+    }
+}
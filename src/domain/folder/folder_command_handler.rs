@@ -1,12 +1,24 @@
-use crate::db::repository::folder_repository::{FolderOrderUpdate, FolderRepository};
-use crate::db::repository::script_repository::ScriptRepository;
+use crate::bus::{Command, CommandError, Reply};
+use crate::db::repository::folder_repository::FolderRepository;
+use crate::db::repository::script_repository::{NewScript, ScriptDetails, ScriptRepository};
+use crate::db::repository::script_revision_repository::ScriptRevisionRepository;
+use crate::db::repository::tag_repository::TagRepository;
+use crate::db::repository::trash_repository::TrashRepository;
+use crate::domain::execution::interpreter::InterpreterSettings;
+use crate::domain::execution::script_execution::ScriptExecution;
 use crate::domain::folder::folder_event_handler::FolderEvent;
-use prisma_client_rust::bigdecimal::ToPrimitive;
+use crate::domain::folder::folder_history::{CommandOrigin, FolderHistory, HistoryEntry};
+use crate::domain::folder::folder_tree::child_folders;
+use crate::domain::folder::script_tags::{normalize_tag, tags_of_script};
 use std::sync::Arc;
 
-#[derive(Debug)]
+const MAX_SEARCH_HITS: usize = 100;
+
+#[derive(Debug, Clone)]
 pub enum FolderCommand {
-    CreateFolder {},
+    CreateFolder {
+        parent_id: Option<i32>,
+    },
     SelectFolder {
         folder_id: i32,
     },
@@ -17,6 +29,12 @@ pub enum FolderCommand {
         folder_id: i32,
         name: String,
         command: String,
+        interpreter_settings: InterpreterSettings,
+    },
+    // Several scripts at once, taken back by a single undo
+    AddScriptsToFolder {
+        folder_id: i32,
+        scripts: Vec<NewScript>,
     },
     UpdateScript {
         script_id: i32,
@@ -26,6 +44,11 @@ pub enum FolderCommand {
         script_id: i32,
         new_name: String,
     },
+    // Everything the edit window saves, taken back by a single undo
+    UpdateScriptDetails {
+        script_id: i32,
+        details: ScriptDetails,
+    },
     RenameFolder {
         folder_id: i32,
         new_name: String,
@@ -33,15 +56,108 @@ pub enum FolderCommand {
     DeleteScript {
         script_id: i32,
     },
-    ReorderFolders {
-        from_index: i32,
-        to_index: i32,
+    DeleteScripts {
+        script_ids: Vec<i32>,
+    },
+    // Shares an existing script with one more folder, nothing is copied
+    LinkScriptToFolder {
+        script_id: i32,
+        folder_id: i32,
+    },
+    RemoveScriptFromFolder {
+        script_id: i32,
+        folder_id: i32,
+    },
+    // Into the end of the target folder, or to `index` among its scripts when undone
+    MoveScriptToFolder {
+        script_id: i32,
+        from_folder_id: i32,
+        to_folder_id: i32,
+        index: Option<usize>,
+    },
+    ReorderScripts {
+        folder_id: i32,
+        from_index: usize,
+        to_index: usize,
+    },
+    MoveFolder {
+        folder_id: i32,
+        parent_id: Option<i32>,
+        index: usize,
+    },
+    SearchScripts {
+        query: String,
+    },
+    LoadTags,
+    // Scripts of all folders carrying the tag
+    FilterScriptsByTag {
+        tag: String,
     },
+    SetScriptPinned {
+        script_id: i32,
+        pinned: bool,
+    },
+    LoadPinnedScripts,
+    OpenRevisionHistory {
+        script_id: i32,
+    },
+    // Goes back to an earlier name and command, which are saved as a revision first
+    RestoreScriptRevision {
+        script_id: i32,
+        revision_id: i32,
+    },
+    ReorderPinnedScripts {
+        from_index: usize,
+        to_index: usize,
+    },
+    LoadTrash,
+    RestoreTrashedFolder {
+        folder_id: i32,
+    },
+    RestoreTrashedScript {
+        script_id: i32,
+    },
+    RestoreTrashedScripts {
+        script_ids: Vec<i32>,
+    },
+    DeleteFolderPermanently {
+        folder_id: i32,
+    },
+    DeleteScriptPermanently {
+        script_id: i32,
+    },
+    EmptyTrash,
+    // Deletes what has been in the trash for longer than the retention days, run at startup
+    PurgeExpiredTrash,
+    SetTrashRetentionDays {
+        days: u32,
+    },
+    // Take back the latest step, or do it again
+    Undo,
+    Redo,
+}
+
+impl Command for FolderCommand {
+    type Output = FolderCommandResult;
 }
 
+// What a folder command replies once it went through, see `dispatch_folder_command_with_reply`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FolderCommandOutput {
+    Done,
+    FolderCreated { folder_id: i32 },
+    ScriptCreated { script_id: i32 },
+}
+
+pub type FolderCommandResult = Result<FolderCommandOutput, CommandError>;
+
 pub struct FolderCommandHandler {
     folder_repository: Arc<FolderRepository>,
     script_repository: Arc<ScriptRepository>,
+    tag_repository: Arc<TagRepository>,
+    script_revision_repository: Arc<ScriptRevisionRepository>,
+    trash_repository: Arc<TrashRepository>,
+    history: Arc<FolderHistory>,
 }
 
 impl FolderCommandHandler {
@@ -49,46 +165,70 @@ impl FolderCommandHandler {
         Self {
             folder_repository: Arc::new(FolderRepository::new()),
             script_repository: Arc::new(ScriptRepository::new()),
+            tag_repository: Arc::new(TagRepository::new()),
+            script_revision_repository: Arc::new(ScriptRevisionRepository::new()),
+            trash_repository: Arc::new(TrashRepository::new()),
+            history: Arc::new(FolderHistory::new()),
         }
     }
 
-    pub fn handle(&self, wrapped: crate::WrappedFolderCommand) -> Result<(), ()> {
-        let command = wrapped.command;
-        let callback = wrapped.callback;
+    pub fn handle(&self, command: FolderCommand, reply: Reply<FolderCommandResult>) {
+        self.apply(command, reply, CommandOrigin::User);
+    }
 
+    // Commands that change folders or scripts record the command taking them back once they went
+    // through, the inverse of an undone step goes to the redo stack
+    fn apply(
+        &self,
+        command: FolderCommand,
+        reply: Reply<FolderCommandResult>,
+        origin: CommandOrigin,
+    ) {
         match command {
-            FolderCommand::CreateFolder {} => {
-                let db = Arc::new(crate::db::get_db::get_db()).clone();
+            FolderCommand::CreateFolder { parent_id } => {
                 let folder_repository = self.folder_repository.clone();
+                let history = self.history.clone();
                 crate::spawn_task(async move {
-                    let total_num_folders = db.scripts_folder().count(vec![]).exec().await.unwrap();
+                    let ordering = match folder_repository.get_child_folder_count(parent_id).await {
+                        Ok(count) => count as i32,
+                        Err(e) => {
+                            reply.send(Err(CommandError::report("Failed to add folder", &e)));
+                            return;
+                        }
+                    };
                     let folder_name = "New Collection".to_string();
 
-                    match folder_repository
-                        .create_script_folder(&folder_name, total_num_folders.to_i32().unwrap())
+                    let result = match folder_repository
+                        .create_script_folder(&folder_name, ordering, parent_id)
                         .await
                     {
-                        Ok(_) => {
+                        Ok(created_folder) => {
+                            history.record(
+                                &origin,
+                                "New folder",
+                                FolderCommand::DeleteFolder {
+                                    folder_id: created_folder.id,
+                                },
+                            );
                             crate::dispatch_folder_event(FolderEvent::FolderAdded {
                                 name: folder_name.clone(),
-                                ordering: total_num_folders.to_i32().unwrap(),
+                                ordering,
+                                parent_id,
                             });
+                            Ok(FolderCommandOutput::FolderCreated {
+                                folder_id: created_folder.id,
+                            })
                         }
-                        Err(e) => eprintln!("Failed to add folder: {:?}", e),
-                    }
+                        Err(e) => Err(CommandError::report("Failed to add folder", &e)),
+                    };
 
-                    if let Some(cb) = callback {
-                        let _ = crate::EVENT_SENDER
-                            .get()
-                            .unwrap()
-                            .send(crate::AppMessage::Callback(cb));
-                    }
+                    reply.send(result);
                 });
             }
             FolderCommand::SelectFolder { folder_id } => {
                 crate::spawn_task(async move {
                     let db = crate::db::get_db::get_db();
-                    match db
+                    let result = match db
                         .application_state()
                         .upsert(
                             crate::prisma::application_state::id::equals(1),
@@ -112,86 +252,55 @@ impl FolderCommandHandler {
                                 "Successfully updated last opened folder id to {}",
                                 folder_id
                             );
+                            Ok(FolderCommandOutput::Done)
                         }
-                        Err(e) => eprintln!("Failed to update last opened folder id: {:?}", e),
-                    }
+                        Err(e) => Err(CommandError::report(
+                            "Failed to update last opened folder id",
+                            &e,
+                        )),
+                    };
 
-                    if let Some(cb) = callback {
-                        let _ = crate::EVENT_SENDER
-                            .get()
-                            .unwrap()
-                            .send(crate::AppMessage::Callback(cb));
-                    }
+                    reply.send(result);
                 });
             }
             FolderCommand::DeleteFolder { folder_id } => {
                 let folder_repository = self.folder_repository.clone();
+                let history = self.history.clone();
                 crate::spawn_task(async move {
-                    match folder_repository.delete_script_folder(folder_id).await {
+                    // Also trashes the subfolders and closes the gap among the siblings
+                    let result = match folder_repository.trash_folder(folder_id).await {
                         Ok(_) => {
-                            println!(
-                                "Folder with id {} and related data deleted successfully",
-                                folder_id
+                            history.record(
+                                &origin,
+                                "Delete folder",
+                                FolderCommand::RestoreTrashedFolder { folder_id },
                             );
+                            crate::dispatch_folder_event(FolderEvent::FolderDeleted { folder_id });
+                            log::debug!("Folder with id {} moved to the trash", folder_id);
+                            Ok(FolderCommandOutput::Done)
                         }
-                        Err(e) => eprintln!("Failed to delete folder: {:?}", e),
-                    }
-
-                    let all_folders = folder_repository.get_all_folders().await;
-                    match all_folders {
-                        Ok(folders) => {
-                            let mut sorted_folders = folders;
-                            sorted_folders.sort_by_key(|f| f.ordering);
-                            for (index, folder) in sorted_folders.iter_mut().enumerate() {
-                                folder.ordering = index as i32;
-                            }
-                            let order_update_param = sorted_folders
-                                .iter()
-                                .map(|f| FolderOrderUpdate {
-                                    folder_id: f.id.to_i32().unwrap(),
-                                    new_ordering: f.ordering,
-                                })
-                                .collect::<Vec<FolderOrderUpdate>>();
-
-                            match folder_repository
-                                .batch_order_update(order_update_param)
-                                .await
-                            {
-                                Ok(_) => {
-                                    crate::dispatch_folder_event(FolderEvent::FolderDeleted {
-                                        folder_id,
-                                    });
-                                    println!(
-                                        "Folder orderings updated successfully after deletion"
-                                    );
-                                }
-                                Err(e) => eprintln!("Failed to update folder orderings: {:?}", e),
-                            }
-                        }
-                        Err(e) => eprintln!("Failed to retrieve all folders: {:?}", e),
-                    }
+                        Err(e) => Err(CommandError::report("Failed to delete folder", &e)),
+                    };
 
-                    if let Some(cb) = callback {
-                        let _ = crate::EVENT_SENDER
-                            .get()
-                            .unwrap()
-                            .send(crate::AppMessage::Callback(cb));
-                    }
+                    reply.send(result);
                 });
             }
             FolderCommand::AddScriptToFolder {
                 folder_id,
                 name,
                 command,
+                interpreter_settings,
             } => {
                 let script_repository = self.script_repository.clone();
+                let history = self.history.clone();
                 crate::spawn_task(async move {
-                    match script_repository
-                        .create_script(name.clone(), command.clone())
+                    let result = match script_repository
+                        .create_script(name.clone(), command.clone(), interpreter_settings)
                         .await
                     {
                         Ok(created_script) => {
                             println!("created script: {:?}", created_script);
+                            // New scripts go to the end of the folder
                             match script_repository
                                 .create_script_relationship(folder_id, created_script.id)
                                 .await
@@ -201,31 +310,73 @@ impl FolderCommandHandler {
                                         "Script '{}' added to folder id {} successfully",
                                         name, folder_id
                                     );
+                                    history.record(
+                                        &origin,
+                                        "New script",
+                                        FolderCommand::DeleteScript {
+                                            script_id: created_script.id,
+                                        },
+                                    );
                                     crate::dispatch_folder_event(FolderEvent::ScriptAdded {
                                         folder_id,
                                     });
+                                    Ok(FolderCommandOutput::ScriptCreated {
+                                        script_id: created_script.id,
+                                    })
+                                }
+                                Err(e) => {
+                                    Err(CommandError::report("Failed to create relationship", &e))
                                 }
-                                Err(e) => eprintln!("Failed to create relationship: {:?}", e),
                             }
                         }
-                        Err(e) => eprintln!("Failed to add script: {:?}", e),
-                    }
+                        Err(e) => Err(CommandError::report("Failed to add script", &e)),
+                    };
 
-                    if let Some(cb) = callback {
-                        let _ = crate::EVENT_SENDER
-                            .get()
-                            .unwrap()
-                            .send(crate::AppMessage::Callback(cb));
-                    }
+                    reply.send(result);
+                });
+            }
+            FolderCommand::AddScriptsToFolder { folder_id, scripts } => {
+                let script_repository = self.script_repository.clone();
+                let history = self.history.clone();
+                crate::spawn_task(async move {
+                    let result = match script_repository
+                        .add_scripts_to_folder(folder_id, scripts)
+                        .await
+                    {
+                        Ok(script_ids) => {
+                            let label = match script_ids.len() {
+                                1 => "New script".to_string(),
+                                count => format!("Add {} scripts", count),
+                            };
+                            history.record(
+                                &origin,
+                                &label,
+                                FolderCommand::DeleteScripts { script_ids },
+                            );
+                            crate::dispatch_folder_event(FolderEvent::ScriptAdded { folder_id });
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to add scripts", &e)),
+                    };
+
+                    reply.send(result);
                 });
             }
             FolderCommand::RenameFolder {
                 folder_id,
                 new_name,
             } => {
+                let folder_repository = self.folder_repository.clone();
+                let history = self.history.clone();
                 crate::spawn_task(async move {
+                    let previous_name = folder_repository
+                        .get_all_folders()
+                        .await
+                        .ok()
+                        .and_then(|folders| folders.into_iter().find(|f| f.id == folder_id))
+                        .map(|folder| folder.name);
                     let db = crate::db::get_db::get_db();
-                    match db
+                    let result = match db
                         .scripts_folder()
                         .update_many(
                             vec![crate::prisma::scripts_folder::id::equals(folder_id)],
@@ -239,67 +390,75 @@ impl FolderCommandHandler {
                                 "Folder id {} renamed to '{}' successfully",
                                 folder_id, new_name
                             );
+                            if let Some(previous_name) = previous_name
+                                && previous_name != new_name
+                            {
+                                history.record(
+                                    &origin,
+                                    "Rename folder",
+                                    FolderCommand::RenameFolder {
+                                        folder_id,
+                                        new_name: previous_name,
+                                    },
+                                );
+                            }
                             crate::dispatch_folder_event(FolderEvent::FolderRenamed {
                                 folder_id,
                                 new_name,
                             });
+                            Ok(FolderCommandOutput::Done)
                         }
-                        Err(e) => eprintln!("Failed to rename folder: {:?}", e),
-                    }
+                        Err(e) => Err(CommandError::report("Failed to rename folder", &e)),
+                    };
 
-                    if let Some(cb) = callback {
-                        let _ = crate::EVENT_SENDER
-                            .get()
-                            .unwrap()
-                            .send(crate::AppMessage::Callback(cb));
-                    }
+                    reply.send(result);
                 });
             }
             FolderCommand::UpdateScript {
                 script_id,
                 new_command,
             } => {
+                let script_repository = self.script_repository.clone();
+                let history = self.history.clone();
                 crate::spawn_task(async move {
-                    let db = crate::db::get_db::get_db();
-                    match db
-                        .shell_script()
-                        .update_many(
-                            vec![crate::prisma::shell_script::id::equals(script_id)],
-                            vec![crate::prisma::shell_script::command::set(
-                                new_command.clone(),
-                            )],
-                        )
-                        .exec()
+                    let previous = script_repository.get_script_by_id(script_id).await;
+                    let result = match script_repository
+                        .update_script_command(script_id, new_command.clone())
                         .await
                     {
                         Ok(_) => {
                             println!("Script id {} updated successfully", script_id);
+                            if let Ok(Some(previous)) = previous
+                                && previous.command != new_command
+                            {
+                                history.record(
+                                    &origin,
+                                    "Edit command",
+                                    FolderCommand::UpdateScript {
+                                        script_id,
+                                        new_command: previous.command,
+                                    },
+                                );
+                            }
                             crate::dispatch_folder_event(FolderEvent::ScriptUpdated { script_id });
+                            Ok(FolderCommandOutput::Done)
                         }
-                        Err(e) => eprintln!("Failed to update script: {:?}", e),
-                    }
+                        Err(e) => Err(CommandError::report("Failed to update script", &e)),
+                    };
 
-                    if let Some(cb) = callback {
-                        let _ = crate::EVENT_SENDER
-                            .get()
-                            .unwrap()
-                            .send(crate::AppMessage::Callback(cb));
-                    }
+                    reply.send(result);
                 });
             }
             FolderCommand::UpdateScriptName {
                 script_id,
                 new_name,
             } => {
+                let script_repository = self.script_repository.clone();
+                let history = self.history.clone();
                 crate::spawn_task(async move {
-                    let db = crate::db::get_db::get_db();
-                    match db
-                        .shell_script()
-                        .update_many(
-                            vec![crate::prisma::shell_script::id::equals(script_id)],
-                            vec![crate::prisma::shell_script::name::set(new_name.clone())],
-                        )
-                        .exec()
+                    let previous = script_repository.get_script_by_id(script_id).await;
+                    let result = match script_repository
+                        .update_script_name(script_id, new_name.clone())
                         .await
                     {
                         Ok(_) => {
@@ -307,72 +466,711 @@ impl FolderCommandHandler {
                                 "Script id {} renamed to '{}' successfully",
                                 script_id, new_name
                             );
+                            if let Ok(Some(previous)) = previous
+                                && previous.name != new_name
+                            {
+                                history.record(
+                                    &origin,
+                                    "Rename script",
+                                    FolderCommand::UpdateScriptName {
+                                        script_id,
+                                        new_name: previous.name,
+                                    },
+                                );
+                            }
+                            crate::dispatch_folder_event(FolderEvent::ScriptUpdated { script_id });
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to rename script", &e)),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::UpdateScriptDetails { script_id, details } => {
+                let script_repository = self.script_repository.clone();
+                let history = self.history.clone();
+                crate::spawn_task(async move {
+                    let previous = script_repository.get_script_by_id(script_id).await;
+                    // Sorted like `tags_of_script`, so that unchanged tags compare equal
+                    let mut tags: Vec<String> = details
+                        .tags
+                        .iter()
+                        .filter_map(|t| normalize_tag(t))
+                        .collect();
+                    tags.sort_unstable();
+                    tags.dedup();
+                    let details = ScriptDetails { tags, ..details };
+                    let result = match script_repository
+                        .update_script_details(script_id, details.clone())
+                        .await
+                    {
+                        Ok(_) => {
+                            if let Ok(Some(previous)) = previous {
+                                let previous = script_details(&previous);
+                                if previous != details {
+                                    history.record(
+                                        &origin,
+                                        "Edit script",
+                                        FolderCommand::UpdateScriptDetails {
+                                            script_id,
+                                            details: previous,
+                                        },
+                                    );
+                                }
+                            }
                             crate::dispatch_folder_event(FolderEvent::ScriptUpdated { script_id });
+                            crate::dispatch_folder_event(FolderEvent::TagsChanged);
+                            Ok(FolderCommandOutput::Done)
                         }
-                        Err(e) => eprintln!("Failed to rename script: {:?}", e),
-                    }
+                        Err(e) => Err(CommandError::report("Failed to save script", &e)),
+                    };
 
-                    if let Some(cb) = callback {
-                        let _ = crate::EVENT_SENDER
-                            .get()
-                            .unwrap()
-                            .send(crate::AppMessage::Callback(cb));
-                    }
+                    reply.send(result);
+                });
+            }
+            FolderCommand::DeleteScripts { script_ids } => {
+                let script_repository = self.script_repository.clone();
+                let history = self.history.clone();
+                crate::spawn_task(async move {
+                    let result = match script_repository.trash_scripts(script_ids.clone()).await {
+                        Ok(_) => {
+                            for script_id in script_ids.iter() {
+                                crate::dispatch_folder_event(FolderEvent::ScriptDeleted {
+                                    script_id: *script_id,
+                                });
+                            }
+                            history.record(
+                                &origin,
+                                "Delete scripts",
+                                FolderCommand::RestoreTrashedScripts { script_ids },
+                            );
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to delete scripts", &e)),
+                    };
+
+                    reply.send(result);
                 });
             }
             FolderCommand::DeleteScript { script_id } => {
                 let script_repository = self.script_repository.clone();
+                let history = self.history.clone();
                 crate::spawn_task(async move {
-                    match script_repository.delete_script(script_id).await {
+                    let result = match script_repository.trash_script(script_id).await {
                         Ok(_) => {
-                            println!("Script id {} deleted successfully", script_id);
+                            log::debug!("Script id {} moved to the trash", script_id);
+                            history.record(
+                                &origin,
+                                "Delete script",
+                                FolderCommand::RestoreTrashedScript { script_id },
+                            );
                             crate::dispatch_folder_event(FolderEvent::ScriptDeleted { script_id });
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to delete script", &e)),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::LinkScriptToFolder {
+                script_id,
+                folder_id,
+            } => {
+                let script_repository = self.script_repository.clone();
+                let history = self.history.clone();
+                crate::spawn_task(async move {
+                    // Linking into a folder the script is already in changes nothing
+                    let was_linked = script_repository
+                        .get_script_by_id(script_id)
+                        .await
+                        .ok()
+                        .flatten()
+                        .and_then(|script| {
+                            script.rel_scriptsfolder_shellscript().ok().map(|rels| {
+                                rels.iter().any(|rel| rel.scripts_folder_id == folder_id)
+                            })
+                        })
+                        .unwrap_or(true);
+                    let result = match script_repository
+                        .add_script_to_folder(folder_id, script_id)
+                        .await
+                    {
+                        Ok(_) => {
+                            if !was_linked {
+                                history.record(
+                                    &origin,
+                                    "Add script to folder",
+                                    FolderCommand::RemoveScriptFromFolder {
+                                        script_id,
+                                        folder_id,
+                                    },
+                                );
+                            }
+                            crate::dispatch_folder_event(FolderEvent::ScriptLinkedToFolder {
+                                script_id,
+                                folder_id,
+                            });
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to add script to folder", &e)),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::RemoveScriptFromFolder {
+                script_id,
+                folder_id,
+            } => {
+                let script_repository = self.script_repository.clone();
+                let history = self.history.clone();
+                crate::spawn_task(async move {
+                    let result = match script_repository
+                        .remove_script_from_folder(folder_id, script_id)
+                        .await
+                    {
+                        Ok(_) => {
+                            // The script's last folder sends it to the trash instead
+                            let inverse = match script_repository.get_script_by_id(script_id).await
+                            {
+                                Ok(Some(_)) => FolderCommand::LinkScriptToFolder {
+                                    script_id,
+                                    folder_id,
+                                },
+                                _ => FolderCommand::RestoreTrashedScript { script_id },
+                            };
+                            history.record(&origin, "Remove script from folder", inverse);
+                            crate::dispatch_folder_event(FolderEvent::ScriptRemovedFromFolder {
+                                script_id,
+                                folder_id,
+                            });
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report(
+                            "Failed to remove script from folder",
+                            &e,
+                        )),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::MoveScriptToFolder {
+                script_id,
+                from_folder_id,
+                to_folder_id,
+                index,
+            } => {
+                let script_repository = self.script_repository.clone();
+                let history = self.history.clone();
+                crate::spawn_task(async move {
+                    let result = match script_repository
+                        .move_script_to_folder(script_id, from_folder_id, to_folder_id, index)
+                        .await
+                    {
+                        // Already in the target folder, nothing was moved
+                        Ok(None) => Ok(FolderCommandOutput::Done),
+                        Ok(Some(source_index)) => {
+                            history.record(
+                                &origin,
+                                "Move script",
+                                FolderCommand::MoveScriptToFolder {
+                                    script_id,
+                                    from_folder_id: to_folder_id,
+                                    to_folder_id: from_folder_id,
+                                    index: Some(source_index),
+                                },
+                            );
+                            crate::dispatch_folder_event(FolderEvent::ScriptMovedToFolder {
+                                script_id,
+                                from_folder_id,
+                                to_folder_id,
+                            });
+                            Ok(FolderCommandOutput::Done)
                         }
-                        Err(e) => eprintln!("Failed to delete script: {:?}", e),
-                    }
+                        Err(e) => Err(CommandError::report("Failed to move script to folder", &e)),
+                    };
 
-                    if let Some(cb) = callback {
-                        let _ = crate::EVENT_SENDER
-                            .get()
-                            .unwrap()
-                            .send(crate::AppMessage::Callback(cb));
-                    }
+                    reply.send(result);
                 });
             }
-            FolderCommand::ReorderFolders {
-                from_index: from_index,
-                to_index: to_index,
+            FolderCommand::ReorderScripts {
+                folder_id,
+                from_index,
+                to_index,
+            } => {
+                let script_repository = self.script_repository.clone();
+                let history = self.history.clone();
+                crate::spawn_task(async move {
+                    let result = match script_repository
+                        .reorder_scripts(folder_id, from_index, to_index)
+                        .await
+                    {
+                        Ok(_) => {
+                            if from_index != to_index {
+                                history.record(
+                                    &origin,
+                                    "Reorder scripts",
+                                    FolderCommand::ReorderScripts {
+                                        folder_id,
+                                        from_index: to_index,
+                                        to_index: from_index,
+                                    },
+                                );
+                            }
+                            crate::dispatch_folder_event(FolderEvent::ScriptsReordered {
+                                folder_id,
+                            });
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to reorder scripts", &e)),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::MoveFolder {
+                folder_id,
+                parent_id,
+                index,
             } => {
                 let folder_repository = self.folder_repository.clone();
+                let history = self.history.clone();
                 crate::spawn_task(async move {
-                    let moving_downwards = from_index < to_index;
-                    let index_bound =
-                        (folder_repository.get_all_folders().await.unwrap().len() - 1).max(0);
+                    // Where the folder is now, as the arguments of the move back
+                    let previous_place =
+                        folder_repository
+                            .get_all_folders()
+                            .await
+                            .ok()
+                            .and_then(|folders| {
+                                let folder = folders.iter().find(|f| f.id == folder_id)?;
+                                let index = child_folders(&folders, folder.parent_id)
+                                    .iter()
+                                    .position(|f| f.id == folder_id)?;
+                                Some((folder.parent_id, index))
+                            });
+                    let result = match folder_repository
+                        .move_folder(folder_id, parent_id, index)
+                        .await
+                    {
+                        Ok(_) => {
+                            if let Some((previous_parent_id, previous_index)) = previous_place
+                                && (previous_parent_id, previous_index) != (parent_id, index)
+                            {
+                                history.record(
+                                    &origin,
+                                    "Move folder",
+                                    FolderCommand::MoveFolder {
+                                        folder_id,
+                                        parent_id: previous_parent_id,
+                                        index: previous_index,
+                                    },
+                                );
+                            }
+                            crate::dispatch_folder_event(FolderEvent::FolderMoved {
+                                folder_id,
+                                parent_id,
+                            });
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to move folder", &e)),
+                    };
+                    reply.send(result);
+                });
+            }
+            FolderCommand::SearchScripts { query } => {
+                let script_repository = self.script_repository.clone();
+                crate::spawn_task(async move {
+                    let result = match script_repository
+                        .search_scripts(&query, MAX_SEARCH_HITS)
+                        .await
+                    {
+                        Ok(hits) => {
+                            crate::dispatch_folder_event(FolderEvent::ScriptsSearched {
+                                query,
+                                hits,
+                            });
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to search scripts", &e)),
+                    };
 
-                    match folder_repository
-                        .reorder_folders(
-                            from_index.to_usize().unwrap(),
-                            to_index.to_usize().unwrap(),
-                        )
+                    reply.send(result);
+                });
+            }
+            FolderCommand::LoadTags => {
+                let tag_repository = self.tag_repository.clone();
+                crate::spawn_task(async move {
+                    let result = match tag_repository.get_all_tag_names().await {
+                        Ok(tags) => {
+                            crate::dispatch_folder_event(FolderEvent::TagsLoaded { tags });
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to load tags", &e)),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::FilterScriptsByTag { tag } => {
+                let tag_repository = self.tag_repository.clone();
+                crate::spawn_task(async move {
+                    let result = match tag_repository.get_scripts_with_tag(&tag).await {
+                        Ok(scripts) => {
+                            crate::dispatch_folder_event(FolderEvent::ScriptsOfTagLoaded {
+                                tag,
+                                scripts,
+                            });
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report(
+                            &format!("Failed to load scripts tagged {}", tag),
+                            &e,
+                        )),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::SetScriptPinned { script_id, pinned } => {
+                let script_repository = self.script_repository.clone();
+                let history = self.history.clone();
+                crate::spawn_task(async move {
+                    let result = match script_repository.set_script_pinned(script_id, pinned).await
+                    {
+                        Ok(_) => {
+                            history.record(
+                                &origin,
+                                if pinned { "Pin script" } else { "Unpin script" },
+                                FolderCommand::SetScriptPinned {
+                                    script_id,
+                                    pinned: !pinned,
+                                },
+                            );
+                            crate::dispatch_folder_event(FolderEvent::ScriptPinned {
+                                script_id,
+                                pinned,
+                            });
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to pin script", &e)),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::LoadPinnedScripts => {
+                let script_repository = self.script_repository.clone();
+                crate::spawn_task(async move {
+                    let result = match script_repository.get_pinned_scripts().await {
+                        Ok(scripts) => {
+                            crate::dispatch_folder_event(FolderEvent::PinnedScriptsLoaded {
+                                scripts,
+                            });
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to load pinned scripts", &e)),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::ReorderPinnedScripts {
+                from_index,
+                to_index,
+            } => {
+                let script_repository = self.script_repository.clone();
+                let history = self.history.clone();
+                crate::spawn_task(async move {
+                    let result = match script_repository
+                        .reorder_pinned_scripts(from_index, to_index)
                         .await
                     {
                         Ok(_) => {
-                            crate::dispatch_folder_event(FolderEvent::FoldersReordered {
-                                from_index,
-                                to_index: to_index.to_i32().unwrap(),
+                            if from_index != to_index {
+                                history.record(
+                                    &origin,
+                                    "Reorder pinned scripts",
+                                    FolderCommand::ReorderPinnedScripts {
+                                        from_index: to_index,
+                                        to_index: from_index,
+                                    },
+                                );
+                            }
+                            crate::dispatch_folder_event(FolderEvent::PinnedScriptsReordered);
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to reorder pinned scripts", &e)),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::OpenRevisionHistory { script_id } => {
+                crate::dispatch_folder_event(FolderEvent::RevisionHistoryOpened { script_id });
+                reply.send(Ok(FolderCommandOutput::Done));
+            }
+            FolderCommand::RestoreScriptRevision {
+                script_id,
+                revision_id,
+            } => {
+                let script_revision_repository = self.script_revision_repository.clone();
+                let history = self.history.clone();
+                crate::spawn_task(async move {
+                    let result = match script_revision_repository
+                        .restore_revision(script_id, revision_id)
+                        .await
+                    {
+                        Ok(replaced_revision_id) => {
+                            log::debug!(
+                                "Script id {} restored to revision id {}",
+                                script_id,
+                                revision_id
+                            );
+                            // The replaced name and command were kept as a revision of their own
+                            if let Some(replaced_revision_id) = replaced_revision_id {
+                                history.record(
+                                    &origin,
+                                    "Restore version",
+                                    FolderCommand::RestoreScriptRevision {
+                                        script_id,
+                                        revision_id: replaced_revision_id,
+                                    },
+                                );
+                            }
+                            crate::dispatch_folder_event(FolderEvent::ScriptUpdated { script_id });
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report(
+                            "Failed to restore script revision",
+                            &e,
+                        )),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::LoadTrash => {
+                let trash_repository = self.trash_repository.clone();
+                crate::spawn_task(async move {
+                    let result = match trash_repository.get_trash().await {
+                        Ok(trash) => {
+                            crate::dispatch_folder_event(FolderEvent::TrashLoaded { trash });
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to load trash", &e)),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::RestoreTrashedFolder { folder_id } => {
+                let trash_repository = self.trash_repository.clone();
+                let history = self.history.clone();
+                crate::spawn_task(async move {
+                    let result = match trash_repository.restore_folder(folder_id).await {
+                        Ok(_) => {
+                            log::debug!("Folder id {} restored from the trash", folder_id);
+                            history.record(
+                                &origin,
+                                "Restore folder",
+                                FolderCommand::DeleteFolder { folder_id },
+                            );
+                            crate::dispatch_folder_event(FolderEvent::RestoredFromTrash);
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to restore folder", &e)),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::RestoreTrashedScript { script_id } => {
+                let trash_repository = self.trash_repository.clone();
+                let history = self.history.clone();
+                crate::spawn_task(async move {
+                    let result = match trash_repository.restore_script(script_id).await {
+                        Ok(_) => {
+                            log::debug!("Script id {} restored from the trash", script_id);
+                            history.record(
+                                &origin,
+                                "Restore script",
+                                FolderCommand::DeleteScript { script_id },
+                            );
+                            crate::dispatch_folder_event(FolderEvent::RestoredFromTrash);
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to restore script", &e)),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::RestoreTrashedScripts { script_ids } => {
+                let trash_repository = self.trash_repository.clone();
+                let history = self.history.clone();
+                crate::spawn_task(async move {
+                    let result = match trash_repository.restore_scripts(&script_ids).await {
+                        Ok(_) => {
+                            history.record(
+                                &origin,
+                                "Restore scripts",
+                                FolderCommand::DeleteScripts { script_ids },
+                            );
+                            crate::dispatch_folder_event(FolderEvent::RestoredFromTrash);
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to restore scripts", &e)),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::DeleteFolderPermanently { folder_id } => {
+                let folder_repository = self.folder_repository.clone();
+                crate::spawn_task(async move {
+                    let result = match folder_repository.delete_folder_permanently(folder_id).await
+                    {
+                        Ok(_) => {
+                            log::debug!(
+                                "Folder id {} and related data deleted for good",
+                                folder_id
+                            );
+                            crate::dispatch_folder_event(FolderEvent::TrashPurged { count: 1 });
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to delete folder", &e)),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::DeleteScriptPermanently { script_id } => {
+                let script_repository = self.script_repository.clone();
+                crate::spawn_task(async move {
+                    let result = match script_repository.delete_script(script_id).await {
+                        Ok(_) => {
+                            log::debug!("Script id {} deleted for good", script_id);
+                            crate::dispatch_folder_event(FolderEvent::TrashPurged { count: 1 });
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to delete script", &e)),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::EmptyTrash => {
+                let trash_repository = self.trash_repository.clone();
+                crate::spawn_task(async move {
+                    let result = match trash_repository.purge(None).await {
+                        Ok(count) => {
+                            crate::dispatch_folder_event(FolderEvent::TrashPurged { count });
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to empty the trash", &e)),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::PurgeExpiredTrash => {
+                let trash_repository = self.trash_repository.clone();
+                crate::spawn_task(async move {
+                    let purged = match trash_repository.get_retention_days().await {
+                        Ok(days) => trash_repository.purge(Some(days)).await,
+                        Err(e) => Err(e),
+                    };
+                    let result = match purged {
+                        Ok(count) => {
+                            crate::dispatch_folder_event(FolderEvent::TrashPurged { count });
+                            Ok(FolderCommandOutput::Done)
+                        }
+                        Err(e) => Err(CommandError::report("Failed to purge the trash", &e)),
+                    };
+
+                    reply.send(result);
+                });
+            }
+            FolderCommand::SetTrashRetentionDays { days } => {
+                let trash_repository = self.trash_repository.clone();
+                crate::spawn_task(async move {
+                    let result = match trash_repository.set_retention_days(days).await {
+                        Ok(_) => {
+                            crate::dispatch_folder_event(FolderEvent::TrashRetentionDaysChanged {
+                                days,
                             });
+                            Ok(FolderCommandOutput::Done)
                         }
-                        Err(e) => eprintln!("Failed to reorder folders: {:?}", e),
-                    }
-                    if let Some(cb) = callback {
-                        let _ = crate::EVENT_SENDER
-                            .get()
-                            .unwrap()
-                            .send(crate::AppMessage::Callback(cb));
-                    }
+                        Err(e) => Err(CommandError::report(
+                            "Failed to save the trash retention",
+                            &e,
+                        )),
+                    };
+
+                    reply.send(result);
                 });
             }
+            FolderCommand::Undo => match self.history.take_undo() {
+                Some(entry) => {
+                    log::debug!("Undoing '{}'", entry.label);
+                    let history = self.history.clone();
+                    let reply = put_back_on_failure(reply, entry.clone(), move |entry| {
+                        history.put_back_undo(entry)
+                    });
+                    self.apply(entry.command, reply, CommandOrigin::Undo(entry.label));
+                }
+                // Nothing to undo
+                None => reply.send(Ok(FolderCommandOutput::Done)),
+            },
+            FolderCommand::Redo => match self.history.take_redo() {
+                Some(entry) => {
+                    log::debug!("Redoing '{}'", entry.label);
+                    let history = self.history.clone();
+                    let reply = put_back_on_failure(reply, entry.clone(), move |entry| {
+                        history.put_back_redo(entry)
+                    });
+                    self.apply(entry.command, reply, CommandOrigin::Redo(entry.label));
+                }
+                // Nothing to redo
+                None => reply.send(Ok(FolderCommandOutput::Done)),
+            },
         }
-        Ok(())
+    }
+}
+
+// A replayed step whose command failed is not lost, `put_back` returns it to its stack
+fn put_back_on_failure<F>(
+    reply: Reply<FolderCommandResult>,
+    entry: HistoryEntry,
+    put_back: F,
+) -> Reply<FolderCommandResult>
+where
+    F: FnOnce(HistoryEntry) + Send + 'static,
+{
+    Reply::to(move |result: FolderCommandResult| {
+        if result.is_err() {
+            put_back(entry);
+        }
+        reply.send(result);
+    })
+}
+
+// The state of a script as the edit window shows it, to take an edit back to
+fn script_details(script: &crate::prisma::shell_script::Data) -> ScriptDetails {
+    let execution = ScriptExecution::from(script);
+    ScriptDetails {
+        command: script.command.clone(),
+        description: script.description.clone(),
+        interpreter_settings: execution.interpreter_settings,
+        working_directory: execution.working_directory,
+        env_vars: execution.env_vars,
+        tags: tags_of_script(script)
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
     }
 }
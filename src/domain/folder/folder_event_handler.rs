@@ -1,25 +1,50 @@
 use std::sync::Arc;
 
+use crate::bus::Event;
 use crate::db::get_db::get_db;
 use crate::db::repository::folder_repository::FolderRepository;
-use crate::db::repository::script_repository::ScriptRepository;
+use crate::db::repository::script_repository::{ScriptRepository, ScriptSearchHit};
+use crate::db::repository::script_revision_repository::ScriptRevisionRepository;
+use crate::db::repository::trash_repository::TrashContents;
+use crate::domain::folder::folder_command_handler::FolderCommand;
 use crate::with_folder_state_reducer;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum FolderEvent {
-    FolderAdded { name: String, ordering: i32 },
+    FolderAdded { name: String, ordering: i32, parent_id: Option<i32> },
     FolderSelected { folder_id: i32 },
     FolderDeleted { folder_id: i32 },
     ScriptAdded { folder_id: i32 },
     ScriptUpdated { script_id: i32 },
     FolderRenamed { folder_id: i32, new_name: String },
     ScriptDeleted { script_id: i32 },
-    FoldersReordered { from_index: i32, to_index: i32 },
+    FolderMoved { folder_id: i32, parent_id: Option<i32> },
+    ScriptLinkedToFolder { script_id: i32, folder_id: i32 },
+    ScriptsReordered { folder_id: i32 },
+    ScriptMovedToFolder { script_id: i32, from_folder_id: i32, to_folder_id: i32 },
+    ScriptRemovedFromFolder { script_id: i32, folder_id: i32 },
+    ScriptsSearched { query: String, hits: Vec<ScriptSearchHit> },
+    TagsChanged,
+    TagsLoaded { tags: Vec<String> },
+    ScriptsOfTagLoaded { tag: String, scripts: Vec<crate::prisma::shell_script::Data> },
+    ScriptPinned { script_id: i32, pinned: bool },
+    PinnedScriptsLoaded { scripts: Vec<crate::prisma::shell_script::Data> },
+    PinnedScriptsReordered,
+    RevisionHistoryOpened { script_id: i32 },
+    TrashLoaded { trash: TrashContents },
+    RestoredFromTrash,
+    TrashPurged { count: usize },
+    TrashRetentionDaysChanged { days: u32 },
+    // What undo and redo would take back or do again next, `None` when there is nothing
+    HistoryChanged { undo_label: Option<String>, redo_label: Option<String> },
 }
 
+impl Event for FolderEvent {}
+
 pub struct FolderEventHandler {
     folder_repository: Arc<FolderRepository>,
     script_repository: Arc<ScriptRepository>,
+    script_revision_repository: Arc<ScriptRevisionRepository>,
 }
 
 impl FolderEventHandler {
@@ -27,6 +52,7 @@ impl FolderEventHandler {
         Self {
             folder_repository: Arc::new(FolderRepository::new()),
             script_repository: Arc::new(ScriptRepository::new()),
+            script_revision_repository: Arc::new(ScriptRevisionRepository::new()),
         }
     }
 
@@ -34,25 +60,31 @@ impl FolderEventHandler {
         let folder_repository = self.folder_repository.clone();
         let script_repository = self.script_repository.clone();
         match event {
-            FolderEvent::FoldersReordered{from_index, to_index} => {
-                with_folder_state_reducer(
-                    |reducer| reducer.insert_folder_into_index(from_index as usize, to_index as usize)
-                )
+            FolderEvent::FolderMoved {
+                folder_id,
+                parent_id,
+            } => {
+                log::debug!("Folder moved event received for folder id: {}", folder_id);
+                // Shows the folder where it was dropped
+                if let Some(parent_id) = parent_id {
+                    with_folder_state_reducer(|reducer| reducer.expand_folder(parent_id));
+                }
+                Self::reload_folders(folder_repository);
             }
-            FolderEvent::FolderAdded { name, ordering } => {
+            FolderEvent::FolderAdded {
+                name,
+                ordering,
+                parent_id,
+            } => {
                 // fetch all folder and set it into the state
                 println!(
                     "Folder added event received for folder: {}, now refetch all folders",
                     name
                 );
-                crate::spawn_task(async move {
-                    match folder_repository.get_all_folders().await {
-                        Ok(folders) => {
-                            crate::with_folder_state_reducer(|r| r.set_folder_list(folders));
-                        }
-                        Err(e) => eprintln!("Failed to load folders: {:?}", e),
-                    }
-                });
+                if let Some(parent_id) = parent_id {
+                    with_folder_state_reducer(|reducer| reducer.expand_folder(parent_id));
+                }
+                Self::reload_folders(folder_repository);
             }
             FolderEvent::FolderSelected { folder_id: id } => {
                 println!("Folder selected event received for folder id: {}", id);
@@ -66,10 +98,7 @@ impl FolderEventHandler {
                             crate::with_folder_state_reducer(|r| r.set_app_state(Some(app_state)));
 
                             if let Some(folder_id) = folder_id_opt {
-                                match script_repository
-                                    .get_scripts_with_relations_by_folder(folder_id)
-                                    .await
-                                {
+                                match script_repository.get_scripts_by_folder(folder_id).await {
                                     Ok(folder_scripts) => {
                                         println!(
                                             "Found {} scripts for folder {}",
@@ -81,7 +110,7 @@ impl FolderEventHandler {
                                         });
                                     }
                                     Err(e) => {
-                                        eprintln!("Failed to load scripts for folder: {:?}", e)
+                                        crate::report_error("Failed to load scripts for folder", &e)
                                     }
                                 }
                             } else {
@@ -91,15 +120,30 @@ impl FolderEventHandler {
                         Ok(None) => {
                             println!("No application state found");
                         }
-                        Err(e) => eprintln!("Failed to load application state: {:?}", e),
+                        Err(e) => crate::report_error("Failed to load application state", &e),
                     }
                 });
             }
             FolderEvent::FolderDeleted { folder_id } => {
                 with_folder_state_reducer(|reducer| reducer.delete_folder(folder_id));
                 println!("Folder deleted event received for folder id: {}", folder_id);
+                // The remaining siblings were renumbered
+                Self::reload_folders(folder_repository);
+                Self::refresh_tag_filter();
+                crate::dispatch_folder_command(FolderCommand::LoadTags);
+                // Pinned scripts of the deleted folders went with them
+                crate::dispatch_folder_command(FolderCommand::LoadPinnedScripts);
+                crate::dispatch_folder_command(FolderCommand::LoadTrash);
             }
             FolderEvent::ScriptAdded { folder_id } => {
+                Self::refresh_script_search();
+                Self::refresh_tag_filter();
+                // Scripts can be added to other folders than the open one, e.g. by the history import
+                let selected_folder_id =
+                    crate::with_folder_state(|state| *state.selected_folder_id.read().unwrap());
+                if selected_folder_id != Some(folder_id) {
+                    return;
+                }
                 crate::spawn_task(async move {
                     // must be those scripts of folder with folder_id, need to left join rel table
                     match script_repository.get_scripts_by_folder(folder_id).await {
@@ -108,12 +152,16 @@ impl FolderEventHandler {
                                 r.set_scripts_of_selected_folder(scripts)
                             });
                         }
-                        Err(e) => eprintln!("Failed to load scripts: {:?}", e),
+                        Err(e) => crate::report_error("Failed to load scripts", &e),
                     }
                 });
             }
             FolderEvent::ScriptUpdated { script_id } => {
                 println!("Script updated event received for script id: {}", script_id);
+                Self::refresh_script_search();
+                Self::refresh_tag_filter();
+                Self::refresh_pinned_scripts();
+                self.reload_revisions_of(script_id);
                 crate::with_folder_state(|state| {
                     if let Some(folder_id) = *state.selected_folder_id.read().unwrap() {
                         crate::spawn_task(async move {
@@ -123,7 +171,7 @@ impl FolderEventHandler {
                                         r.set_scripts_of_selected_folder(scripts)
                                     });
                                 }
-                                Err(e) => eprintln!("Failed to reload scripts: {:?}", e),
+                                Err(e) => crate::report_error("Failed to reload scripts", &e),
                             }
                         });
                     }
@@ -141,12 +189,205 @@ impl FolderEventHandler {
             }
             FolderEvent::ScriptDeleted { script_id } => {
                 println!("Script deleted event received for script id: {}", script_id);
+                Self::refresh_script_search();
+                Self::refresh_tag_filter();
+                // Tags only this script carried are gone
+                crate::dispatch_folder_command(FolderCommand::LoadTags);
+                Self::refresh_pinned_scripts();
+                self.reload_revisions_of(script_id);
+                crate::dispatch_folder_command(FolderCommand::LoadTrash);
                 // just remove the script from UI state
                 crate::with_folder_state_reducer(|r| {
                     r.delete_script_from_selected_folder(script_id)
                 });
             }
+            FolderEvent::ScriptLinkedToFolder {
+                script_id,
+                folder_id,
+            }
+            | FolderEvent::ScriptRemovedFromFolder {
+                script_id,
+                folder_id,
+            } => {
+                log::debug!(
+                    "Folders of script id {} changed (folder id {})",
+                    script_id,
+                    folder_id
+                );
+                Self::refresh_script_search();
+                Self::refresh_tag_filter();
+                Self::refresh_pinned_scripts();
+                // Either the list itself or the folder indicators of its cards changed
+                Self::reload_scripts_of_selected_folder(script_repository);
+                // A script removed from its last folder went to the trash
+                crate::dispatch_folder_command(FolderCommand::LoadTrash);
+            }
+            FolderEvent::ScriptMovedToFolder {
+                script_id,
+                from_folder_id,
+                to_folder_id,
+            } => {
+                log::debug!(
+                    "Script id {} moved from folder id {} to folder id {}",
+                    script_id,
+                    from_folder_id,
+                    to_folder_id
+                );
+                Self::refresh_script_search();
+                Self::refresh_tag_filter();
+                Self::refresh_pinned_scripts();
+                Self::reload_scripts_of_selected_folder(script_repository);
+            }
+            FolderEvent::ScriptsReordered { folder_id } => {
+                log::debug!("Scripts of folder id {} reordered", folder_id);
+                Self::reload_scripts_of_selected_folder(script_repository);
+            }
+            FolderEvent::ScriptsSearched { query, hits } => {
+                crate::with_folder_state_reducer(|r| r.set_script_search_hits(&query, hits));
+            }
+            FolderEvent::TagsChanged => {
+                crate::dispatch_folder_command(FolderCommand::LoadTags);
+            }
+            FolderEvent::TagsLoaded { tags } => {
+                crate::with_folder_state_reducer(|r| r.set_tag_list(tags));
+            }
+            FolderEvent::ScriptsOfTagLoaded { tag, scripts } => {
+                crate::with_folder_state_reducer(|r| r.set_scripts_with_tag(&tag, scripts));
+            }
+            FolderEvent::ScriptPinned { script_id, pinned } => {
+                log::debug!("Script id {} pinned: {}", script_id, pinned);
+                crate::dispatch_folder_command(FolderCommand::LoadPinnedScripts);
+                // The pin buttons of the folder's cards
+                Self::reload_scripts_of_selected_folder(script_repository);
+            }
+            FolderEvent::PinnedScriptsLoaded { scripts } => {
+                crate::with_folder_state_reducer(|r| r.set_pinned_scripts(scripts));
+            }
+            FolderEvent::PinnedScriptsReordered => {
+                crate::dispatch_folder_command(FolderCommand::LoadPinnedScripts);
+            }
+            FolderEvent::RevisionHistoryOpened { script_id } => {
+                crate::with_folder_state_reducer(|r| r.open_revision_history(script_id));
+                self.reload_revisions_of(script_id);
+            }
+            FolderEvent::TrashLoaded { trash } => {
+                crate::with_folder_state_reducer(|r| r.set_trash(trash));
+            }
+            FolderEvent::RestoredFromTrash => {
+                // Restored folders and scripts can show up anywhere
+                Self::reload_folders(folder_repository);
+                Self::reload_scripts_of_selected_folder(script_repository);
+                Self::refresh_script_search();
+                Self::refresh_tag_filter();
+                crate::dispatch_folder_command(FolderCommand::LoadTags);
+                crate::dispatch_folder_command(FolderCommand::LoadPinnedScripts);
+                crate::dispatch_folder_command(FolderCommand::LoadTrash);
+            }
+            FolderEvent::TrashPurged { count } => {
+                log::debug!("{} item(s) deleted from the trash for good", count);
+                crate::dispatch_folder_command(FolderCommand::LoadTrash);
+            }
+            FolderEvent::TrashRetentionDaysChanged { days } => {
+                log::debug!("Trash retention set to {} day(s)", days);
+                crate::spawn_task(async move {
+                    match folder_repository.get_app_state().await {
+                        Ok(app_state) => {
+                            crate::with_folder_state_reducer(|r| r.set_app_state(app_state));
+                        }
+                        Err(e) => crate::report_error("Failed to load application state", &e),
+                    }
+                });
+            }
+            FolderEvent::HistoryChanged {
+                undo_label,
+                redo_label,
+            } => {
+                with_folder_state_reducer(|r| r.set_history_labels(undo_label, redo_label));
+            }
         };
     }
-    
+
+    fn reload_scripts_of_selected_folder(script_repository: Arc<ScriptRepository>) {
+        let Some(folder_id) =
+            crate::with_folder_state(|state| *state.selected_folder_id.read().unwrap())
+        else {
+            return;
+        };
+        crate::spawn_task(async move {
+            match script_repository.get_scripts_by_folder(folder_id).await {
+                Ok(scripts) => {
+                    crate::with_folder_state_reducer(|r| r.set_scripts_of_selected_folder(scripts));
+                }
+                Err(e) => crate::report_error("Failed to reload scripts", &e),
+            }
+        });
+    }
+
+    // Only refetch when the versions window is showing this script
+    fn reload_revisions_of(&self, script_id: i32) {
+        let opened_script_id =
+            crate::with_folder_state(|state| *state.revision_history_script_id.read().unwrap());
+        if opened_script_id != Some(script_id) {
+            return;
+        }
+        let script_repository = self.script_repository.clone();
+        let script_revision_repository = self.script_revision_repository.clone();
+        crate::spawn_task(async move {
+            let script = match script_repository.get_script_by_id(script_id).await {
+                Ok(Some(script)) => script,
+                Ok(None) => {
+                    crate::with_folder_state_reducer(|r| r.close_revision_history());
+                    return;
+                }
+                Err(e) => {
+                    crate::report_error("Failed to load script", &e);
+                    return;
+                }
+            };
+            match script_revision_repository
+                .get_revisions_by_script(script_id)
+                .await
+            {
+                Ok(revisions) => {
+                    crate::with_folder_state_reducer(|r| r.set_script_revisions(script, revisions));
+                }
+                Err(e) => crate::report_error("Failed to load script revisions", &e),
+            }
+        });
+    }
+
+    fn reload_folders(folder_repository: Arc<FolderRepository>) {
+        crate::spawn_task(async move {
+            match folder_repository.get_all_folders().await {
+                Ok(folders) => {
+                    crate::with_folder_state_reducer(|r| r.set_folder_list(folders));
+                }
+                Err(e) => crate::report_error("Failed to load folders", &e),
+            }
+        });
+    }
+
+    // Same for the scripts of all folders listed by the tag filter
+    fn refresh_tag_filter() {
+        let tag = crate::with_folder_state(|state| state.tag_filter.read().unwrap().clone());
+        if let Some(tag) = tag {
+            crate::dispatch_folder_command(FolderCommand::FilterScriptsByTag { tag });
+        }
+    }
+
+    // Edits of unpinned scripts cannot change the "Pinned" list, nothing to reload while it is empty
+    fn refresh_pinned_scripts() {
+        if crate::with_folder_state(|state| !state.pinned_scripts.read().unwrap().is_empty()) {
+            crate::dispatch_folder_command(FolderCommand::LoadPinnedScripts);
+        }
+    }
+
+    // Keeps the search results in line with the edited scripts while a search is showing
+    fn refresh_script_search() {
+        let query =
+            crate::with_folder_state(|state| state.script_search_query.read().unwrap().clone());
+        if !query.trim().is_empty() {
+            crate::dispatch_folder_command(FolderCommand::SearchScripts { query });
+        }
+    }
 }
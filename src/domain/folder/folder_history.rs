@@ -0,0 +1,99 @@
+use crate::domain::folder::folder_command_handler::FolderCommand;
+use crate::domain::folder::folder_event_handler::FolderEvent;
+use std::sync::Mutex;
+
+// Older steps are dropped beyond this
+const MAX_HISTORY_LEN: usize = 100;
+
+// Why a command runs, which decides the stack its inverse goes to. Undo and redo carry the label
+// of the step they replay, so that it reads the same on both stacks.
+#[derive(Debug, Clone)]
+pub enum CommandOrigin {
+    User,
+    Undo(String),
+    Redo(String),
+}
+
+// A step that can be taken back: the command doing so and what the menu calls it
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub label: String,
+    pub command: FolderCommand,
+}
+
+#[derive(Default)]
+struct Stacks {
+    undo: Vec<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+}
+
+// Undo and redo stacks of the folder commands. Commands record their inverse once they went
+// through, replaying an entry runs its command through the handler like any other.
+#[derive(Default)]
+pub struct FolderHistory {
+    stacks: Mutex<Stacks>,
+}
+
+impl FolderHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, origin: &CommandOrigin, label: &str, inverse: FolderCommand) {
+        {
+            let mut stacks = self.stacks.lock().unwrap();
+            let (stack, label) = match origin {
+                CommandOrigin::User => {
+                    // A new step makes the undone ones unreachable
+                    stacks.redo.clear();
+                    (&mut stacks.undo, label)
+                }
+                CommandOrigin::Undo(label) => (&mut stacks.redo, label.as_str()),
+                CommandOrigin::Redo(label) => (&mut stacks.undo, label.as_str()),
+            };
+            stack.push(HistoryEntry {
+                label: label.to_string(),
+                command: inverse,
+            });
+            if stack.len() > MAX_HISTORY_LEN {
+                stack.remove(0);
+            }
+        }
+        self.notify();
+    }
+
+    pub fn take_undo(&self) -> Option<HistoryEntry> {
+        let entry = self.stacks.lock().unwrap().undo.pop();
+        self.notify();
+        entry
+    }
+
+    pub fn take_redo(&self) -> Option<HistoryEntry> {
+        let entry = self.stacks.lock().unwrap().redo.pop();
+        self.notify();
+        entry
+    }
+
+    // For a taken entry whose command failed, it goes back on top as if it was never taken
+    pub fn put_back_undo(&self, entry: HistoryEntry) {
+        self.stacks.lock().unwrap().undo.push(entry);
+        self.notify();
+    }
+
+    pub fn put_back_redo(&self, entry: HistoryEntry) {
+        self.stacks.lock().unwrap().redo.push(entry);
+        self.notify();
+    }
+
+    fn notify(&self) {
+        let (undo_label, redo_label) = {
+            let stacks = self.stacks.lock().unwrap();
+            let label = |stack: &[HistoryEntry]| stack.last().map(|entry| entry.label.clone());
+            (label(&stacks.undo), label(&stacks.redo))
+        };
+        crate::dispatch_folder_event(FolderEvent::HistoryChanged {
+            undo_label,
+            redo_label,
+        });
+    }
+}
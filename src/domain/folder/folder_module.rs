@@ -0,0 +1,16 @@
+use crate::bus::{Bus, DomainModule};
+use crate::domain::folder::folder_command_handler::{FolderCommand, FolderCommandHandler};
+use crate::domain::folder::folder_event_handler::{FolderEvent, FolderEventHandler};
+
+pub struct FolderModule;
+
+impl DomainModule for FolderModule {
+    fn register(self, bus: &mut Bus) {
+        let command_handler = FolderCommandHandler::new();
+        bus.handle_commands(move |command: FolderCommand, reply| {
+            command_handler.handle(command, reply)
+        });
+        let event_handler = FolderEventHandler::new();
+        bus.subscribe(move |event: FolderEvent| event_handler.handle(event));
+    }
+}
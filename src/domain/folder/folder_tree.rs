@@ -0,0 +1,205 @@
+use crate::prisma::scripts_folder::Data;
+use std::collections::HashSet;
+
+// A folder as shown in the left column, `depth` 0 being a top-level folder
+pub struct FolderRow<'a> {
+    pub folder: &'a Data,
+    pub depth: usize,
+    pub has_children: bool,
+}
+
+// Children of `parent_id` (top-level folders for `None`) in their ordering
+pub fn child_folders(folders: &[Data], parent_id: Option<i32>) -> Vec<&Data> {
+    let mut children: Vec<&Data> = folders
+        .iter()
+        .filter(|folder| folder.parent_id == parent_id)
+        .collect();
+    children.sort_by_key(|folder| (folder.ordering, folder.id));
+    children
+}
+
+// The folder followed by all of its descendants, parents before their children
+pub fn subtree_ids(folders: &[Data], folder_id: i32) -> Vec<i32> {
+    let mut ids = vec![folder_id];
+    let mut index = 0;
+    while index < ids.len() {
+        let children: Vec<i32> = child_folders(folders, Some(ids[index]))
+            .into_iter()
+            .map(|folder| folder.id)
+            // A corrupted parent chain must not loop forever
+            .filter(|id| !ids.contains(id))
+            .collect();
+        ids.extend(children);
+        index += 1;
+    }
+    ids
+}
+
+// The folder with the subfolders that went to the trash together with it, i.e. in the same
+// `deleted_at`, parents before their children
+pub fn trashed_together(folders: &[Data], folder_id: i32) -> Vec<i32> {
+    let deleted_at = folders
+        .iter()
+        .find(|folder| folder.id == folder_id)
+        .and_then(|folder| folder.deleted_at);
+    subtree_ids(folders, folder_id)
+        .into_iter()
+        .filter(|id| {
+            folders
+                .iter()
+                .any(|folder| folder.id == *id && folder.deleted_at == deleted_at)
+        })
+        .collect()
+}
+
+// Depth-first rows of the tree, skipping the children of collapsed folders
+pub fn visible_rows<'a>(folders: &'a [Data], collapsed: &HashSet<i32>) -> Vec<FolderRow<'a>> {
+    let mut rows = vec![];
+    let mut visited = HashSet::new();
+    push_rows(folders, None, 0, collapsed, &mut visited, &mut rows);
+    rows
+}
+
+fn push_rows<'a>(
+    folders: &'a [Data],
+    parent_id: Option<i32>,
+    depth: usize,
+    collapsed: &HashSet<i32>,
+    visited: &mut HashSet<i32>,
+    rows: &mut Vec<FolderRow<'a>>,
+) {
+    for folder in child_folders(folders, parent_id) {
+        if !visited.insert(folder.id) {
+            continue;
+        }
+        let has_children = folders.iter().any(|f| f.parent_id == Some(folder.id));
+        rows.push(FolderRow {
+            folder,
+            depth,
+            has_children,
+        });
+        if has_children && !collapsed.contains(&folder.id) {
+            push_rows(
+                folders,
+                Some(folder.id),
+                depth + 1,
+                collapsed,
+                visited,
+                rows,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folder(id: i32, parent_id: Option<i32>, ordering: i32) -> Data {
+        Data {
+            id,
+            name: format!("Folder {}", id),
+            ordering,
+            parent_id,
+            deleted_at: None,
+            created_at: 0.0,
+            created_at_hk: String::new(),
+            rel_scriptsfolder_shellscript: None,
+        }
+    }
+
+    fn trashed(mut folder: Data, deleted_at: f64) -> Data {
+        folder.deleted_at = Some(deleted_at);
+        folder
+    }
+
+    fn ids(folders: Vec<&Data>) -> Vec<i32> {
+        folders.into_iter().map(|folder| folder.id).collect()
+    }
+
+    // 1
+    // ├── 3
+    // │   └── 5
+    // └── 4
+    // 2
+    fn tree() -> Vec<Data> {
+        vec![
+            folder(5, Some(3), 0),
+            folder(4, Some(1), 1),
+            folder(3, Some(1), 0),
+            folder(2, None, 1),
+            folder(1, None, 0),
+        ]
+    }
+
+    #[test]
+    fn lists_children_in_their_ordering() {
+        let folders = tree();
+        assert_eq!(ids(child_folders(&folders, None)), vec![1, 2]);
+        assert_eq!(ids(child_folders(&folders, Some(1))), vec![3, 4]);
+        assert!(child_folders(&folders, Some(5)).is_empty());
+
+        // Equal orderings fall back to the id
+        let folders = vec![folder(7, None, 0), folder(6, None, 0)];
+        assert_eq!(ids(child_folders(&folders, None)), vec![6, 7]);
+    }
+
+    #[test]
+    fn subtree_lists_parents_before_their_children() {
+        let folders = tree();
+        assert_eq!(subtree_ids(&folders, 1), vec![1, 3, 4, 5]);
+        assert_eq!(subtree_ids(&folders, 3), vec![3, 5]);
+        assert_eq!(subtree_ids(&folders, 2), vec![2]);
+    }
+
+    #[test]
+    fn subtree_stops_at_a_parent_cycle() {
+        let folders = vec![
+            folder(1, None, 0),
+            folder(2, Some(3), 0),
+            folder(3, Some(2), 0),
+            folder(4, Some(4), 0),
+        ];
+        assert_eq!(subtree_ids(&folders, 2), vec![2, 3]);
+        assert_eq!(subtree_ids(&folders, 4), vec![4]);
+    }
+
+    #[test]
+    fn restores_only_the_subfolders_trashed_together() {
+        let folders = vec![
+            trashed(folder(1, None, 0), 200.0),
+            trashed(folder(2, Some(1), 0), 200.0),
+            // Trashed with its subfolder before its parent
+            trashed(folder(3, Some(1), 1), 100.0),
+            trashed(folder(4, Some(3), 0), 100.0),
+        ];
+        assert_eq!(trashed_together(&folders, 1), vec![1, 2]);
+        assert_eq!(trashed_together(&folders, 3), vec![3, 4]);
+    }
+
+    #[test]
+    fn visible_rows_skip_the_children_of_collapsed_folders() {
+        let folders = tree();
+        let rows = |collapsed: &[i32]| -> Vec<(i32, usize, bool)> {
+            visible_rows(&folders, &collapsed.iter().copied().collect())
+                .into_iter()
+                .map(|row| (row.folder.id, row.depth, row.has_children))
+                .collect()
+        };
+        assert_eq!(
+            rows(&[]),
+            vec![
+                (1, 0, true),
+                (3, 1, true),
+                (5, 2, false),
+                (4, 1, false),
+                (2, 0, false),
+            ]
+        );
+        assert_eq!(
+            rows(&[3]),
+            vec![(1, 0, true), (3, 1, true), (4, 1, false), (2, 0, false)]
+        );
+        assert_eq!(rows(&[1]), vec![(1, 0, true), (2, 0, false)]);
+    }
+}
@@ -1,3 +1,8 @@
 pub mod folder_command_handler;
 pub mod folder_event_handler;
+pub mod folder_history;
+pub mod folder_module;
+pub mod folder_tree;
 mod policy;
+pub mod script_diff;
+pub mod script_tags;
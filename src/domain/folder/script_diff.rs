@@ -0,0 +1,128 @@
+use similar::{ChangeTag, TextDiff};
+
+// Unchanged lines kept around each change
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    Unchanged,
+    Removed,
+    Added,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    // 1-based line numbers in the old and the new text, `None` on the side the line is missing from
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    pub text: String,
+}
+
+// Unified line diff from `old` to `new`, split into hunks of changes with their context.
+// Identical texts give no hunks.
+pub fn unified_diff(old: &str, new: &str) -> Vec<Vec<DiffLine>> {
+    let diff = TextDiff::from_lines(old, new);
+    diff.grouped_ops(CONTEXT_LINES)
+        .iter()
+        .map(|hunk| {
+            hunk.iter()
+                .flat_map(|op| diff.iter_changes(op))
+                .map(|change| DiffLine {
+                    kind: match change.tag() {
+                        ChangeTag::Equal => DiffLineKind::Unchanged,
+                        ChangeTag::Delete => DiffLineKind::Removed,
+                        ChangeTag::Insert => DiffLineKind::Added,
+                    },
+                    old_line: change.old_index().map(|index| index + 1),
+                    new_line: change.new_index().map(|index| index + 1),
+                    text: change.value().trim_end_matches(['\r', '\n']).to_string(),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(hunk: &[DiffLine]) -> Vec<(DiffLineKind, Option<usize>, Option<usize>, &str)> {
+        hunk.iter()
+            .map(|line| (line.kind, line.old_line, line.new_line, line.text.as_str()))
+            .collect()
+    }
+
+    fn numbered_lines(count: usize) -> String {
+        (1..=count).map(|n| format!("line {}\n", n)).collect()
+    }
+
+    #[test]
+    fn identical_texts_have_no_hunks() {
+        assert!(unified_diff("echo a\necho b\n", "echo a\necho b\n").is_empty());
+        assert!(unified_diff("", "").is_empty());
+    }
+
+    #[test]
+    fn numbers_the_lines_of_both_sides() {
+        let hunks = unified_diff("a\nb\nc\n", "a\nB\nc\nd\n");
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(
+            summary(&hunks[0]),
+            vec![
+                (DiffLineKind::Unchanged, Some(1), Some(1), "a"),
+                (DiffLineKind::Removed, Some(2), None, "b"),
+                (DiffLineKind::Added, None, Some(2), "B"),
+                (DiffLineKind::Unchanged, Some(3), Some(3), "c"),
+                (DiffLineKind::Added, None, Some(4), "d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_three_lines_of_context_around_a_change() {
+        let old = numbered_lines(20);
+        let new = old.replace("line 10\n", "line ten\n");
+        let hunks = unified_diff(&old, &new);
+        assert_eq!(hunks.len(), 1);
+        let old_lines: Vec<Option<usize>> = hunks[0].iter().map(|line| line.old_line).collect();
+        assert_eq!(
+            old_lines,
+            vec![
+                Some(7),
+                Some(8),
+                Some(9),
+                Some(10),
+                None,
+                Some(11),
+                Some(12),
+                Some(13)
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_distant_changes_into_hunks() {
+        let old = numbered_lines(30);
+        let new = old
+            .replace("line 2\n", "line two\n")
+            .replace("line 28\n", "line twenty-eight\n");
+        let hunks = unified_diff(&old, &new);
+        assert_eq!(hunks.len(), 2);
+        assert!(hunks[0].iter().any(|line| line.text == "line two"));
+        assert!(hunks[1].iter().any(|line| line.text == "line twenty-eight"));
+    }
+
+    #[test]
+    fn strips_line_endings_and_handles_a_missing_final_newline() {
+        let hunks = unified_diff("echo a\r\necho b", "echo a\r\necho c");
+        assert_eq!(
+            summary(&hunks[0]),
+            vec![
+                (DiffLineKind::Unchanged, Some(1), Some(1), "echo a"),
+                (DiffLineKind::Removed, Some(2), None, "echo b"),
+                (DiffLineKind::Added, None, Some(2), "echo c"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use crate::prisma::shell_script::Data;
+
+// `#Prod`, ` prod ` and `prod` are the same tag, spaces inside a tag become dashes
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().trim_start_matches('#').trim();
+    if tag.is_empty() {
+        return None;
+    }
+    Some(
+        tag.split_whitespace()
+            .collect::<Vec<_>>()
+            .join("-")
+            .to_lowercase(),
+    )
+}
+
+// Tags typed into the edit window, separated by commas, duplicates removed
+pub fn parse_tag_list(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = vec![];
+    for tag in text.split(',').filter_map(normalize_tag) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+// Requires the tags to be fetched with the script, see `tags_fetch`
+pub fn tags_of_script(script: &Data) -> Vec<&str> {
+    let mut tags: Vec<&str> = script
+        .rel_shellscript_tag()
+        .map(|rels| {
+            rels.iter()
+                .filter_map(|rel| rel.tag().ok())
+                .map(|tag| tag.name.as_str())
+                .collect()
+        })
+        .unwrap_or_default();
+    tags.sort_unstable();
+    tags
+}
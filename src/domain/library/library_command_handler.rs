@@ -0,0 +1,267 @@
+use crate::bus::Command;
+use crate::db::repository::folder_repository::FolderRepository;
+use crate::db::repository::library_repository::LibraryRepository;
+use crate::db::repository::script_repository::ScriptRepository;
+use crate::domain::library::library_document::{
+    ConflictPolicy, ImportMode, LibraryDocument, LibraryFolder,
+};
+use crate::domain::library::library_event_handler::LibraryEvent;
+use crate::domain::library::project_tasks::discover_project_tasks;
+use crate::domain::library::shell_files::{
+    export_folder_files, read_shell_script_files, write_files,
+};
+use crate::domain::library::shell_history::{dedupe_history, history_files, read_history_file};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub enum LibraryCommand {
+    // JSON or YAML, depending on the extension of the path
+    Export {
+        path: PathBuf,
+    },
+    Import {
+        path: PathBuf,
+        mode: ImportMode,
+    },
+    // Reads ~/.zsh_history and ~/.bash_history for the history import checklist
+    LoadShellHistory,
+    // Creates or updates a folder with the package.json, Makefile and justfile tasks of a project
+    ImportProject {
+        directory: PathBuf,
+    },
+    // One executable file per script of the folder, optionally with a `select` menu over them
+    ExportFolderAsShellScripts {
+        folder_id: i32,
+        directory: PathBuf,
+        with_menu: bool,
+    },
+    // The inverse of ExportFolderAsShellScripts, scripts of the same name are overwritten
+    ImportShellScripts {
+        directory: PathBuf,
+    },
+}
+
+impl Command for LibraryCommand {
+    type Output = ();
+}
+
+pub struct LibraryCommandHandler {
+    library_repository: Arc<LibraryRepository>,
+    folder_repository: Arc<FolderRepository>,
+    script_repository: Arc<ScriptRepository>,
+}
+
+impl LibraryCommandHandler {
+    pub fn new() -> Self {
+        Self {
+            library_repository: Arc::new(LibraryRepository::new()),
+            folder_repository: Arc::new(FolderRepository::new()),
+            script_repository: Arc::new(ScriptRepository::new()),
+        }
+    }
+
+    pub fn handle(&self, command: LibraryCommand) {
+        match command {
+            LibraryCommand::Export { path } => {
+                let library_repository = self.library_repository.clone();
+                crate::spawn_task(async move {
+                    let document = match library_repository.export_library().await {
+                        Ok(document) => document,
+                        Err(e) => {
+                            report_failure(
+                                "Failed to load the library for export",
+                                &e,
+                                format!("Export failed: {}", e),
+                            );
+                            return;
+                        }
+                    };
+                    match document.write_to(&path) {
+                        Ok(_) => {
+                            crate::dispatch_library_event(LibraryEvent::Exported {
+                                path,
+                                folder_count: document.all_folders().len(),
+                                script_count: document.script_count(),
+                            });
+                        }
+                        Err(message) => report_failure("Export failed", &message, message.clone()),
+                    }
+                });
+            }
+            LibraryCommand::Import { path, mode } => {
+                let library_repository = self.library_repository.clone();
+                crate::spawn_task(async move {
+                    let document = match LibraryDocument::read_from(&path) {
+                        Ok(document) => document,
+                        Err(message) => {
+                            report_failure("Import failed", &message, message.clone());
+                            return;
+                        }
+                    };
+                    match library_repository.import_library(document, mode).await {
+                        Ok(summary) => {
+                            crate::dispatch_library_event(LibraryEvent::Imported { path, summary });
+                        }
+                        Err(e) => report_failure(
+                            "Failed to import library",
+                            &e,
+                            format!("Import failed, nothing was changed: {}", e),
+                        ),
+                    }
+                });
+            }
+            LibraryCommand::LoadShellHistory => {
+                crate::spawn_task(async move {
+                    let home = crate::domain::execution::launcher::home_dir();
+                    let mut entries = vec![];
+                    for (shell, path) in history_files(&home) {
+                        match read_history_file(shell, &path).await {
+                            Ok(file_entries) => {
+                                entries.extend(file_entries.into_iter().map(|entry| (shell, entry)))
+                            }
+                            Err(e) => crate::report_error(
+                                &format!("Failed to read {}", path.display()),
+                                &e,
+                            ),
+                        }
+                    }
+                    crate::dispatch_library_event(LibraryEvent::ShellHistoryLoaded {
+                        commands: dedupe_history(entries),
+                    });
+                });
+            }
+            LibraryCommand::ImportProject { directory } => {
+                let library_repository = self.library_repository.clone();
+                crate::spawn_task(async move {
+                    let tasks = match discover_project_tasks(&directory) {
+                        Ok(tasks) => tasks,
+                        Err(message) => {
+                            report_failure("Import failed", &message, message.clone());
+                            return;
+                        }
+                    };
+                    let directory = directory.canonicalize().unwrap_or(directory);
+                    let folder_name = directory
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_else(|| directory.display().to_string());
+                    match library_repository
+                        .import_project_tasks(
+                            folder_name,
+                            directory.to_string_lossy().to_string(),
+                            tasks,
+                        )
+                        .await
+                    {
+                        Ok((folder_id, summary)) => {
+                            crate::dispatch_library_event(LibraryEvent::ProjectImported {
+                                directory,
+                                folder_id,
+                                summary,
+                            });
+                        }
+                        Err(e) => report_failure(
+                            "Failed to import project tasks",
+                            &e,
+                            format!("Import failed, nothing was changed: {}", e),
+                        ),
+                    }
+                });
+            }
+            LibraryCommand::ExportFolderAsShellScripts {
+                folder_id,
+                directory,
+                with_menu,
+            } => {
+                let folder_repository = self.folder_repository.clone();
+                let script_repository = self.script_repository.clone();
+                crate::spawn_task(async move {
+                    let loaded = async {
+                        let folder_name = folder_repository
+                            .get_all_folders()
+                            .await?
+                            .into_iter()
+                            .find(|folder| folder.id == folder_id)
+                            .map(|folder| folder.name)
+                            .unwrap_or_default();
+                        let scripts = script_repository.get_scripts_by_folder(folder_id).await?;
+                        prisma_client_rust::Result::Ok((folder_name, scripts))
+                    };
+                    let (folder_name, scripts) = match loaded.await {
+                        Ok(loaded) => loaded,
+                        Err(e) => {
+                            report_failure(
+                                "Failed to load the folder for export",
+                                &e,
+                                format!("Export failed: {}", e),
+                            );
+                            return;
+                        }
+                    };
+                    let files = export_folder_files(&folder_name, &scripts, with_menu);
+                    match write_files(&directory, &files) {
+                        Ok(_) => {
+                            crate::dispatch_library_event(LibraryEvent::ShellScriptsExported {
+                                directory,
+                                file_count: files.len(),
+                            });
+                        }
+                        Err(message) => report_failure("Export failed", &message, message.clone()),
+                    }
+                });
+            }
+            LibraryCommand::ImportShellScripts { directory } => {
+                let library_repository = self.library_repository.clone();
+                crate::spawn_task(async move {
+                    let imported = match read_shell_script_files(&directory) {
+                        Ok(imported) if !imported.scripts.is_empty() => imported,
+                        Ok(_) => {
+                            crate::dispatch_library_event(LibraryEvent::OperationFailed {
+                                message: format!(
+                                    "No script files found in {}",
+                                    directory.display()
+                                ),
+                            });
+                            return;
+                        }
+                        Err(message) => {
+                            report_failure("Import failed", &message, message.clone());
+                            return;
+                        }
+                    };
+                    // Same path as a library import of a single folder, so that importing the
+                    // directory again updates the scripts instead of duplicating them
+                    let document = LibraryDocument::new(vec![LibraryFolder {
+                        name: imported.folder_name,
+                        ordering: 0,
+                        scripts: imported.scripts,
+                        folders: vec![],
+                    }]);
+                    match library_repository
+                        .import_library(document, ImportMode::Merge(ConflictPolicy::Overwrite))
+                        .await
+                    {
+                        Ok(summary) => {
+                            crate::dispatch_library_event(LibraryEvent::Imported {
+                                path: directory,
+                                summary,
+                            });
+                        }
+                        Err(e) => report_failure(
+                            "Failed to import shell scripts",
+                            &e,
+                            format!("Import failed, nothing was changed: {}", e),
+                        ),
+                    }
+                });
+            }
+        }
+    }
+}
+
+// Logged and shown as a toast like any other failure, the library window shows `status` as well
+fn report_failure<E: std::fmt::Debug>(message: &str, error: &E, status: String) {
+    crate::report_error(message, error);
+    crate::dispatch_library_event(LibraryEvent::OperationFailed { message: status });
+}
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+// Bumped whenever a change to the document would be misread by an older version of the app
+pub const LIBRARY_FORMAT_VERSION: u32 = 2;
+
+// Folders, their scripts and the links between them, written to a JSON or YAML file.
+// Fields are listed in a fixed order and sorted by ordering so that exports diff nicely in a repo.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryDocument {
+    pub version: u32,
+    pub folders: Vec<LibraryFolder>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryFolder {
+    pub name: String,
+    pub ordering: i32,
+    #[serde(default)]
+    pub scripts: Vec<LibraryScript>,
+    // Subfolders, new in version 2. An older app would have dropped them silently.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub folders: Vec<LibraryFolder>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryScript {
+    // Id of the script in the exporting database. A script linked into several folders is listed
+    // in each of them with the same id and is imported once.
+    pub id: i32,
+    pub name: String,
+    pub command: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(default)]
+    pub ordering: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_directory: Option<String>,
+    #[serde(default = "default_interpreter")]
+    pub interpreter: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shebang: Option<String>,
+    #[serde(default = "default_source_rc")]
+    pub source_rc: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env_vars: Vec<LibraryEnvVar>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryEnvVar {
+    pub key: String,
+    pub value: String,
+}
+
+fn default_interpreter() -> String {
+    crate::domain::execution::interpreter::Interpreter::LoginShell
+        .as_str()
+        .to_string()
+}
+
+fn default_source_rc() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LibraryFormat {
+    Json,
+    Yaml,
+}
+
+impl LibraryFormat {
+    // `.yaml` / `.yml` files are YAML, everything else is JSON
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("yaml") | Some("yml") => LibraryFormat::Yaml,
+            _ => LibraryFormat::Json,
+        }
+    }
+}
+
+impl LibraryDocument {
+    pub fn new(folders: Vec<LibraryFolder>) -> Self {
+        Self {
+            version: LIBRARY_FORMAT_VERSION,
+            folders,
+        }
+    }
+
+    // Folders at every level of the tree, parents before their subfolders
+    pub fn all_folders(&self) -> Vec<&LibraryFolder> {
+        let mut folders: Vec<&LibraryFolder> = self.folders.iter().collect();
+        let mut index = 0;
+        while index < folders.len() {
+            let folder = folders[index];
+            folders.extend(folder.folders.iter());
+            index += 1;
+        }
+        folders
+    }
+
+    pub fn script_count(&self) -> usize {
+        let mut ids: Vec<i32> = self
+            .all_folders()
+            .into_iter()
+            .flat_map(|folder| folder.scripts.iter().map(|script| script.id))
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids.len()
+    }
+
+    pub fn write_to(&self, path: &Path) -> Result<(), String> {
+        let content = match LibraryFormat::from_path(path) {
+            LibraryFormat::Json => serde_json::to_string_pretty(self).map_err(|e| e.to_string())?,
+            LibraryFormat::Yaml => serde_yaml::to_string(self).map_err(|e| e.to_string())?,
+        };
+        std::fs::write(path, content)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    pub fn read_from(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let document: Self = match LibraryFormat::from_path(path) {
+            LibraryFormat::Json => serde_json::from_str(&content).map_err(|e| e.to_string())?,
+            LibraryFormat::Yaml => serde_yaml::from_str(&content).map_err(|e| e.to_string())?,
+        };
+        if document.version > LIBRARY_FORMAT_VERSION {
+            return Err(format!(
+                "{} was exported by a newer version of the app (format version {}, supported up to {})",
+                path.display(),
+                document.version,
+                LIBRARY_FORMAT_VERSION
+            ));
+        }
+        Ok(document)
+    }
+}
+
+// What happens to a script of the document whose name is already used in the target folder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    Skip,
+    Overwrite,
+    KeepBoth,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    // Folders are matched by name, scripts by name within their folder
+    Merge(ConflictPolicy),
+    // Everything in the database is deleted first, run history included
+    Replace,
+}
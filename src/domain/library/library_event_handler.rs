@@ -0,0 +1,156 @@
+use crate::bus::Event;
+use crate::db::repository::folder_repository::FolderRepository;
+use crate::db::repository::library_repository::ImportSummary;
+use crate::domain::folder::folder_command_handler::FolderCommand;
+use crate::domain::library::shell_history::HistoryCommand;
+use crate::with_library_state_reducer;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub enum LibraryEvent {
+    Exported {
+        path: PathBuf,
+        folder_count: usize,
+        script_count: usize,
+    },
+    Imported {
+        path: PathBuf,
+        summary: ImportSummary,
+    },
+    OperationFailed {
+        message: String,
+    },
+    ShellHistoryLoaded {
+        commands: Vec<HistoryCommand>,
+    },
+    ShellScriptsExported {
+        directory: PathBuf,
+        file_count: usize,
+    },
+    ProjectImported {
+        directory: PathBuf,
+        folder_id: i32,
+        summary: ImportSummary,
+    },
+}
+
+impl Event for LibraryEvent {}
+
+pub struct LibraryEventHandler {
+    folder_repository: Arc<FolderRepository>,
+}
+
+impl LibraryEventHandler {
+    pub fn new() -> Self {
+        Self {
+            folder_repository: Arc::new(FolderRepository::new()),
+        }
+    }
+
+    pub fn handle(&self, event: LibraryEvent) {
+        match event {
+            LibraryEvent::Exported {
+                path,
+                folder_count,
+                script_count,
+            } => {
+                with_library_state_reducer(|r| {
+                    r.set_status_message(format!(
+                        "Exported {} folders and {} scripts to {}",
+                        folder_count,
+                        script_count,
+                        path.display()
+                    ))
+                });
+            }
+            LibraryEvent::Imported { path, summary } => {
+                with_library_state_reducer(|r| {
+                    r.set_status_message(format!(
+                        "Imported {}: {} folders created, {} scripts created, {} updated, {} skipped",
+                        path.display(),
+                        summary.folders_created,
+                        summary.scripts_created,
+                        summary.scripts_updated,
+                        summary.scripts_skipped
+                    ))
+                });
+                self.reload_folders(None);
+            }
+            LibraryEvent::OperationFailed { message } => {
+                with_library_state_reducer(|r| r.set_status_message(message));
+            }
+            LibraryEvent::ShellScriptsExported {
+                directory,
+                file_count,
+            } => {
+                with_library_state_reducer(|r| {
+                    r.set_status_message(format!(
+                        "Wrote {} files to {}",
+                        file_count,
+                        directory.display()
+                    ))
+                });
+            }
+            LibraryEvent::ProjectImported {
+                directory,
+                folder_id,
+                summary,
+            } => {
+                with_library_state_reducer(|r| {
+                    r.set_status_message(format!(
+                        "Imported the tasks of {}: {} created, {} updated, {} unchanged, {} moved to the trash",
+                        directory.display(),
+                        summary.scripts_created,
+                        summary.scripts_updated,
+                        summary.scripts_unchanged,
+                        summary.scripts_trashed
+                    ))
+                });
+                self.reload_folders(Some(folder_id));
+            }
+            LibraryEvent::ShellHistoryLoaded { commands } => {
+                if commands.is_empty() {
+                    with_library_state_reducer(|r| {
+                        r.set_status_message("No shell history found".to_string())
+                    });
+                } else {
+                    with_library_state_reducer(|r| r.open_history_import(commands));
+                }
+            }
+        }
+    }
+
+    // Selects `folder_to_show` if given. Otherwise keeps the selected folder when it survived the
+    // import and falls back to the first folder.
+    fn reload_folders(&self, folder_to_show: Option<i32>) {
+        let folder_repository = self.folder_repository.clone();
+        crate::spawn_task(async move {
+            let folders = match folder_repository.get_all_folders().await {
+                Ok(folders) => folders,
+                Err(e) => {
+                    crate::report_error("Failed to load folders", &e);
+                    return;
+                }
+            };
+            let selected_folder_id =
+                crate::with_folder_state(|state| *state.selected_folder_id.read().unwrap());
+            let folder_to_select = folder_to_show
+                .or(selected_folder_id)
+                .filter(|id| folders.iter().any(|folder| folder.id == *id))
+                .or_else(|| folders.first().map(|folder| folder.id));
+            crate::with_folder_state_reducer(|r| r.set_folder_list(folders));
+            match folder_to_select {
+                Some(folder_id) => {
+                    crate::dispatch_folder_command(FolderCommand::SelectFolder { folder_id })
+                }
+                None => crate::with_folder_state_reducer(|r| r.clear_selected_folder()),
+            }
+            // Imported scripts may carry tags
+            crate::dispatch_folder_command(FolderCommand::LoadTags);
+            // Replacing the library drops the pinned scripts and the trash with everything else
+            crate::dispatch_folder_command(FolderCommand::LoadPinnedScripts);
+            crate::dispatch_folder_command(FolderCommand::LoadTrash);
+        });
+    }
+}
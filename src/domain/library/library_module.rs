@@ -0,0 +1,18 @@
+use crate::bus::{Bus, DomainModule, Reply};
+use crate::domain::library::library_command_handler::{LibraryCommand, LibraryCommandHandler};
+use crate::domain::library::library_event_handler::{LibraryEvent, LibraryEventHandler};
+
+pub struct LibraryModule;
+
+impl DomainModule for LibraryModule {
+    fn register(self, bus: &mut Bus) {
+        let command_handler = LibraryCommandHandler::new();
+        // Imports report back through events, there is nothing to reply
+        bus.handle_commands(move |command: LibraryCommand, reply: Reply<()>| {
+            command_handler.handle(command);
+            reply.send(());
+        });
+        let event_handler = LibraryEventHandler::new();
+        bus.subscribe(move |event: LibraryEvent| event_handler.handle(event));
+    }
+}
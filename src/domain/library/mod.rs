@@ -0,0 +1,7 @@
+pub mod library_command_handler;
+pub mod library_document;
+pub mod library_event_handler;
+pub mod library_module;
+pub mod project_tasks;
+pub mod shell_files;
+pub mod shell_history;
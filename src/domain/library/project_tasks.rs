@@ -0,0 +1,297 @@
+use std::path::Path;
+
+const MAKEFILE_NAMES: [&str; 3] = ["GNUmakefile", "makefile", "Makefile"];
+const JUSTFILE_NAMES: [&str; 3] = ["justfile", "Justfile", ".justfile"];
+
+// A task of one of the task runners of a project. The name identifies the task on re-import,
+// e.g. `package.json: build`, while the command may change (npm -> pnpm after adding a lock file).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectTask {
+    pub name: String,
+    pub command: String,
+}
+
+// package.json scripts, Makefile targets and justfile recipes, in the order of their files
+pub fn discover_project_tasks(directory: &Path) -> Result<Vec<ProjectTask>, String> {
+    let mut tasks = vec![];
+
+    let package_json = directory.join("package.json");
+    if package_json.is_file() {
+        let content = read(&package_json)?;
+        let runner = package_manager(directory);
+        tasks.extend(
+            package_json_scripts(&content)
+                .map_err(|e| format!("Failed to parse {}: {}", package_json.display(), e))?
+                .into_iter()
+                .map(|script| ProjectTask {
+                    name: format!("package.json: {}", script),
+                    command: format!("{} run {}", runner, script),
+                }),
+        );
+    }
+
+    if let Some(makefile) = first_existing(directory, &MAKEFILE_NAMES) {
+        tasks.extend(
+            makefile_targets(&read(&makefile)?)
+                .into_iter()
+                .map(|target| ProjectTask {
+                    name: format!("make: {}", target),
+                    command: format!("make {}", target),
+                }),
+        );
+    }
+
+    if let Some(justfile) = first_existing(directory, &JUSTFILE_NAMES) {
+        tasks.extend(
+            justfile_recipes(&read(&justfile)?)
+                .into_iter()
+                .map(|recipe| ProjectTask {
+                    name: format!("just: {}", recipe),
+                    command: format!("just {}", recipe),
+                }),
+        );
+    }
+
+    if tasks.is_empty() {
+        return Err(format!(
+            "No package.json scripts, Makefile targets or justfile recipes found in {}",
+            directory.display()
+        ));
+    }
+    Ok(tasks)
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+fn first_existing(directory: &Path, names: &[&str]) -> Option<std::path::PathBuf> {
+    names
+        .iter()
+        .map(|name| directory.join(name))
+        .find(|path| path.is_file())
+}
+
+// Follows the lock file, npm when there is none
+fn package_manager(directory: &Path) -> &'static str {
+    [
+        ("pnpm-lock.yaml", "pnpm"),
+        ("yarn.lock", "yarn"),
+        ("bun.lockb", "bun"),
+        ("bun.lock", "bun"),
+    ]
+    .into_iter()
+    .find(|(lock_file, _)| directory.join(lock_file).is_file())
+    .map(|(_, runner)| runner)
+    .unwrap_or("npm")
+}
+
+fn package_json_scripts(content: &str) -> Result<Vec<String>, serde_json::Error> {
+    // `preserve_order` keeps the scripts in the order of the file
+    let package: serde_json::Value = serde_json::from_str(content)?;
+    Ok(package
+        .get("scripts")
+        .and_then(|scripts| scripts.as_object())
+        .map(|scripts| scripts.keys().cloned().collect())
+        .unwrap_or_default())
+}
+
+// Explicit targets only: no special targets (`.PHONY`), pattern rules (`%.o`) or variables
+fn makefile_targets(content: &str) -> Vec<String> {
+    let mut targets: Vec<String> = vec![];
+    for line in content.lines() {
+        // Recipe lines start with a tab
+        if line.starts_with('\t') || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let Some((names, rest)) = line.split_once(':') else {
+            continue;
+        };
+        // `VAR := value` and `VAR ::= value` are assignments, not rules
+        if rest.starts_with('=') || rest.starts_with(":=") || names.contains('=') {
+            continue;
+        }
+        for name in names.split_whitespace() {
+            let is_plain_target = !name.starts_with('.')
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_./".contains(c));
+            if is_plain_target && !targets.iter().any(|target| target == name) {
+                targets.push(name.to_string());
+            }
+        }
+    }
+    targets
+}
+
+// Public recipes only, `_private` ones and those marked `[private]` are helpers
+fn justfile_recipes(content: &str) -> Vec<String> {
+    const KEYWORDS: [&str; 6] = ["set", "alias", "export", "import", "mod", "if"];
+    let mut recipes = vec![];
+    let mut private_attribute = false;
+    for line in content.lines() {
+        if line.starts_with(' ') || line.starts_with('\t') || line.trim().is_empty() {
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            private_attribute |= line.contains("private");
+            continue;
+        }
+        let private = std::mem::take(&mut private_attribute);
+
+        let Some((header, rest)) = line.split_once(':') else {
+            continue;
+        };
+        // `name := value` is a variable
+        if rest.starts_with('=') {
+            continue;
+        }
+        let header = header.trim_start_matches('@');
+        let Some(name) = header.split_whitespace().next() else {
+            continue;
+        };
+        if KEYWORDS.contains(&name)
+            || name.starts_with('_')
+            || private
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            continue;
+        }
+        recipes.push(name.to_string());
+    }
+    recipes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A fresh directory per test, removed again when the test is done
+    struct ProjectDir(std::path::PathBuf);
+
+    impl ProjectDir {
+        fn new(name: &str, files: &[(&str, &str)]) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "ssm-project-tasks-{}-{}",
+                std::process::id(),
+                name
+            ));
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            for (file_name, content) in files {
+                std::fs::write(path.join(file_name), content).unwrap();
+            }
+            Self(path)
+        }
+    }
+
+    impl Drop for ProjectDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn lists_package_json_scripts_in_file_order() {
+        let scripts =
+            package_json_scripts(r#"{"scripts": {"test": "jest", "build": "tsc", "dev": "vite"}}"#)
+                .unwrap();
+        assert_eq!(scripts, vec!["test", "build", "dev"]);
+        assert!(
+            package_json_scripts(r#"{"name": "app"}"#)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(package_json_scripts("{").is_err());
+    }
+
+    #[test]
+    fn lists_explicit_makefile_targets() {
+        let makefile = "\
+CC := gcc
+VERSION ::= 1.0
+FLAGS = -O2
+.PHONY: build test
+# lint: not a target
+build: main.o
+\t$(CC) -o app main.o
+%.o: %.c
+\t$(CC) -c $<
+test build-docs: build
+install:
+\tcp app /usr/local/bin
+test:
+";
+        assert_eq!(
+            makefile_targets(makefile),
+            vec!["build", "test", "build-docs", "install"]
+        );
+    }
+
+    #[test]
+    fn lists_public_justfile_recipes() {
+        let justfile = "\
+set shell := [\"bash\", \"-c\"]
+version := \"1.0\"
+alias b := build
+
+# Builds the app
+build target=\"debug\":
+    cargo build
+@test: build
+    cargo test
+_helper:
+    echo hidden
+[private]
+setup:
+    echo hidden
+[linux]
+deploy env:
+    ./deploy.sh {{env}}
+";
+        assert_eq!(justfile_recipes(justfile), vec!["build", "test", "deploy"]);
+    }
+
+    #[test]
+    fn discovers_tasks_of_every_runner_with_the_locked_package_manager() {
+        let project = ProjectDir::new(
+            "all",
+            &[
+                ("package.json", r#"{"scripts": {"dev": "vite"}}"#),
+                ("pnpm-lock.yaml", ""),
+                ("Makefile", "build:\n\tcc main.c\n"),
+                ("justfile", "release:\n    ./release.sh\n"),
+            ],
+        );
+        let task = |name: &str, command: &str| ProjectTask {
+            name: name.to_string(),
+            command: command.to_string(),
+        };
+        assert_eq!(
+            discover_project_tasks(&project.0).unwrap(),
+            vec![
+                task("package.json: dev", "pnpm run dev"),
+                task("make: build", "make build"),
+                task("just: release", "just release"),
+            ]
+        );
+    }
+
+    #[test]
+    fn defaults_to_npm_without_a_lock_file() {
+        let project = ProjectDir::new("npm", &[("package.json", r#"{"scripts": {"x": "y"}}"#)]);
+        let tasks = discover_project_tasks(&project.0).unwrap();
+        assert_eq!(tasks[0].command, "npm run x");
+    }
+
+    #[test]
+    fn fails_without_any_tasks() {
+        let project = ProjectDir::new("empty", &[("README.md", "# nothing to run")]);
+        assert!(discover_project_tasks(&project.0).is_err());
+    }
+}
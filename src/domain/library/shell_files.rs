@@ -0,0 +1,738 @@
+use crate::domain::execution::interpreter::{Interpreter, InterpreterSettings};
+use crate::domain::library::library_document::{LibraryEnvVar, LibraryScript};
+use crate::prisma::shell_script;
+use std::path::Path;
+
+pub const MENU_FILE_NAME: &str = "menu.sh";
+const EXPORT_MARKER: &str = "Exported from Shell Script Manager";
+const MENU_MARKER: &str = "Menu generated by Shell Script Manager";
+
+// Header keys, read back by the import so that a folder survives the round trip
+const NAME_KEY: &str = "Name";
+const INTERPRETER_KEY: &str = "Interpreter";
+const SOURCE_RC_KEY: &str = "Source rc";
+const WORKING_DIRECTORY_KEY: &str = "Working directory";
+const ENV_KEY: &str = "Env";
+const FOLDER_KEY: &str = "Folder";
+
+#[derive(Debug, Clone)]
+pub struct ShellScriptFile {
+    pub file_name: String,
+    pub content: String,
+}
+
+// A directory of script files read back into a folder
+#[derive(Debug, Clone)]
+pub struct ImportedShellScripts {
+    // Taken from menu.sh when there is one, otherwise the name of the directory
+    pub folder_name: String,
+    pub scripts: Vec<LibraryScript>,
+}
+
+// One file per script in the order of the folder, plus `menu.sh` if requested
+pub fn export_folder_files(
+    folder_name: &str,
+    scripts: &[shell_script::Data],
+    with_menu: bool,
+) -> Vec<ShellScriptFile> {
+    let mut files: Vec<ShellScriptFile> = vec![];
+    for script in scripts {
+        let settings = InterpreterSettings::from(script);
+        let file_name = unique_file_name(&script.name, &settings, &files);
+        files.push(ShellScriptFile {
+            file_name,
+            content: render_script_file(script, &settings),
+        });
+    }
+    if with_menu {
+        let menu = render_menu(folder_name, scripts, &files);
+        files.push(ShellScriptFile {
+            file_name: MENU_FILE_NAME.to_string(),
+            content: menu,
+        });
+    }
+    files
+}
+
+pub fn write_files(directory: &Path, files: &[ShellScriptFile]) -> Result<(), String> {
+    std::fs::create_dir_all(directory)
+        .map_err(|e| format!("Failed to create {}: {}", directory.display(), e))?;
+    for file in files {
+        let path = directory.join(&file.file_name);
+        std::fs::write(&path, &file.content)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+                .map_err(|e| format!("Failed to make {} executable: {}", path.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+// `.sh` files and any other file starting with a shebang, menu.sh excluded
+pub fn read_shell_script_files(directory: &Path) -> Result<ImportedShellScripts, String> {
+    let entries = std::fs::read_dir(directory)
+        .map_err(|e| format!("Failed to read {}: {}", directory.display(), e))?;
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+
+    let mut folder_name = directory
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| directory.display().to_string());
+    // menu.sh lists the files in the order of the exported folder
+    let mut menu_order: Vec<String> = vec![];
+    let mut scripts: Vec<(String, LibraryScript)> = vec![];
+    for path in paths {
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if content
+            .lines()
+            .nth(1)
+            .is_some_and(|line| line.contains(MENU_MARKER))
+        {
+            if let Some(name) = header_value(&content, "#", FOLDER_KEY) {
+                folder_name = name;
+            }
+            menu_order = menu_files(&content);
+            continue;
+        }
+        let is_script = path.extension().is_some_and(|extension| extension == "sh")
+            || content.starts_with("#!");
+        if !is_script {
+            continue;
+        }
+        let file_stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        scripts.push((file_name, parse_script_file(&file_stem, &content)));
+    }
+
+    scripts.sort_by_key(|(file_name, _)| {
+        menu_order
+            .iter()
+            .position(|listed| listed == file_name)
+            .unwrap_or(usize::MAX)
+    });
+    let scripts = scripts
+        .into_iter()
+        .enumerate()
+        .map(|(index, (_, mut script))| {
+            script.id = index as i32;
+            script.ordering = index as i32;
+            script
+        })
+        .collect();
+    Ok(ImportedShellScripts {
+        folder_name,
+        scripts,
+    })
+}
+
+fn render_script_file(script: &shell_script::Data, settings: &InterpreterSettings) -> String {
+    let comment = comment_prefix(settings);
+    let env_vars = env_vars_of(script);
+    let mut lines = vec![export_shebang(settings)];
+    lines.push(format!(
+        "{} {}: {}",
+        comment,
+        NAME_KEY,
+        escape_header_value(&script.name)
+    ));
+    lines.push(format!(
+        "{} {}: {}",
+        comment,
+        INTERPRETER_KEY,
+        settings.interpreter.as_str()
+    ));
+    if !settings.source_rc {
+        lines.push(format!("{} {}: false", comment, SOURCE_RC_KEY));
+    }
+    if let Some(working_directory) = &script.working_directory {
+        lines.push(format!(
+            "{} {}: {}",
+            comment,
+            WORKING_DIRECTORY_KEY,
+            escape_header_value(working_directory)
+        ));
+    }
+    for (key, value) in env_vars.iter() {
+        lines.push(format!(
+            "{} {}: {}={}",
+            comment,
+            ENV_KEY,
+            key,
+            escape_header_value(value)
+        ));
+    }
+    lines.push(format!("{} {}", comment, EXPORT_MARKER));
+    lines.push(String::new());
+
+    let prelude = prelude_lines(settings, script.working_directory.as_deref(), &env_vars);
+    if !prelude.is_empty() {
+        lines.extend(prelude);
+        lines.push(String::new());
+    }
+    lines.push(script.command.trim_end().to_string());
+    lines.join("\n") + "\n"
+}
+
+fn render_menu(
+    folder_name: &str,
+    scripts: &[shell_script::Data],
+    files: &[ShellScriptFile],
+) -> String {
+    let names: Vec<String> = scripts
+        .iter()
+        .map(|script| format!("  {}", shell_quote(&script.name)))
+        .collect();
+    let file_names: Vec<String> = files
+        .iter()
+        .map(|file| format!("  {}", shell_quote(&file.file_name)))
+        .collect();
+    format!(
+        r#"#!/usr/bin/env bash
+# {marker}
+# {folder_key}: {folder_name}
+cd "$(dirname "$0")" || exit 1
+
+names=(
+{names}
+)
+files=(
+{files}
+)
+
+PS3='Run which script? '
+select name in "${{names[@]}}"; do
+  if [ -n "$name" ]; then
+    ./"${{files[$((REPLY - 1))]}}"
+    exit $?
+  fi
+  echo "Invalid choice: $REPLY"
+done
+"#,
+        marker = MENU_MARKER,
+        folder_key = FOLDER_KEY,
+        folder_name = escape_header_value(folder_name),
+        names = names.join("\n"),
+        files = file_names.join("\n"),
+    )
+}
+
+// Entries of the `files=( ... )` array of menu.sh
+fn menu_files(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .skip_while(|line| line.trim() != "files=(")
+        .skip(1)
+        .take_while(|line| line.trim() != ")")
+        .map(|line| shell_unquote(line.trim()))
+        .collect()
+}
+
+fn parse_script_file(file_stem: &str, content: &str) -> LibraryScript {
+    let mut lines = content.lines().peekable();
+    let shebang = lines
+        .next_if(|line| line.starts_with("#!"))
+        .map(|line| line.to_string());
+
+    let mut name = None;
+    let mut interpreter = None;
+    let mut source_rc = true;
+    let mut working_directory = None;
+    let mut env_vars = vec![];
+    let mut exported = false;
+    // The header ends at the first line that is not one of our comments
+    while let Some(&line) = lines.peek() {
+        let Some(comment) = line.strip_prefix("# ").or_else(|| line.strip_prefix("// ")) else {
+            break;
+        };
+        if comment == EXPORT_MARKER {
+            exported = true;
+        } else if let Some(value) = key_value(comment, NAME_KEY) {
+            name = Some(unescape_header_value(value));
+        } else if let Some(value) = key_value(comment, INTERPRETER_KEY) {
+            interpreter = Some(Interpreter::from_db_value(value));
+        } else if let Some(value) = key_value(comment, SOURCE_RC_KEY) {
+            source_rc = value != "false";
+        } else if let Some(value) = key_value(comment, WORKING_DIRECTORY_KEY) {
+            working_directory = Some(unescape_header_value(value));
+        } else if let Some(value) = key_value(comment, ENV_KEY) {
+            if let Some((key, value)) = value.split_once('=')
+                && is_env_key(key)
+            {
+                env_vars.push((key.to_string(), unescape_header_value(value)));
+            }
+        } else {
+            break;
+        }
+        lines.next();
+    }
+    if exported {
+        lines.next_if(|line| line.is_empty());
+    }
+
+    let interpreter = interpreter.unwrap_or_else(|| interpreter_of_shebang(shebang.as_deref()));
+    let settings = InterpreterSettings {
+        interpreter,
+        shebang: (interpreter == Interpreter::Custom)
+            .then(|| shebang.clone())
+            .flatten(),
+        source_rc,
+    };
+
+    // The `cd` / `export` lines written by the export are already covered by the header
+    let mut body: Vec<&str> = lines.collect();
+    // Quoted values with line breaks span several lines of the file
+    let prelude = prelude_lines(&settings, working_directory.as_deref(), &env_vars).join("\n");
+    let prelude: Vec<&str> = prelude.lines().collect();
+    let starts_with_prelude = !prelude.is_empty()
+        && body.len() >= prelude.len()
+        && body
+            .iter()
+            .zip(prelude.iter())
+            .all(|(line, expected)| line == expected);
+    if exported && starts_with_prelude {
+        body.drain(..prelude.len());
+        if body.first().is_some_and(|line| line.is_empty()) {
+            body.remove(0);
+        }
+    }
+
+    LibraryScript {
+        id: 0,
+        name: name.unwrap_or_else(|| file_stem.to_string()),
+        command: body.join("\n").trim_end().to_string(),
+        description: String::new(),
+        ordering: 0,
+        working_directory,
+        interpreter: settings.interpreter.as_str().to_string(),
+        shebang: settings.shebang,
+        source_rc: settings.source_rc,
+        env_vars: env_vars
+            .into_iter()
+            .map(|(key, value)| LibraryEnvVar { key, value })
+            .collect(),
+        tags: vec![],
+    }
+}
+
+fn key_value<'a>(comment: &'a str, key: &str) -> Option<&'a str> {
+    comment
+        .strip_prefix(key)
+        .and_then(|rest| rest.strip_prefix(": "))
+}
+
+fn header_value(content: &str, comment: &str, key: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        line.strip_prefix(comment)
+            .map(str::trim_start)
+            .and_then(|rest| key_value(rest, key))
+            .map(unescape_header_value)
+    })
+}
+
+// Header values are single comment lines, so line breaks (and the backslash) are escaped
+fn escape_header_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str(r"\\"),
+            '\n' => escaped.push_str(r"\n"),
+            '\r' => escaped.push_str(r"\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape_header_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+// Anything else cannot be exported by a shell, and could inject code into `export` lines
+fn is_env_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn env_vars_of(script: &shell_script::Data) -> Vec<(String, String)> {
+    script
+        .shell_script_env_var()
+        .map(|env_vars| {
+            env_vars
+                .iter()
+                .filter(|env_var| is_env_key(&env_var.key))
+                .map(|env_var| (env_var.key.clone(), env_var.value.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// The login shell is unknown on the receiving machine, bash is the safest bet
+fn export_shebang(settings: &InterpreterSettings) -> String {
+    let shebang = match settings.interpreter {
+        Interpreter::LoginShell | Interpreter::Bash => "#!/usr/bin/env bash",
+        Interpreter::Zsh => "#!/usr/bin/env zsh",
+        Interpreter::Sh => "#!/bin/sh",
+        Interpreter::Fish => "#!/usr/bin/env fish",
+        Interpreter::Python3 => "#!/usr/bin/env python3",
+        Interpreter::Node => "#!/usr/bin/env node",
+        Interpreter::Custom => {
+            return match settings.shebang.as_deref().map(str::trim) {
+                Some(shebang) if shebang.starts_with("#!") => shebang.to_string(),
+                Some(shebang) if !shebang.is_empty() => format!("#!{}", shebang),
+                _ => "#!/bin/sh".to_string(),
+            };
+        }
+    };
+    shebang.to_string()
+}
+
+fn interpreter_of_shebang(shebang: Option<&str>) -> Interpreter {
+    let Some(shebang) = shebang else {
+        return Interpreter::LoginShell;
+    };
+    let mut parts = shebang.trim_start_matches("#!").split_whitespace();
+    let mut program = parts.next().unwrap_or_default().rsplit('/').next();
+    if program == Some("env") {
+        program = parts.find(|part| !part.starts_with('-'));
+    }
+    match program.unwrap_or_default() {
+        "bash" => Interpreter::Bash,
+        "zsh" => Interpreter::Zsh,
+        "sh" => Interpreter::Sh,
+        "fish" => Interpreter::Fish,
+        "python3" | "python" => Interpreter::Python3,
+        "node" => Interpreter::Node,
+        _ => Interpreter::Custom,
+    }
+}
+
+fn comment_prefix(settings: &InterpreterSettings) -> &'static str {
+    if settings.code_lang() == "js" {
+        "//"
+    } else {
+        "#"
+    }
+}
+
+// `cd` and `export` lines that give the file the working directory and environment of the
+// script. Only for POSIX-like shells, the other interpreters keep them in the header only.
+fn prelude_lines(
+    settings: &InterpreterSettings,
+    working_directory: Option<&str>,
+    env_vars: &[(String, String)],
+) -> Vec<String> {
+    if !settings.interpreter.is_shell() || settings.interpreter == Interpreter::Fish {
+        return vec![];
+    }
+    let mut lines = vec![];
+    if let Some(working_directory) = working_directory.filter(|dir| !dir.trim().is_empty()) {
+        let target = match working_directory.strip_prefix("~/") {
+            Some(rest) => format!("~/{}", shell_quote(rest)),
+            None if working_directory == "~" => "~".to_string(),
+            None => shell_quote(working_directory),
+        };
+        lines.push(format!("cd {} || exit 1", target));
+    }
+    for (key, value) in env_vars {
+        lines.push(format!("export {}={}", key, shell_quote(value)));
+    }
+    lines
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn shell_unquote(value: &str) -> String {
+    value
+        .strip_prefix('\'')
+        .and_then(|value| value.strip_suffix('\''))
+        .map(|value| value.replace(r"'\''", "'"))
+        .unwrap_or_else(|| value.to_string())
+}
+
+// `Deploy staging!` -> `deploy-staging.sh`, numbered when two scripts end up with the same name
+fn unique_file_name(
+    name: &str,
+    settings: &InterpreterSettings,
+    files: &[ShellScriptFile],
+) -> String {
+    let mut stem = String::new();
+    for c in name.to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            stem.push(c);
+        } else if !stem.ends_with('-') {
+            stem.push('-');
+        }
+    }
+    let stem = match stem.trim_matches('-') {
+        "" | "menu" => "script".to_string(),
+        stem => stem.to_string(),
+    };
+    let extension = match settings.code_lang().as_str() {
+        "py" => "py",
+        "js" => "js",
+        "rb" => "rb",
+        _ => "sh",
+    };
+    (1..)
+        .map(|n| match n {
+            1 => format!("{}.{}", stem, extension),
+            n => format!("{}-{}.{}", stem, n, extension),
+        })
+        .find(|candidate| !files.iter().any(|file| file.file_name == *candidate))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prisma::shell_script_env_var;
+
+    fn script(name: &str, command: &str, interpreter: Interpreter) -> shell_script::Data {
+        shell_script::Data {
+            id: 1,
+            name: name.to_string(),
+            command: command.to_string(),
+            description: String::new(),
+            ordering: 0,
+            working_directory: None,
+            interpreter: interpreter.as_str().to_string(),
+            shebang: None,
+            source_rc: true,
+            pinned: false,
+            pinned_ordering: 0,
+            deleted_at: None,
+            created_at: 0.0,
+            created_at_hk: String::new(),
+            rel_scriptsfolder_shellscript: None,
+            script_run: None,
+            script_placeholder_value: None,
+            shell_script_env_var: Some(vec![]),
+            rel_shellscript_tag: None,
+            shell_script_revision: None,
+        }
+    }
+
+    fn with_env_vars(
+        mut script: shell_script::Data,
+        env_vars: &[(&str, &str)],
+    ) -> shell_script::Data {
+        script.shell_script_env_var = Some(
+            env_vars
+                .iter()
+                .enumerate()
+                .map(|(index, (key, value))| shell_script_env_var::Data {
+                    id: index as i32,
+                    shell_script_id: script.id,
+                    key: key.to_string(),
+                    value: value.to_string(),
+                    ordering: index as i32,
+                    created_at: 0.0,
+                    created_at_hk: String::new(),
+                    shell_script: None,
+                })
+                .collect(),
+        );
+        script
+    }
+
+    fn env_vars(script: &LibraryScript) -> Vec<(&str, &str)> {
+        script
+            .env_vars
+            .iter()
+            .map(|env_var| (env_var.key.as_str(), env_var.value.as_str()))
+            .collect()
+    }
+
+    fn round_trip(script: &shell_script::Data) -> LibraryScript {
+        let files = export_folder_files("Folder", std::slice::from_ref(script), false);
+        let file_stem = files[0].file_name.split('.').next().unwrap().to_string();
+        parse_script_file(&file_stem, &files[0].content)
+    }
+
+    #[test]
+    fn shell_script_survives_the_round_trip() {
+        let mut exported = with_env_vars(
+            script(
+                "Deploy staging",
+                "./deploy.sh \"$TARGET\"\necho done\n",
+                Interpreter::Bash,
+            ),
+            &[("TARGET", "it's staging"), ("LINES", "one\ntwo")],
+        );
+        exported.working_directory = Some("~/projects/my app".to_string());
+        exported.source_rc = false;
+
+        let imported = round_trip(&exported);
+        assert_eq!(imported.name, "Deploy staging");
+        assert_eq!(imported.command, "./deploy.sh \"$TARGET\"\necho done");
+        assert_eq!(imported.interpreter, "bash");
+        assert!(!imported.source_rc);
+        assert_eq!(
+            imported.working_directory.as_deref(),
+            Some("~/projects/my app")
+        );
+        assert_eq!(
+            env_vars(&imported),
+            vec![("TARGET", "it's staging"), ("LINES", "one\ntwo")]
+        );
+    }
+
+    #[test]
+    fn writes_the_prelude_into_shell_files_only() {
+        let mut shell = with_env_vars(script("Build", "make", Interpreter::Sh), &[("CC", "gcc")]);
+        shell.working_directory = Some("/src".to_string());
+        let content = &export_folder_files("Folder", &[shell], false)[0].content;
+        assert!(content.starts_with("#!/bin/sh\n"));
+        assert!(content.contains("\ncd '/src' || exit 1\nexport CC='gcc'\n\nmake\n"));
+
+        let node = with_env_vars(
+            script("Serve", "console.log(1)", Interpreter::Node),
+            &[("PORT", "80")],
+        );
+        let content = &export_folder_files("Folder", &[node.clone()], false)[0].content;
+        assert!(content.contains("// Env: PORT=80\n"));
+        assert!(!content.contains("export"));
+        let imported = round_trip(&node);
+        assert_eq!(imported.command, "console.log(1)");
+        assert_eq!(env_vars(&imported), vec![("PORT", "80")]);
+    }
+
+    #[test]
+    fn line_breaks_in_header_values_cannot_add_lines() {
+        let mut exported = script("Name\nrm -rf ~", "true", Interpreter::Bash);
+        exported.working_directory = Some("C:\\dir\r\nrm -rf ~".to_string());
+        let content =
+            &export_folder_files("Folder", std::slice::from_ref(&exported), false)[0].content;
+        // The quoted `cd` line below the header may span lines, the header must not
+        let header: Vec<&str> = content
+            .lines()
+            .take_while(|line| !line.contains(EXPORT_MARKER))
+            .collect();
+        assert!(header.iter().all(|line| line.starts_with('#')));
+
+        let imported = round_trip(&exported);
+        assert_eq!(imported.name, "Name\nrm -rf ~");
+        assert_eq!(
+            imported.working_directory.as_deref(),
+            Some("C:\\dir\r\nrm -rf ~")
+        );
+        assert_eq!(imported.command, "true");
+    }
+
+    #[test]
+    fn skips_env_keys_a_shell_cannot_export() {
+        let exported = with_env_vars(
+            script("Env", "env", Interpreter::Bash),
+            &[
+                ("_OK1", "a"),
+                ("1ST", "b"),
+                ("A=B", "c"),
+                ("X;rm -rf ~;Y", "d"),
+                ("", "e"),
+            ],
+        );
+        let content =
+            &export_folder_files("Folder", std::slice::from_ref(&exported), false)[0].content;
+        assert!(!content.contains("rm -rf"));
+        assert_eq!(env_vars(&round_trip(&exported)), vec![("_OK1", "a")]);
+
+        let imported = parse_script_file("f", "#!/bin/sh\n# Env: BAD KEY=1\n# Env: GOOD=2\necho");
+        assert_eq!(env_vars(&imported), vec![("GOOD", "2")]);
+    }
+
+    #[test]
+    fn reads_plain_files_with_the_interpreter_of_their_shebang() {
+        let imported = parse_script_file("backup", "#!/usr/bin/env -S python3\nprint(1)\n");
+        assert_eq!(imported.name, "backup");
+        assert_eq!(imported.interpreter, "python3");
+        assert_eq!(imported.command, "print(1)");
+
+        let imported = parse_script_file("tool", "#!/opt/bin/tool --flag\nrun\n");
+        assert_eq!(imported.interpreter, "custom");
+        assert_eq!(imported.shebang.as_deref(), Some("#!/opt/bin/tool --flag"));
+
+        let imported = parse_script_file("plain", "# just a comment\nls\n");
+        assert_eq!(imported.interpreter, "login_shell");
+        assert_eq!(imported.command, "# just a comment\nls");
+    }
+
+    #[test]
+    fn numbers_file_names_that_collide() {
+        let scripts = [
+            script("Deploy staging!", "a", Interpreter::Bash),
+            script("deploy  staging", "b", Interpreter::Bash),
+            script("menu", "c", Interpreter::Bash),
+            script("Report", "d", Interpreter::Python3),
+        ];
+        let files = export_folder_files("Folder", &scripts, true);
+        let file_names: Vec<&str> = files.iter().map(|file| file.file_name.as_str()).collect();
+        assert_eq!(
+            file_names,
+            vec![
+                "deploy-staging.sh",
+                "deploy-staging-2.sh",
+                "script.sh",
+                "report.py",
+                MENU_FILE_NAME,
+            ]
+        );
+    }
+
+    #[test]
+    fn directory_round_trip_keeps_the_folder_name_and_order() {
+        let directory =
+            std::env::temp_dir().join(format!("ssm-shell-files-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        let scripts = [
+            script("Zeta", "echo z", Interpreter::Bash),
+            script("Alpha", "echo a", Interpreter::Bash),
+        ];
+        let files = export_folder_files("Ops\nTools", &scripts, true);
+        write_files(&directory, &files).unwrap();
+        let imported = read_shell_script_files(&directory);
+        let _ = std::fs::remove_dir_all(&directory);
+
+        let imported = imported.unwrap();
+        assert_eq!(imported.folder_name, "Ops\nTools");
+        let names: Vec<(&str, i32)> = imported
+            .scripts
+            .iter()
+            .map(|script| (script.name.as_str(), script.ordering))
+            .collect();
+        assert_eq!(names, vec![("Zeta", 0), ("Alpha", 1)]);
+    }
+}
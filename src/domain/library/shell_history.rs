@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const META: u8 = 0x83;
+const MAX_NAME_CHARS: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryShell {
+    Zsh,
+    Bash,
+}
+
+impl HistoryShell {
+    pub fn label(&self) -> &'static str {
+        match self {
+            HistoryShell::Zsh => "zsh",
+            HistoryShell::Bash => "bash",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub command: String,
+    // Seconds since the epoch, only written by zsh's EXTENDED_HISTORY and bash's HISTTIMEFORMAT
+    pub timestamp: Option<i64>,
+}
+
+// One row of the import checklist: a distinct command and how it was used
+#[derive(Debug, Clone)]
+pub struct HistoryCommand {
+    pub command: String,
+    pub shell: HistoryShell,
+    pub use_count: usize,
+    pub last_used: Option<i64>,
+}
+
+// `~/.zsh_history` and `~/.bash_history`, or wherever $HISTFILE points for the running shell
+pub fn history_files(home: &str) -> Vec<(HistoryShell, PathBuf)> {
+    let mut files = vec![
+        (HistoryShell::Zsh, Path::new(home).join(".zsh_history")),
+        (HistoryShell::Bash, Path::new(home).join(".bash_history")),
+    ];
+    if let Ok(histfile) = std::env::var("HISTFILE") {
+        let histfile = PathBuf::from(histfile);
+        let shell = if histfile.to_string_lossy().contains("zsh") {
+            HistoryShell::Zsh
+        } else {
+            HistoryShell::Bash
+        };
+        if !files.iter().any(|(_, path)| *path == histfile) {
+            files.push((shell, histfile));
+        }
+    }
+    files.retain(|(_, path)| path.is_file());
+    files
+}
+
+pub async fn read_history_file(
+    shell: HistoryShell,
+    path: &Path,
+) -> std::io::Result<Vec<HistoryEntry>> {
+    let bytes = tokio::fs::read(path).await?;
+    Ok(match shell {
+        HistoryShell::Zsh => parse_zsh_history(&bytes),
+        HistoryShell::Bash => parse_bash_history(&String::from_utf8_lossy(&bytes)),
+    })
+}
+
+// zsh writes `: <start>:<elapsed>;<command>` with EXTENDED_HISTORY and the bare command otherwise.
+// Newlines inside a command are written as a backslash at the end of the line.
+pub fn parse_zsh_history(bytes: &[u8]) -> Vec<HistoryEntry> {
+    let content = String::from_utf8_lossy(&unmetafy(bytes)).into_owned();
+    let mut entries = vec![];
+    let mut lines = content.lines();
+    while let Some(first_line) = lines.next() {
+        let mut line = first_line.to_string();
+        let mut command_lines = vec![];
+        while line.ends_with('\\') {
+            line.pop();
+            command_lines.push(line);
+            line = match lines.next() {
+                Some(next_line) => next_line.to_string(),
+                None => String::new(),
+            };
+        }
+        command_lines.push(line);
+        let raw = command_lines.join("\n");
+
+        let (timestamp, command) = match parse_extended_header(&raw) {
+            Some((timestamp, command)) => (Some(timestamp), command.to_string()),
+            None => (None, raw),
+        };
+        if !command.trim().is_empty() {
+            entries.push(HistoryEntry { command, timestamp });
+        }
+    }
+    entries
+}
+
+// `: 1700000000:0;git status` -> (1700000000, "git status")
+fn parse_extended_header(raw: &str) -> Option<(i64, &str)> {
+    let rest = raw.strip_prefix(": ")?;
+    let (header, command) = rest.split_once(';')?;
+    let (start, elapsed) = header.split_once(':')?;
+    if !elapsed.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some((start.trim().parse().ok()?, command))
+}
+
+// zsh escapes bytes that clash with its internal tokens as META followed by the byte xor 32
+fn unmetafy(bytes: &[u8]) -> Vec<u8> {
+    let mut unmetafied = Vec::with_capacity(bytes.len());
+    let mut iter = bytes.iter();
+    while let Some(&byte) = iter.next() {
+        if byte == META {
+            if let Some(&next) = iter.next() {
+                unmetafied.push(next ^ 32);
+            }
+        } else {
+            unmetafied.push(byte);
+        }
+    }
+    unmetafied
+}
+
+// bash writes one command per line. With HISTTIMEFORMAT set every command is preceded by a
+// `#<seconds>` line, and the lines up to the next such line belong to one (multi-line) command.
+pub fn parse_bash_history(content: &str) -> Vec<HistoryEntry> {
+    let is_timestamp = |line: &str| {
+        line.strip_prefix('#')
+            .is_some_and(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+    };
+    let has_timestamps = content.lines().any(is_timestamp);
+
+    let mut entries = vec![];
+    if !has_timestamps {
+        for line in content.lines() {
+            if !line.trim().is_empty() {
+                entries.push(HistoryEntry {
+                    command: line.to_string(),
+                    timestamp: None,
+                });
+            }
+        }
+        return entries;
+    }
+
+    let mut timestamp = None;
+    let mut command_lines: Vec<&str> = vec![];
+    let mut flush = |timestamp: Option<i64>, command_lines: &mut Vec<&str>| {
+        let command = command_lines.join("\n");
+        if !command.trim().is_empty() {
+            entries.push(HistoryEntry { command, timestamp });
+        }
+        command_lines.clear();
+    };
+    for line in content.lines() {
+        if is_timestamp(line) {
+            flush(timestamp, &mut command_lines);
+            timestamp = line[1..].parse().ok();
+        } else {
+            command_lines.push(line);
+        }
+    }
+    flush(timestamp, &mut command_lines);
+    entries
+}
+
+// Identical commands (ignoring surrounding whitespace) are merged, most recently used first
+pub fn dedupe_history(entries: Vec<(HistoryShell, HistoryEntry)>) -> Vec<HistoryCommand> {
+    // Position in the file stands in for the time when there are no timestamps
+    let mut commands: HashMap<String, (HistoryCommand, usize)> = HashMap::new();
+    for (position, (shell, entry)) in entries.into_iter().enumerate() {
+        let command = entry.command.trim().to_string();
+        let (history_command, last_position) =
+            commands.entry(command.clone()).or_insert_with(|| {
+                (
+                    HistoryCommand {
+                        command,
+                        shell,
+                        use_count: 0,
+                        last_used: None,
+                    },
+                    position,
+                )
+            });
+        history_command.use_count += 1;
+        history_command.last_used = history_command.last_used.max(entry.timestamp);
+        *last_position = position;
+    }
+
+    let mut commands: Vec<(HistoryCommand, usize)> = commands.into_values().collect();
+    commands.sort_by(|(a, a_position), (b, b_position)| {
+        b.last_used
+            .cmp(&a.last_used)
+            .then(b_position.cmp(a_position))
+    });
+    commands.into_iter().map(|(command, _)| command).collect()
+}
+
+// First line of the command, shortened to something that fits on a script card
+pub fn script_name_from_command(command: &str) -> String {
+    let first_line = command
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    let words: Vec<&str> = first_line.split_whitespace().collect();
+    let name = words.join(" ");
+    if name.chars().count() <= MAX_NAME_CHARS {
+        name
+    } else {
+        let shortened: String = name.chars().take(MAX_NAME_CHARS - 1).collect();
+        format!("{}…", shortened.trim_end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(command: &str, timestamp: Option<i64>) -> HistoryEntry {
+        HistoryEntry {
+            command: command.to_string(),
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn parses_plain_and_extended_zsh_history() {
+        let history = b": 1700000000:0;git status\nls -la\n: 1700000005:12;cargo build\n";
+        assert_eq!(
+            parse_zsh_history(history),
+            vec![
+                entry("git status", Some(1700000000)),
+                entry("ls -la", None),
+                entry("cargo build", Some(1700000005)),
+            ]
+        );
+    }
+
+    #[test]
+    fn joins_multi_line_zsh_commands() {
+        let history = b": 1700000000:0;for f in *; do\\\n  echo $f\\\ndone\n: 1700000001:0;pwd\n";
+        assert_eq!(
+            parse_zsh_history(history),
+            vec![
+                entry("for f in *; do\n  echo $f\ndone", Some(1700000000)),
+                entry("pwd", Some(1700000001)),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_a_semicolon_command_without_extended_header() {
+        assert_eq!(
+            parse_zsh_history(b"echo a; echo b\n"),
+            vec![entry("echo a; echo b", None)]
+        );
+    }
+
+    #[test]
+    fn unmetafies_zsh_bytes() {
+        // `é` is 0xC3 0xA9, zsh writes 0xA9 as META followed by 0xA9 ^ 32
+        let history = [b"echo caf".as_slice(), &[0xC3, META, 0xA9 ^ 32], b"\n"].concat();
+        assert_eq!(parse_zsh_history(&history), vec![entry("echo café", None)]);
+    }
+
+    #[test]
+    fn skips_blank_entries() {
+        assert_eq!(
+            parse_zsh_history(b"\n: 1700000000:0;\nls\n"),
+            vec![entry("ls", None)]
+        );
+        assert_eq!(parse_bash_history("ls\n   \npwd\n").len(), 2);
+    }
+
+    #[test]
+    fn parses_bash_history_without_timestamps() {
+        assert_eq!(
+            parse_bash_history("git pull\nmake test\n"),
+            vec![entry("git pull", None), entry("make test", None)]
+        );
+    }
+
+    #[test]
+    fn groups_bash_lines_between_timestamps() {
+        let history = "#1700000000\ngit pull\n#1700000010\nfor f in *; do\n  echo $f\ndone\n";
+        assert_eq!(
+            parse_bash_history(history),
+            vec![
+                entry("git pull", Some(1700000000)),
+                entry("for f in *; do\n  echo $f\ndone", Some(1700000010)),
+            ]
+        );
+    }
+
+    #[test]
+    fn bash_comments_are_not_timestamps() {
+        let history = "#1700000000\n# just a comment\n";
+        assert_eq!(
+            parse_bash_history(history),
+            vec![entry("# just a comment", Some(1700000000))]
+        );
+    }
+
+    #[test]
+    fn merges_identical_commands_most_recent_first() {
+        let commands = dedupe_history(vec![
+            (HistoryShell::Zsh, entry("ls", Some(10))),
+            (HistoryShell::Zsh, entry("git status", Some(20))),
+            (HistoryShell::Bash, entry("  ls  ", Some(30))),
+        ]);
+        let summary: Vec<(&str, usize, Option<i64>)> = commands
+            .iter()
+            .map(|c| (c.command.as_str(), c.use_count, c.last_used))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("ls", 2, Some(30)), ("git status", 1, Some(20))]
+        );
+    }
+
+    #[test]
+    fn orders_by_position_without_timestamps() {
+        let commands = dedupe_history(vec![
+            (HistoryShell::Bash, entry("a", None)),
+            (HistoryShell::Bash, entry("b", None)),
+            (HistoryShell::Bash, entry("a", None)),
+        ]);
+        let order: Vec<&str> = commands.iter().map(|c| c.command.as_str()).collect();
+        assert_eq!(order, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn names_scripts_after_the_first_line() {
+        assert_eq!(
+            script_name_from_command("\n  docker   compose up -d\nmore"),
+            "docker compose up -d"
+        );
+        let long_name = script_name_from_command(&"x".repeat(100));
+        assert_eq!(long_name.chars().count(), MAX_NAME_CHARS);
+        assert!(long_name.ends_with('…'));
+    }
+}
@@ -1 +1,5 @@
+pub mod execution;
 pub mod folder;
+pub mod library;
+pub mod notification;
+pub mod palette;
@@ -0,0 +1,2 @@
+pub mod notification_event_handler;
+pub mod notification_module;
@@ -0,0 +1,90 @@
+use crate::bus::Event;
+use crate::domain::execution::execution_event_handler::ExecutionEvent;
+use crate::state::execution_state::ScriptRunOutput;
+use crate::state::notification_state::Notification;
+use crate::{with_execution_state, with_notification_state_reducer};
+
+// Output lines shown in the toast of a failed run, the console has the rest
+const FAILED_RUN_OUTPUT_LINES: usize = 20;
+
+#[derive(Debug, Clone)]
+pub enum NotificationEvent {
+    Raised { notification: Notification },
+}
+
+impl Event for NotificationEvent {}
+
+pub struct NotificationEventHandler {}
+
+impl NotificationEventHandler {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn handle(&self, event: NotificationEvent) {
+        match event {
+            NotificationEvent::Raised { notification } => {
+                with_notification_state_reducer(|r| r.show(notification));
+            }
+        }
+    }
+
+    // Non-zero exit codes and scripts killed by a signal show up as toasts, runs stopped from
+    // the console do not. Launch failures are reported where the spawn fails.
+    pub fn handle_execution_event(&self, event: ExecutionEvent) {
+        let notification = match event {
+            ExecutionEvent::RunFinished {
+                run_id,
+                signal: Some(signal),
+                ..
+            } => with_run(run_id, |run| {
+                if run.stop_requested {
+                    return None;
+                }
+                Some(Notification::error(
+                    format!("'{}' was killed by signal {}", run.script_name, signal),
+                    format!("$ {}\n{}", run.command, output_tail(run)),
+                ))
+            }),
+            ExecutionEvent::RunFinished {
+                run_id,
+                exit_code: Some(exit_code),
+                ..
+            } if exit_code != 0 => with_run(run_id, |run| {
+                if run.stop_requested {
+                    return None;
+                }
+                Some(Notification::warning(
+                    format!("'{}' exited with code {}", run.script_name, exit_code),
+                    format!("$ {}\n{}", run.command, output_tail(run)),
+                ))
+            }),
+            _ => None,
+        };
+        if let Some(notification) = notification {
+            with_notification_state_reducer(|r| r.show(notification));
+        }
+    }
+}
+
+fn output_tail(run: &ScriptRunOutput) -> String {
+    let tail: Vec<&str> = run
+        .lines
+        .iter()
+        .rev()
+        .take(FAILED_RUN_OUTPUT_LINES)
+        .rev()
+        .map(|line| line.text.as_str())
+        .collect();
+    tail.join("\n")
+}
+
+fn with_run<F>(run_id: u64, f: F) -> Option<Notification>
+where
+    F: FnOnce(&ScriptRunOutput) -> Option<Notification>,
+{
+    with_execution_state(|state| {
+        let runs = state.runs.read().unwrap();
+        runs.iter().find(|run| run.run_id == run_id).and_then(f)
+    })
+}
@@ -0,0 +1,19 @@
+use crate::bus::{Bus, DomainModule};
+use crate::domain::execution::execution_event_handler::ExecutionEvent;
+use crate::domain::notification::notification_event_handler::{
+    NotificationEvent, NotificationEventHandler,
+};
+use std::rc::Rc;
+
+pub struct NotificationModule;
+
+impl DomainModule for NotificationModule {
+    // Has to be registered after the execution module, so the run is finished in the state by the
+    // time a failed run is turned into a toast
+    fn register(self, bus: &mut Bus) {
+        let handler = Rc::new(NotificationEventHandler::new());
+        let notification_handler = handler.clone();
+        bus.subscribe(move |event: NotificationEvent| notification_handler.handle(event));
+        bus.subscribe(move |event: ExecutionEvent| handler.handle_execution_event(event));
+    }
+}
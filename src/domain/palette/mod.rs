@@ -0,0 +1,4 @@
+pub mod palette_command_handler;
+pub mod palette_event_handler;
+pub mod palette_module;
+pub mod palette_search;
@@ -0,0 +1,88 @@
+use crate::bus::Command;
+use crate::db::repository::script_repository::ScriptRepository;
+use crate::db::repository::script_run_repository::ScriptRunRepository;
+use crate::domain::folder::script_tags::tags_of_script;
+use crate::domain::palette::palette_event_handler::PaletteEvent;
+use crate::state::palette_state::PaletteEntry;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub enum PaletteCommand {
+    // Loads every script of the database with its folders and run statistics
+    Open,
+}
+
+impl Command for PaletteCommand {
+    type Output = ();
+}
+
+pub struct PaletteCommandHandler {
+    script_repository: Arc<ScriptRepository>,
+    script_run_repository: Arc<ScriptRunRepository>,
+}
+
+impl PaletteCommandHandler {
+    pub fn new() -> Self {
+        Self {
+            script_repository: Arc::new(ScriptRepository::new()),
+            script_run_repository: Arc::new(ScriptRunRepository::new()),
+        }
+    }
+
+    pub fn handle(&self, command: PaletteCommand) {
+        match command {
+            PaletteCommand::Open => {
+                let script_repository = self.script_repository.clone();
+                let script_run_repository = self.script_run_repository.clone();
+                crate::spawn_task(async move {
+                    let scripts = match script_repository.get_all_scripts_with_folders().await {
+                        Ok(scripts) => scripts,
+                        Err(e) => {
+                            crate::report_error(
+                                "Failed to load scripts for the command palette",
+                                &e,
+                            );
+                            return;
+                        }
+                    };
+                    let run_stats =
+                        script_run_repository
+                            .get_run_stats()
+                            .await
+                            .unwrap_or_else(|e| {
+                                crate::report_error("Failed to load run statistics", &e);
+                                Default::default()
+                            });
+
+                    let entries = scripts
+                        .into_iter()
+                        .map(|script| {
+                            let folders = script
+                                .rel_scriptsfolder_shellscript()
+                                .map(|rels| {
+                                    rels.iter()
+                                        .filter_map(|rel| rel.scripts_folder().ok())
+                                        .map(|folder| (folder.id, folder.name.clone()))
+                                        .collect()
+                                })
+                                .unwrap_or_default();
+                            let tags = tags_of_script(&script)
+                                .into_iter()
+                                .map(|tag| tag.to_string())
+                                .collect();
+                            let stats = run_stats.get(&script.id).copied().unwrap_or_default();
+                            PaletteEntry {
+                                script,
+                                folders,
+                                tags,
+                                run_count: stats.run_count,
+                                last_started_at: stats.last_started_at,
+                            }
+                        })
+                        .collect();
+                    crate::dispatch_palette_event(PaletteEvent::Opened { entries });
+                });
+            }
+        }
+    }
+}
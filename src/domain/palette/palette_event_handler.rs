@@ -0,0 +1,26 @@
+use crate::bus::Event;
+use crate::state::palette_state::PaletteEntry;
+use crate::with_palette_state_reducer;
+
+#[derive(Debug, Clone)]
+pub enum PaletteEvent {
+    Opened { entries: Vec<PaletteEntry> },
+}
+
+impl Event for PaletteEvent {}
+
+pub struct PaletteEventHandler {}
+
+impl PaletteEventHandler {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn handle(&self, event: PaletteEvent) {
+        match event {
+            PaletteEvent::Opened { entries } => {
+                with_palette_state_reducer(|r| r.open_palette(entries));
+            }
+        }
+    }
+}
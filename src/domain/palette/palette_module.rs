@@ -0,0 +1,17 @@
+use crate::bus::{Bus, DomainModule, Reply};
+use crate::domain::palette::palette_command_handler::{PaletteCommand, PaletteCommandHandler};
+use crate::domain::palette::palette_event_handler::{PaletteEvent, PaletteEventHandler};
+
+pub struct PaletteModule;
+
+impl DomainModule for PaletteModule {
+    fn register(self, bus: &mut Bus) {
+        let command_handler = PaletteCommandHandler::new();
+        bus.handle_commands(move |command: PaletteCommand, reply: Reply<()>| {
+            command_handler.handle(command);
+            reply.send(());
+        });
+        let event_handler = PaletteEventHandler::new();
+        bus.subscribe(move |event: PaletteEvent| event_handler.handle(event));
+    }
+}
@@ -0,0 +1,186 @@
+use crate::domain::folder::script_tags::normalize_tag;
+use crate::state::palette_state::PaletteEntry;
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+
+// Indices into `entries`, best match first. An empty query lists the most used scripts first.
+// Words starting with `#` keep only the scripts carrying all of those tags.
+pub fn rank_entries(entries: &[PaletteEntry], query: &str, now_millis: f64) -> Vec<usize> {
+    let (tags, text) = split_tag_filters(query);
+    let query = text.as_str();
+    let matcher = SkimMatcherV2::default();
+    let mut ranked: Vec<(usize, i64)> = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            if !tags.iter().all(|tag| entry.tags.contains(tag)) {
+                return None;
+            }
+            let usage = usage_boost(entry, now_millis);
+            if query.is_empty() {
+                return Some((index, usage));
+            }
+            // Name hits count most, then folder names, then anything in the command or description
+            let name_score = matcher
+                .fuzzy_match(&entry.script.name, query)
+                .map(|s| s * 3);
+            let folder_score = entry
+                .folders
+                .iter()
+                .filter_map(|(_, name)| matcher.fuzzy_match(name, query))
+                .max()
+                .map(|s| s * 2);
+            let command_score = matcher.fuzzy_match(&entry.script.command, query);
+            let description_score = matcher.fuzzy_match(&entry.script.description, query);
+            let best = [name_score, folder_score, command_score, description_score]
+                .into_iter()
+                .flatten()
+                .max()?;
+            Some((index, best + usage))
+        })
+        .collect();
+    ranked.sort_by(|(a_index, a_score), (b_index, b_score)| {
+        b_score.cmp(a_score).then_with(|| {
+            entries[*a_index]
+                .script
+                .name
+                .to_lowercase()
+                .cmp(&entries[*b_index].script.name.to_lowercase())
+        })
+    });
+    ranked.into_iter().map(|(index, _)| index).collect()
+}
+
+// `#prod deploy` -> (["prod"], "deploy")
+fn split_tag_filters(query: &str) -> (Vec<String>, String) {
+    let mut tags = vec![];
+    let mut words = vec![];
+    for word in query.split_whitespace() {
+        if word.starts_with('#') {
+            // A lone `#` while typing filters nothing yet
+            if let Some(tag) = normalize_tag(word) {
+                tags.push(tag);
+            }
+        } else {
+            words.push(word);
+        }
+    }
+    (tags, words.join(" "))
+}
+
+// Frecency: how often a script was run plus how recently, on the scale of fuzzy scores
+fn usage_boost(entry: &PaletteEntry, now_millis: f64) -> i64 {
+    let frequency = (entry.run_count as f64).ln_1p() * 12.0;
+    let recency = match entry.last_started_at {
+        Some(started_at) => {
+            let days_ago = (now_millis - started_at) / MILLIS_PER_DAY;
+            if days_ago < 1.0 {
+                40.0
+            } else if days_ago < 7.0 {
+                25.0
+            } else if days_ago < 30.0 {
+                10.0
+            } else {
+                0.0
+            }
+        }
+        None => 0.0,
+    };
+    (frequency + recency).round() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prisma::shell_script;
+
+    const NOW: f64 = 100.0 * MILLIS_PER_DAY;
+
+    fn entry(name: &str, command: &str) -> PaletteEntry {
+        PaletteEntry {
+            script: shell_script::Data {
+                id: 1,
+                name: name.to_string(),
+                command: command.to_string(),
+                description: String::new(),
+                ordering: 0,
+                working_directory: None,
+                interpreter: "login_shell".to_string(),
+                shebang: None,
+                source_rc: true,
+                pinned: false,
+                pinned_ordering: 0,
+                deleted_at: None,
+                created_at: 0.0,
+                created_at_hk: String::new(),
+                rel_scriptsfolder_shellscript: None,
+                script_run: None,
+                script_placeholder_value: None,
+                shell_script_env_var: None,
+                rel_shellscript_tag: None,
+                shell_script_revision: None,
+            },
+            folders: vec![],
+            tags: vec![],
+            run_count: 0,
+            last_started_at: None,
+        }
+    }
+
+    fn run(mut entry: PaletteEntry, run_count: usize, days_ago: f64) -> PaletteEntry {
+        entry.run_count = run_count;
+        entry.last_started_at = Some(NOW - days_ago * MILLIS_PER_DAY);
+        entry
+    }
+
+    fn tagged(mut entry: PaletteEntry, tags: &[&str]) -> PaletteEntry {
+        entry.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        entry
+    }
+
+    #[test]
+    fn ranks_exact_then_prefix_then_subsequence_matches() {
+        let entries = vec![
+            entry("Dump empty pool logs yearly", "true"),
+            entry("Restart nginx", "true"),
+            entry("Deploy staging", "true"),
+            entry("deploy", "true"),
+        ];
+        assert_eq!(rank_entries(&entries, "deploy", NOW), vec![3, 2, 0]);
+    }
+
+    #[test]
+    fn name_matches_outrank_the_same_match_in_a_command() {
+        let entries = vec![
+            entry("Build", "cargo build && ./deploy"),
+            entry("deploy", "true"),
+        ];
+        assert_eq!(rank_entries(&entries, "deploy", NOW), vec![1, 0]);
+    }
+
+    #[test]
+    fn empty_query_lists_frequently_and_recently_run_scripts_first() {
+        let entries = vec![
+            entry("beta", "true"),
+            run(entry("Ran often last week", "true"), 3, 10.0),
+            entry("Alpha", "true"),
+            run(entry("Ran once today", "true"), 1, 0.5),
+        ];
+        assert_eq!(rank_entries(&entries, "", NOW), vec![3, 1, 2, 0]);
+        assert_eq!(rank_entries(&entries, "   ", NOW), vec![3, 1, 2, 0]);
+    }
+
+    #[test]
+    fn tag_words_keep_only_scripts_carrying_every_tag() {
+        let entries = vec![
+            tagged(entry("Deploy api", "true"), &["prod", "api"]),
+            tagged(entry("Deploy web", "true"), &["prod"]),
+            entry("Deploy docs", "true"),
+        ];
+        assert_eq!(rank_entries(&entries, "#prod deploy", NOW), vec![0, 1]);
+        assert_eq!(rank_entries(&entries, "#Prod #api", NOW), vec![0]);
+        assert_eq!(rank_entries(&entries, "# deploy", NOW).len(), 3);
+    }
+}
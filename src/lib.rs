@@ -4,6 +4,9 @@ use std::sync::OnceLock;
 
 pub static RT_HANDLE: OnceLock<tokio::runtime::Handle> = OnceLock::new();
 
+// Only the tail of very long outputs is kept in the run history
+pub const MAX_PERSISTED_OUTPUT_LINES: usize = 5000;
+
 pub fn spawn_task<F>(future: F)
 where
     F: std::future::Future<Output = ()> + Send + 'static,
@@ -11,116 +14,252 @@ where
     RT_HANDLE.get().unwrap().spawn(future);
 }
 
-pub fn run_terminal_command(command: String) {
+// Entry point of the Execute actions: parameterized scripts first collect their placeholder values
+pub fn execute_script(execution: ScriptExecution) {
+    if domain::execution::placeholder::parse_placeholders(&execution.command).is_empty() {
+        run_terminal_command(execution);
+    } else {
+        dispatch_execution_command(ExecutionCommand::PromptPlaceholders { execution });
+    }
+}
+
+pub fn run_terminal_command(execution: ScriptExecution) {
+    let run_id = crate::state::execution_state::next_run_id();
     spawn_task(async move {
-        // Get the user's home directory
-        let home = std::env::var("HOME").unwrap_or_else(|_| {
-            dirs::home_dir()
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_else(|| "/Users".to_string())
-        });
+        let launcher = ScriptLauncher::prepare(&execution, run_id);
+        let ScriptExecution {
+            script_id,
+            script_name,
+            command,
+            ..
+        } = execution;
+
+        let script_run_repository = ScriptRunRepository::new();
+        let persisted_run_id = match script_run_repository
+            .create_run(script_id, launcher.interpreter_name(), command.clone())
+            .await
+        {
+            Ok(script_run) => Some(script_run.id),
+            Err(e) => {
+                report_error(
+                    &format!("Failed to record the run of '{}'", script_name),
+                    &e,
+                );
+                None
+            }
+        };
 
-        // Detect the user's shell from /etc/passwd or use zsh as default
-        let shell = std::env::var("SHELL").unwrap_or_else(|_| {
-            // Try to read from /etc/passwd
-            std::fs::read_to_string("/etc/passwd")
-                .ok()
-                .and_then(|content| {
-                    content.lines().find_map(|line| {
-                        if line.contains(&home) {
-                            line.split(':').last().map(|s| s.to_string())
-                        } else {
-                            None
-                        }
-                    })
-                })
-                .unwrap_or_else(|| "/bin/zsh".to_string())
+        dispatch_execution_event(ExecutionEvent::RunStarted {
+            run_id,
+            script_id,
+            script_name: script_name.clone(),
+            command: command.clone(),
         });
 
-        #[cfg(debug_assertions)]
-        println!("Using shell: {} for command: {}", shell, command);
-
-        // Build the command that sources the shell config files before running
-        let wrapped_command = if shell.contains("zsh") {
-            format!(
-                "source ~/.zshrc 2>/dev/null; source ~/.zprofile 2>/dev/null; {}",
-                command
-            )
-        } else if shell.contains("bash") {
-            format!(
-                "source ~/.bash_profile 2>/dev/null; source ~/.bashrc 2>/dev/null; {}",
-                command
-            )
-        } else {
-            command.clone()
-        };
+        let spawned = launcher.spawn(true, std::process::Stdio::null());
 
-        let output = tokio::process::Command::new(&shell)
-            .arg("-l") // Login shell
-            .arg("-c")
-            .arg(&wrapped_command)
-            .env("HOME", &home) // Ensure HOME is set
-            .env(
-                "USER",
-                std::env::var("USER").unwrap_or_else(|_| whoami::username()),
-            )
-            .output()
-            .await;
-
-        match output {
-            Ok(output) => {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                let stderr = String::from_utf8_lossy(&output.stderr);
-
-                #[cfg(debug_assertions)]
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) => {
+                report_error(&format!("Failed to start '{}'", script_name), &e);
+                if let Some(persisted_run_id) = persisted_run_id
+                    && let Err(e) = script_run_repository
+                        .finish_run(persisted_run_id, None, e.to_string())
+                        .await
                 {
-                    println!("Command executed: {}", command);
-                    if !stdout.is_empty() {
-                        println!("Output: {}", stdout);
-                    }
-                    if !stderr.is_empty() {
-                        eprintln!("Error: {}", stderr);
-                    }
+                    report_error(
+                        &format!("Failed to record the result of '{}'", script_name),
+                        &e,
+                    );
                 }
+                dispatch_execution_event(ExecutionEvent::RunFailedToStart {
+                    run_id,
+                    script_id,
+                    error: e.to_string(),
+                });
+                launcher.cleanup();
+                return;
+            }
+        };
+
+        // Stream both pipes line-by-line while waiting for the process to exit
+        let stop_receiver = process_registry::register(run_id);
+        let captured_output = std::sync::Mutex::new(std::collections::VecDeque::new());
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let output = async {
+            tokio::join!(
+                forward_output_lines(run_id, stdout, OutputStream::Stdout, &captured_output),
+                forward_output_lines(run_id, stderr, OutputStream::Stderr, &captured_output),
+            );
+        };
+        let wait = process_registry::wait_or_stop(&mut child, stop_receiver);
+        tokio::pin!(output, wait);
+        let status = tokio::select! {
+            status = &mut wait => {
+                // A background process of the script may still hold the pipes open, only what is
+                // already on its way is read
+                let _ = tokio::time::timeout(process_registry::OUTPUT_DRAIN_TIMEOUT, &mut output)
+                    .await;
+                status
+            }
+            () = &mut output => wait.await,
+        };
+        process_registry::unregister(run_id);
+        launcher.cleanup();
 
+        let (exit_code, signal) = match status {
+            Ok(status) => {
                 // Show errors in both debug and release mode
-                if !output.status.success() && !stderr.is_empty() {
-                    eprintln!("Command '{}' failed: {}", command, stderr);
+                if !status.success() {
+                    eprintln!("Command '{}' failed with status: {}", command, status);
                 }
+                (status.code(), terminating_signal(&status))
+            }
+            Err(e) => {
+                report_error(&format!("Failed to wait for '{}'", script_name), &e);
+                (None, None)
+            }
+        };
+
+        if let Some(persisted_run_id) = persisted_run_id {
+            let output = captured_output
+                .into_inner()
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>()
+                .join("\n");
+            if let Err(e) = script_run_repository
+                .finish_run(persisted_run_id, exit_code, output)
+                .await
+            {
+                report_error(
+                    &format!("Failed to record the result of '{}'", script_name),
+                    &e,
+                );
             }
-            Err(e) => eprintln!("Failed to execute command '{}': {:?}", command, e),
         }
+
+        dispatch_execution_event(ExecutionEvent::RunFinished {
+            run_id,
+            script_id,
+            exit_code,
+            signal,
+        });
     });
 }
 
+fn terminating_signal(status: &std::process::ExitStatus) -> Option<i32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        status.signal()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = status;
+        None
+    }
+}
+
+async fn forward_output_lines<R>(
+    run_id: u64,
+    reader: Option<R>,
+    stream: OutputStream,
+    captured_output: &std::sync::Mutex<std::collections::VecDeque<String>>,
+) where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    use tokio::io::AsyncBufReadExt;
+
+    let Some(reader) = reader else {
+        return;
+    };
+    // Split on raw bytes so that non UTF-8 output does not end the stream early
+    let mut segments = tokio::io::BufReader::new(reader).split(b'\n');
+    while let Ok(Some(segment)) = segments.next_segment().await {
+        let line = String::from_utf8_lossy(&segment)
+            .trim_end_matches('\r')
+            .to_string();
+        {
+            let mut captured = captured_output.lock().unwrap();
+            captured.push_back(line.clone());
+            if captured.len() > MAX_PERSISTED_OUTPUT_LINES {
+                captured.pop_front();
+            }
+        }
+        dispatch_execution_event(ExecutionEvent::OutputReceived {
+            run_id,
+            stream,
+            line,
+        });
+    }
+}
+
 pub fn send_event(message: AppMessage) {
     let _ = EVENT_SENDER.get().unwrap().send(message);
 }
 
 pub fn dispatch_folder_event(event: FolderEvent) {
-    println!("Dispatching folder event: {:?}", event);
-    send_event(AppMessage::Event(AppEvent::Folder(event)));
+    log::debug!("Dispatching folder event: {:?}", event);
+    bus::publish(event);
+}
+
+pub fn dispatch_notification_event(event: NotificationEvent) {
+    bus::publish(event);
+}
+
+// Shows a toast, which is how failures reach users of a release build without a terminal
+pub fn notify(notification: Notification) {
+    dispatch_notification_event(NotificationEvent::Raised { notification });
+}
+
+// Logs the failure and shows `message` as an error toast with the error as its detail
+pub fn report_error<E: std::fmt::Debug>(message: &str, error: &E) {
+    eprintln!("{}: {:?}", message, error);
+    notify(Notification::error(message, format!("{:?}", error)));
+}
+
+pub fn dispatch_execution_event(event: ExecutionEvent) {
+    bus::publish(event);
+}
+
+pub fn dispatch_execution_command(command: ExecutionCommand) {
+    log::debug!("Dispatching execution command: {:?}", command);
+    bus::dispatch(command);
+}
+
+pub fn dispatch_palette_event(event: PaletteEvent) {
+    bus::publish(event);
+}
+
+pub fn dispatch_palette_command(command: PaletteCommand) {
+    log::debug!("Dispatching palette command: {:?}", command);
+    bus::dispatch(command);
+}
+
+pub fn dispatch_library_event(event: LibraryEvent) {
+    log::debug!("Dispatching library event: {:?}", event);
+    bus::publish(event);
+}
+
+pub fn dispatch_library_command(command: LibraryCommand) {
+    log::debug!("Dispatching library command: {:?}", command);
+    bus::dispatch(command);
 }
 
 pub fn dispatch_folder_command(command: FolderCommand) {
-    println!("Dispatching folder command: {:?}", command);
-    let wrapped = WrappedFolderCommand {
-        command,
-        callback: None,
-    };
-    send_event(AppMessage::Command(AppCommand::Folder(wrapped)));
+    log::debug!("Dispatching folder command: {:?}", command);
+    bus::dispatch(command);
 }
 
-pub fn dispatch_folder_command_with_callback<F>(command: FolderCommand, callback: Option<F>)
+// `on_reply` runs on the UI thread once the command went through or failed
+pub fn dispatch_folder_command_with_reply<F>(command: FolderCommand, on_reply: F)
 where
-    F: Fn() + Send + 'static,
+    F: FnOnce(FolderCommandResult) + Send + 'static,
 {
-    let cb_box = callback.map(|f| Box::new(f) as Box<dyn Fn() + Send + 'static>);
-    let wrapped = WrappedFolderCommand {
-        command,
-        callback: cb_box,
-    };
-    send_event(AppMessage::Command(AppCommand::Folder(wrapped)));
+    log::debug!("Dispatching folder command: {:?}", command);
+    bus::dispatch_with_reply(command, bus::Reply::to(on_reply));
 }
 
 pub fn with_folder_state<F, R>(f: F) -> R
@@ -140,7 +279,75 @@ where
     };
     f(&reducer)
 }
+
+pub fn with_execution_state<F, R>(f: F) -> R
+where
+    F: FnOnce(&crate::state::execution_state::ExecutionState) -> R,
+{
+    f(&crate::state::execution_state::EXECUTION_STATE)
+}
+
+pub fn with_execution_state_reducer<F, R>(f: F) -> R
+where
+    F: FnOnce(&crate::state::execution_state::ExecutionReducer<'static>) -> R,
+{
+    let reducer = crate::state::execution_state::ExecutionReducer {
+        state: &crate::state::execution_state::EXECUTION_STATE,
+    };
+    f(&reducer)
+}
+pub fn with_palette_state<F, R>(f: F) -> R
+where
+    F: FnOnce(&crate::state::palette_state::PaletteState) -> R,
+{
+    f(&crate::state::palette_state::PALETTE_STATE)
+}
+
+pub fn with_palette_state_reducer<F, R>(f: F) -> R
+where
+    F: FnOnce(&crate::state::palette_state::PaletteReducer<'static>) -> R,
+{
+    let reducer = crate::state::palette_state::PaletteReducer {
+        state: &crate::state::palette_state::PALETTE_STATE,
+    };
+    f(&reducer)
+}
+
+pub fn with_notification_state<F, R>(f: F) -> R
+where
+    F: FnOnce(&crate::state::notification_state::NotificationState) -> R,
+{
+    f(&crate::state::notification_state::NOTIFICATION_STATE)
+}
+
+pub fn with_notification_state_reducer<F, R>(f: F) -> R
+where
+    F: FnOnce(&crate::state::notification_state::NotificationReducer<'static>) -> R,
+{
+    let reducer = crate::state::notification_state::NotificationReducer {
+        state: &crate::state::notification_state::NOTIFICATION_STATE,
+    };
+    f(&reducer)
+}
+
+pub fn with_library_state<F, R>(f: F) -> R
+where
+    F: FnOnce(&crate::state::library_state::LibraryState) -> R,
+{
+    f(&crate::state::library_state::LIBRARY_STATE)
+}
+
+pub fn with_library_state_reducer<F, R>(f: F) -> R
+where
+    F: FnOnce(&crate::state::library_state::LibraryReducer<'static>) -> R,
+{
+    let reducer = crate::state::library_state::LibraryReducer {
+        state: &crate::state::library_state::LIBRARY_STATE,
+    };
+    f(&reducer)
+}
 pub mod app;
+pub mod bus;
 pub mod component;
 pub mod db;
 pub mod domain;
@@ -153,52 +360,26 @@ pub use app::App;
 // Event system
 use crossbeam::channel::{Receiver, Sender};
 
+pub use bus::AppMessage;
+
+use crate::db::repository::script_run_repository::ScriptRunRepository;
+use crate::domain::execution::{
+    execution_command_handler::ExecutionCommand, execution_event_handler::ExecutionEvent,
+    launcher::ScriptLauncher, process_registry, script_execution::ScriptExecution,
+};
 use crate::domain::folder::{
-    folder_command_handler::FolderCommand, folder_event_handler::FolderEvent,
+    folder_command_handler::{FolderCommand, FolderCommandResult},
+    folder_event_handler::FolderEvent,
 };
-
-// Wrapper for commands that can optionally carry a callback to be executed after handling.
-// The callback is optional and boxed; we use `Fn()` for object safety and simplicity.
-pub struct WrappedFolderCommand {
-    pub command: FolderCommand,
-    pub callback: Option<Box<dyn Fn() + Send + 'static>>,
-}
-
-impl std::fmt::Debug for WrappedFolderCommand {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("WrappedFolderCommand")
-            .field("command", &self.command)
-            .field("has_callback", &self.callback.is_some())
-            .finish()
-    }
-}
-
-// Domain-specific message types
-#[derive(Debug)]
-pub enum AppCommand {
-    Folder(WrappedFolderCommand),
-}
-
-#[derive(Debug)]
-pub enum AppEvent {
-    Folder(FolderEvent),
-}
-
-pub enum AppMessage {
-    Command(AppCommand),
-    Event(AppEvent),
-    Callback(Box<dyn Fn() + Send + 'static>),
-}
-
-impl std::fmt::Debug for AppMessage {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            AppMessage::Command(cmd) => f.debug_tuple("Command").field(cmd).finish(),
-            AppMessage::Event(evt) => f.debug_tuple("Event").field(evt).finish(),
-            AppMessage::Callback(_) => f.debug_tuple("Callback").field(&"<callback>").finish(),
-        }
-    }
-}
+use crate::domain::library::{
+    library_command_handler::LibraryCommand, library_event_handler::LibraryEvent,
+};
+use crate::domain::notification::notification_event_handler::NotificationEvent;
+use crate::domain::palette::{
+    palette_command_handler::PaletteCommand, palette_event_handler::PaletteEvent,
+};
+use crate::state::execution_state::OutputStream;
+use crate::state::notification_state::Notification;
 
 pub static EVENT_SENDER: OnceLock<Sender<AppMessage>> = OnceLock::new();
 pub static EVENT_RECEIVER: OnceLock<Receiver<AppMessage>> = OnceLock::new();
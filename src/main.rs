@@ -6,22 +6,7 @@ fn main() -> eframe::Result<()> {
     #[cfg(debug_assertions)]
     env_logger::init();
 
-    // Choose database location based on build mode
-    let db_path = if cfg!(debug_assertions) {
-        // In debug mode, use current directory for easier development
-        std::env::current_dir().unwrap().join("database.db")
-    } else {
-        // In release mode, use proper app data directory
-        let app_data_dir = dirs::data_dir()
-            .unwrap_or_else(|| std::env::current_dir().unwrap())
-            .join("ShellScriptManager");
-
-        // Create directory if it doesn't exist
-        std::fs::create_dir_all(&app_data_dir).ok();
-
-        app_data_dir.join("database.db")
-    };
-
+    let db_path = shell_script_manager::db::get_db::database_path();
     let db_url = format!("file:{}", db_path.display());
 
     let rt = tokio::runtime::Runtime::new().unwrap();
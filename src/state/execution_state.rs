@@ -0,0 +1,184 @@
+use crate::domain::execution::placeholder::Placeholder;
+use crate::domain::execution::script_execution::ScriptExecution;
+use crate::prisma;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, RwLock};
+
+// Keep memory bounded for chatty scripts (e.g. `tail -f`) and long sessions
+const MAX_LINES_PER_RUN: usize = 5000;
+const MAX_RUNS_KEPT: usize = 20;
+
+static NEXT_RUN_ID: AtomicU64 = AtomicU64::new(1);
+
+pub fn next_run_id() -> u64 {
+    NEXT_RUN_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Clone, Debug)]
+pub struct OutputLine {
+    pub stream: OutputStream,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RunStatus {
+    Running,
+    Finished { exit_code: Option<i32> },
+    FailedToStart { error: String },
+}
+
+#[derive(Clone, Debug)]
+pub struct ScriptRunOutput {
+    pub run_id: u64,
+    pub script_id: i32,
+    pub script_name: String,
+    pub command: String,
+    pub lines: Vec<OutputLine>,
+    pub truncated_lines: usize,
+    pub status: RunStatus,
+    pub stop_requested: bool,
+}
+
+impl ScriptRunOutput {
+    pub fn is_running(&self) -> bool {
+        self.status == RunStatus::Running
+    }
+}
+
+// Values being collected for a parameterized script before it is launched
+#[derive(Clone, Debug)]
+pub struct PlaceholderForm {
+    pub execution: ScriptExecution,
+    pub placeholders: Vec<Placeholder>,
+    pub values: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct ExecutionState {
+    pub runs: RwLock<Arc<Vec<ScriptRunOutput>>>,
+    pub selected_run_id: RwLock<Option<u64>>,
+    pub console_open: RwLock<bool>,
+    pub run_history_script_id: RwLock<Option<i32>>,
+    pub run_history: RwLock<Arc<Vec<prisma::script_run::Data>>>,
+    pub placeholder_form: RwLock<Option<PlaceholderForm>>,
+}
+
+pub static EXECUTION_STATE: LazyLock<ExecutionState> = LazyLock::new(|| ExecutionState::default());
+
+pub struct ExecutionReducer<'a> {
+    pub state: &'a ExecutionState,
+}
+
+impl<'a> ExecutionReducer<'a> {
+    pub fn start_run(&self, run_id: u64, script_id: i32, script_name: String, command: String) {
+        let mut runs = self.state.runs.write().unwrap();
+        let runs_vec = Arc::make_mut(&mut *runs);
+        runs_vec.push(ScriptRunOutput {
+            run_id,
+            script_id,
+            script_name,
+            command,
+            lines: vec![],
+            truncated_lines: 0,
+            status: RunStatus::Running,
+            stop_requested: false,
+        });
+
+        // Drop the oldest finished runs once we keep too many around
+        while runs_vec.len() > MAX_RUNS_KEPT {
+            match runs_vec.iter().position(|r| !r.is_running()) {
+                Some(index) => {
+                    runs_vec.remove(index);
+                }
+                None => break,
+            }
+        }
+
+        *self.state.selected_run_id.write().unwrap() = Some(run_id);
+        *self.state.console_open.write().unwrap() = true;
+    }
+
+    pub fn append_output(&self, run_id: u64, stream: OutputStream, text: String) {
+        let mut runs = self.state.runs.write().unwrap();
+        let runs_vec = Arc::make_mut(&mut *runs);
+        if let Some(run) = runs_vec.iter_mut().find(|r| r.run_id == run_id) {
+            run.lines.push(OutputLine { stream, text });
+            if run.lines.len() > MAX_LINES_PER_RUN {
+                let overflow = run.lines.len() - MAX_LINES_PER_RUN;
+                run.lines.drain(..overflow);
+                run.truncated_lines += overflow;
+            }
+        }
+    }
+
+    pub fn mark_stop_requested(&self, run_id: u64) {
+        let mut runs = self.state.runs.write().unwrap();
+        let runs_vec = Arc::make_mut(&mut *runs);
+        if let Some(run) = runs_vec.iter_mut().find(|r| r.run_id == run_id) {
+            run.stop_requested = true;
+        }
+    }
+
+    pub fn finish_run(&self, run_id: u64, status: RunStatus) {
+        let mut runs = self.state.runs.write().unwrap();
+        let runs_vec = Arc::make_mut(&mut *runs);
+        if let Some(run) = runs_vec.iter_mut().find(|r| r.run_id == run_id) {
+            run.status = status;
+        }
+    }
+
+    pub fn select_run(&self, run_id: u64) {
+        *self.state.selected_run_id.write().unwrap() = Some(run_id);
+    }
+
+    pub fn clear_finished_runs(&self) {
+        let mut runs = self.state.runs.write().unwrap();
+        let remaining: Vec<_> = runs.iter().filter(|r| r.is_running()).cloned().collect();
+        let mut selected_run_id = self.state.selected_run_id.write().unwrap();
+        if !remaining.iter().any(|r| Some(r.run_id) == *selected_run_id) {
+            *selected_run_id = remaining.last().map(|r| r.run_id);
+        }
+        *runs = Arc::new(remaining);
+    }
+
+    pub fn set_console_open(&self, open: bool) {
+        *self.state.console_open.write().unwrap() = open;
+    }
+
+    pub fn open_run_history(&self, script_id: i32) {
+        *self.state.run_history_script_id.write().unwrap() = Some(script_id);
+        *self.state.run_history.write().unwrap() = Arc::new(vec![]);
+    }
+
+    pub fn set_run_history(&self, script_id: i32, runs: Vec<prisma::script_run::Data>) {
+        // Ignore responses that arrive after the window switched to another script
+        if *self.state.run_history_script_id.read().unwrap() == Some(script_id) {
+            *self.state.run_history.write().unwrap() = Arc::new(runs);
+        }
+    }
+
+    pub fn close_run_history(&self) {
+        *self.state.run_history_script_id.write().unwrap() = None;
+        *self.state.run_history.write().unwrap() = Arc::new(vec![]);
+    }
+
+    pub fn open_placeholder_form(&self, form: PlaceholderForm) {
+        *self.state.placeholder_form.write().unwrap() = Some(form);
+    }
+
+    pub fn set_placeholder_values(&self, values: Vec<String>) {
+        if let Some(form) = self.state.placeholder_form.write().unwrap().as_mut() {
+            form.values = values;
+        }
+    }
+
+    pub fn close_placeholder_form(&self) {
+        *self.state.placeholder_form.write().unwrap() = None;
+    }
+}
@@ -1,18 +1,50 @@
+use std::collections::HashSet;
 use std::sync::{Arc, LazyLock, RwLock};
 
+use crate::db::repository::script_repository::ScriptSearchHit;
+use crate::db::repository::trash_repository::TrashContents;
+use crate::domain::folder::folder_tree::subtree_ids;
 use crate::prisma;
 use prisma_client_rust::bigdecimal::ToPrimitive;
 
+// Entries of the left column listing scripts across all folders
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VirtualFolder {
+    Pinned,
+    Trash,
+}
+
 #[derive(Default)]
 pub struct FoldersState {
     pub selected_folder_id: RwLock<Option<i32>>,
+    // A virtual entry is open instead of `selected_folder_id`, which is kept for when a folder is
+    // picked again
+    pub virtual_folder: RwLock<Option<VirtualFolder>>,
+    pub pinned_scripts: RwLock<Arc<Vec<prisma::shell_script::Data>>>,
+    pub trash: RwLock<Arc<TrashContents>>,
     pub app_state: RwLock<Arc<Option<prisma::application_state::Data>>>,
     pub folder_list: RwLock<Arc<Vec<prisma::scripts_folder::Data>>>,
+    pub collapsed_folder_ids: RwLock<HashSet<i32>>,
     pub scripts_of_selected_folder: RwLock<Arc<Vec<prisma::shell_script::Data>>>,
     pub folder_to_delete: RwLock<Option<Arc<prisma::scripts_folder::Data>>>,
     pub folder_to_rename: RwLock<Option<Arc<prisma::scripts_folder::Data>>>,
     pub rename_text: RwLock<Option<String>>,
     pub script_to_edit: RwLock<Option<Arc<prisma::shell_script::Data>>>,
+    pub script_search_query: RwLock<String>,
+    pub script_search_hits: RwLock<Arc<Vec<ScriptSearchHit>>>,
+    pub tag_list: RwLock<Arc<Vec<String>>>,
+    // With `tag_filter_all_folders` the scripts column lists `scripts_with_tag` instead of the
+    // scripts of the selected folder
+    pub tag_filter: RwLock<Option<String>>,
+    pub tag_filter_all_folders: RwLock<bool>,
+    pub scripts_with_tag: RwLock<Arc<Vec<prisma::shell_script::Data>>>,
+    // The script whose versions window is open, as it is now, and its earlier versions
+    pub revision_history_script_id: RwLock<Option<i32>>,
+    pub revision_history_script: RwLock<Option<Arc<prisma::shell_script::Data>>>,
+    pub script_revisions: RwLock<Arc<Vec<prisma::shell_script_revision::Data>>>,
+    // Names of the steps Ctrl+Z and Ctrl+Shift+Z would take back or do again
+    pub undo_label: RwLock<Option<String>>,
+    pub redo_label: RwLock<Option<String>>,
 }
 
 pub static FOLDER_STATE: LazyLock<FoldersState> = LazyLock::new(|| FoldersState::default());
@@ -24,12 +56,62 @@ pub struct FolderReducer<'a> {
 impl<'a> FolderReducer<'a> {
     pub fn select_folder(&self, id: i32) {
         *self.state.selected_folder_id.write().unwrap() = Some(id);
+        *self.state.virtual_folder.write().unwrap() = None;
+    }
+
+    pub fn select_virtual_folder(&self, virtual_folder: VirtualFolder) {
+        *self.state.virtual_folder.write().unwrap() = Some(virtual_folder);
     }
 
+    pub fn set_trash(&self, trash: TrashContents) {
+        *self.state.trash.write().unwrap() = Arc::new(trash);
+    }
+
+    // Shown right away, the stored value arrives with the reloaded app state
+    pub fn set_trash_retention_days(&self, days: u32) {
+        let mut app_state = self.state.app_state.write().unwrap();
+        if let Some(app_state) = Arc::make_mut(&mut *app_state) {
+            app_state.trash_retention_days = days as i32;
+        }
+    }
+
+    pub fn set_pinned_scripts(&self, scripts: Vec<prisma::shell_script::Data>) {
+        *self.state.pinned_scripts.write().unwrap() = Arc::new(scripts);
+    }
+
+    // Nothing left to select, e.g. after replacing the whole library with an empty one
+    pub fn clear_selected_folder(&self) {
+        *self.state.selected_folder_id.write().unwrap() = None;
+        *self.state.scripts_of_selected_folder.write().unwrap() = Arc::new(vec![]);
+    }
+
+    // Along with its subfolders, which were deleted with it
     pub fn delete_folder(&self, id: i32) {
         let mut folders = self.state.folder_list.write().unwrap();
-        let updated_folders: Vec<_> = folders.iter().filter(|f| f.id != id).cloned().collect();
+        let deleted_ids = subtree_ids(&folders, id);
+        let updated_folders: Vec<_> = folders
+            .iter()
+            .filter(|f| !deleted_ids.contains(&f.id))
+            .cloned()
+            .collect();
         *folders = Arc::new(updated_folders);
+        drop(folders);
+
+        let selected_folder_id = *self.state.selected_folder_id.read().unwrap();
+        if selected_folder_id.is_some_and(|selected_id| deleted_ids.contains(&selected_id)) {
+            self.clear_selected_folder();
+        }
+    }
+
+    pub fn toggle_folder_collapsed(&self, id: i32) {
+        let mut collapsed = self.state.collapsed_folder_ids.write().unwrap();
+        if !collapsed.remove(&id) {
+            collapsed.insert(id);
+        }
+    }
+
+    pub fn expand_folder(&self, id: i32) {
+        self.state.collapsed_folder_ids.write().unwrap().remove(&id);
     }
 
     pub fn delete_script_from_selected_folder(&self, script_id: i32) {
@@ -63,21 +145,90 @@ impl<'a> FolderReducer<'a> {
         *self.state.scripts_of_selected_folder.write().unwrap() = Arc::new(scripts);
     }
 
-    pub fn set_app_state(&self, app_state: Option<prisma::application_state::Data>) {
-        *self.state.app_state.write().unwrap() = Arc::new(app_state);
+    // Picked up by the scripts column, which owns the edit window
+    pub fn request_script_edit(&self, script: prisma::shell_script::Data) {
+        *self.state.script_to_edit.write().unwrap() = Some(Arc::new(script));
     }
 
-    pub fn insert_folder_into_index(&self, from_folder_index: usize, to_folder_index: usize) {
-        let mut folders = self.state.folder_list.write().unwrap();
-        let folders_vec = Arc::make_mut(&mut *folders);
-        if from_folder_index < 0
-            || to_folder_index < 0
-            || from_folder_index as usize >= folders_vec.len()
-            || to_folder_index as usize >= folders_vec.len()
-        {
+    pub fn take_script_to_edit(&self) -> Option<Arc<prisma::shell_script::Data>> {
+        self.state.script_to_edit.write().unwrap().take()
+    }
+
+    pub fn set_script_search_query(&self, query: &str) {
+        *self.state.script_search_query.write().unwrap() = query.to_string();
+        if query.trim().is_empty() {
+            *self.state.script_search_hits.write().unwrap() = Arc::new(vec![]);
+        }
+    }
+
+    // Results of an older query may arrive after the user kept typing, those are dropped
+    pub fn set_script_search_hits(&self, query: &str, hits: Vec<ScriptSearchHit>) {
+        if *self.state.script_search_query.read().unwrap() != query {
+            return;
+        }
+        *self.state.script_search_hits.write().unwrap() = Arc::new(hits);
+    }
+
+    // A filter on a tag that no longer exists is dropped
+    pub fn set_tag_list(&self, tags: Vec<String>) {
+        let mut tag_filter = self.state.tag_filter.write().unwrap();
+        if tag_filter.as_ref().is_some_and(|tag| !tags.contains(tag)) {
+            *tag_filter = None;
+            *self.state.scripts_with_tag.write().unwrap() = Arc::new(vec![]);
+        }
+        *self.state.tag_list.write().unwrap() = Arc::new(tags);
+    }
+
+    pub fn set_tag_filter(&self, tag: Option<String>) {
+        if tag.is_none() {
+            *self.state.scripts_with_tag.write().unwrap() = Arc::new(vec![]);
+        }
+        *self.state.tag_filter.write().unwrap() = tag;
+    }
+
+    pub fn set_tag_filter_all_folders(&self, all_folders: bool) {
+        *self.state.tag_filter_all_folders.write().unwrap() = all_folders;
+    }
+
+    // Results for a tag that was deselected in the meantime are dropped
+    pub fn set_scripts_with_tag(&self, tag: &str, scripts: Vec<prisma::shell_script::Data>) {
+        if self.state.tag_filter.read().unwrap().as_deref() != Some(tag) {
             return;
         }
-        let folder = folders_vec.remove(from_folder_index as usize);
-        folders_vec.insert(to_folder_index as usize, folder);
+        *self.state.scripts_with_tag.write().unwrap() = Arc::new(scripts);
+    }
+
+    pub fn open_revision_history(&self, script_id: i32) {
+        *self.state.revision_history_script_id.write().unwrap() = Some(script_id);
+        *self.state.revision_history_script.write().unwrap() = None;
+        *self.state.script_revisions.write().unwrap() = Arc::new(vec![]);
+    }
+
+    // Revisions of a script whose window was closed in the meantime are dropped
+    pub fn set_script_revisions(
+        &self,
+        script: prisma::shell_script::Data,
+        revisions: Vec<prisma::shell_script_revision::Data>,
+    ) {
+        if *self.state.revision_history_script_id.read().unwrap() != Some(script.id) {
+            return;
+        }
+        *self.state.revision_history_script.write().unwrap() = Some(Arc::new(script));
+        *self.state.script_revisions.write().unwrap() = Arc::new(revisions);
+    }
+
+    pub fn close_revision_history(&self) {
+        *self.state.revision_history_script_id.write().unwrap() = None;
+        *self.state.revision_history_script.write().unwrap() = None;
+        *self.state.script_revisions.write().unwrap() = Arc::new(vec![]);
+    }
+
+    pub fn set_app_state(&self, app_state: Option<prisma::application_state::Data>) {
+        *self.state.app_state.write().unwrap() = Arc::new(app_state);
+    }
+
+    pub fn set_history_labels(&self, undo_label: Option<String>, redo_label: Option<String>) {
+        *self.state.undo_label.write().unwrap() = undo_label;
+        *self.state.redo_label.write().unwrap() = redo_label;
     }
 }
@@ -0,0 +1,55 @@
+use crate::domain::library::shell_history::HistoryCommand;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, RwLock};
+
+#[derive(Default)]
+pub struct LibraryState {
+    // File picked in File → Import Library…, waiting for the user to choose merge or replace
+    pub import_path: RwLock<Option<PathBuf>>,
+    // Outcome of the last export / import
+    pub status_message: RwLock<Option<String>>,
+    // Deduplicated shell history while the history import window is open
+    pub history_import: RwLock<Option<Arc<Vec<HistoryCommand>>>>,
+    // (folder id, folder name) of the folder being exported as shell scripts
+    pub shell_export_folder: RwLock<Option<(i32, String)>>,
+}
+
+pub static LIBRARY_STATE: LazyLock<LibraryState> = LazyLock::new(|| LibraryState::default());
+
+pub struct LibraryReducer<'a> {
+    pub state: &'a LibraryState,
+}
+
+impl<'a> LibraryReducer<'a> {
+    pub fn request_import(&self, path: PathBuf) {
+        *self.state.import_path.write().unwrap() = Some(path);
+    }
+
+    pub fn take_import_path(&self) -> Option<PathBuf> {
+        self.state.import_path.write().unwrap().take()
+    }
+
+    pub fn set_status_message(&self, message: String) {
+        *self.state.status_message.write().unwrap() = Some(message);
+    }
+
+    pub fn dismiss_status_message(&self) {
+        *self.state.status_message.write().unwrap() = None;
+    }
+
+    pub fn open_history_import(&self, commands: Vec<HistoryCommand>) {
+        *self.state.history_import.write().unwrap() = Some(Arc::new(commands));
+    }
+
+    pub fn close_history_import(&self) {
+        *self.state.history_import.write().unwrap() = None;
+    }
+
+    pub fn request_shell_export(&self, folder_id: i32, folder_name: String) {
+        *self.state.shell_export_folder.write().unwrap() = Some((folder_id, folder_name));
+    }
+
+    pub fn take_shell_export(&self) -> Option<(i32, String)> {
+        self.state.shell_export_folder.write().unwrap().take()
+    }
+}
@@ -1 +1,5 @@
+pub mod execution_state;
 pub mod folder_state;
+pub mod library_state;
+pub mod notification_state;
+pub mod palette_state;
@@ -0,0 +1,129 @@
+use std::sync::{LazyLock, RwLock};
+use std::time::{Duration, Instant};
+
+// More toasts than this push the oldest out
+const MAX_TOASTS: usize = 5;
+// Toasts with their detail open stay until closed
+const TOAST_LIFETIME: Duration = Duration::from_secs(8);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationLevel {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub level: NotificationLevel,
+    pub message: String,
+    // Behind "Details", e.g. the database error or the tail of a script's output
+    pub detail: String,
+}
+
+impl Notification {
+    pub fn error(message: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            level: NotificationLevel::Error,
+            message: message.into(),
+            detail: detail.into(),
+        }
+    }
+
+    pub fn warning(message: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            level: NotificationLevel::Warning,
+            message: message.into(),
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub id: u64,
+    pub notification: Notification,
+    // The same message arriving again bumps this instead of stacking another toast
+    pub count: usize,
+    pub shown_at: Instant,
+    pub expanded: bool,
+}
+
+impl Toast {
+    pub fn is_expired(&self, now: Instant) -> bool {
+        !self.expanded && now.duration_since(self.shown_at) > TOAST_LIFETIME
+    }
+}
+
+#[derive(Default)]
+pub struct NotificationState {
+    pub toasts: RwLock<Vec<Toast>>,
+    pub next_toast_id: RwLock<u64>,
+}
+
+pub static NOTIFICATION_STATE: LazyLock<NotificationState> =
+    LazyLock::new(|| NotificationState::default());
+
+pub struct NotificationReducer<'a> {
+    pub state: &'a NotificationState,
+}
+
+impl<'a> NotificationReducer<'a> {
+    pub fn show(&self, notification: Notification) {
+        let mut toasts = self.state.toasts.write().unwrap();
+        if let Some(toast) = toasts.iter_mut().find(|toast| {
+            toast.notification.level == notification.level
+                && toast.notification.message == notification.message
+        }) {
+            toast.notification.detail = notification.detail;
+            toast.count += 1;
+            toast.shown_at = Instant::now();
+            return;
+        }
+        let id = {
+            let mut next_toast_id = self.state.next_toast_id.write().unwrap();
+            *next_toast_id += 1;
+            *next_toast_id
+        };
+        toasts.push(Toast {
+            id,
+            notification,
+            count: 1,
+            shown_at: Instant::now(),
+            expanded: false,
+        });
+        if toasts.len() > MAX_TOASTS {
+            toasts.remove(0);
+        }
+    }
+
+    pub fn dismiss(&self, id: u64) {
+        self.state
+            .toasts
+            .write()
+            .unwrap()
+            .retain(|toast| toast.id != id);
+    }
+
+    pub fn toggle_expanded(&self, id: u64) {
+        if let Some(toast) = self
+            .state
+            .toasts
+            .write()
+            .unwrap()
+            .iter_mut()
+            .find(|toast| toast.id == id)
+        {
+            toast.expanded = !toast.expanded;
+            toast.shown_at = Instant::now();
+        }
+    }
+
+    pub fn dismiss_expired(&self) {
+        let now = Instant::now();
+        self.state
+            .toasts
+            .write()
+            .unwrap()
+            .retain(|toast| !toast.is_expired(now));
+    }
+}
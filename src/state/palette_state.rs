@@ -0,0 +1,35 @@
+use crate::prisma;
+use std::sync::{Arc, LazyLock, RwLock};
+
+#[derive(Debug, Clone)]
+pub struct PaletteEntry {
+    pub script: prisma::shell_script::Data,
+    // (folder id, folder name) of every folder the script is linked to
+    pub folders: Vec<(i32, String)>,
+    pub tags: Vec<String>,
+    pub run_count: usize,
+    pub last_started_at: Option<f64>,
+}
+
+#[derive(Default)]
+pub struct PaletteState {
+    pub open: RwLock<bool>,
+    pub entries: RwLock<Arc<Vec<PaletteEntry>>>,
+}
+
+pub static PALETTE_STATE: LazyLock<PaletteState> = LazyLock::new(|| PaletteState::default());
+
+pub struct PaletteReducer<'a> {
+    pub state: &'a PaletteState,
+}
+
+impl<'a> PaletteReducer<'a> {
+    pub fn open_palette(&self, entries: Vec<PaletteEntry>) {
+        *self.state.entries.write().unwrap() = Arc::new(entries);
+        *self.state.open.write().unwrap() = true;
+    }
+
+    pub fn close_palette(&self) {
+        *self.state.open.write().unwrap() = false;
+    }
+}